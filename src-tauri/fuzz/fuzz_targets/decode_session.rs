@@ -0,0 +1,44 @@
+//! 会话解码器的模糊测试
+//! 主 crate 是 Tauri 可执行程序，这里直接引入解码相关的源码文件，
+//! 覆盖 Base64 + prost 解码、SessionResponse 转 JSON 以及无 schema 的线格式解析。
+//!
+//! 运行：`cd src-tauri/fuzz && cargo +nightly fuzz run decode_session -- -max_len=65536`
+
+#![no_main]
+#![allow(dead_code)]
+
+use base64::Engine;
+use libfuzzer_sys::fuzz_target;
+
+mod proto {
+    include!("../../src/proto_gen/google.internal.antigravity.rs");
+}
+
+#[path = "../../src/utils/protobuf_wire.rs"]
+mod protobuf_wire;
+
+mod utils {
+    pub(crate) use super::protobuf_wire;
+}
+
+#[path = "../../src/antigravity/account.rs"]
+mod account;
+
+fuzz_target!(|data: &[u8]| {
+    // 原始字节按数据库中的格式编码后走完整的解码路径
+    let b64 = base64::engine::general_purpose::STANDARD.encode(data);
+    if let Ok(msg) = account::decode_session_response(&b64) {
+        account::session_response_to_json(&msg);
+    }
+    let _ = account::inspect_jetski_state_wire(&b64);
+
+    // 未修改的字段必须按原样写回
+    if let Ok(raw) = protobuf_wire::RawMessage::decode(data) {
+        assert_eq!(raw.encode(), data);
+    }
+
+    // 输入本身也可能是损坏的 Base64 文本
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = account::decode_jetski_state_proto(text);
+    }
+});
@@ -2,34 +2,65 @@ use base64::Engine;
 use prost::Message;
 use serde_json::Value;
 
+/// agentManagerInitState（Base64）的最大长度；数据来自数据库或导入文件，超长内容直接拒绝，
+/// 避免解码时按攻击者给出的大小分配内存
+pub const MAX_AGENT_STATE_LEN: usize = 64 * 1024 * 1024;
+
 /// 将 jetskiStateSync.agentManagerInitState 作为 SessionResponse proto 解码
 pub fn decode_jetski_state_proto(b64: &str) -> Result<Value, String> {
+    let msg = decode_session_response(b64)?;
+    Ok(session_response_to_json(&msg))
+}
+
+/// 解码 jetskiStateSync.agentManagerInitState（base64 + proto），返回原始 SessionResponse
+pub fn decode_session_response(b64: &str) -> Result<crate::proto::SessionResponse, String> {
     if b64.trim().is_empty() {
         return Err("jetskiStateSync.agentManagerInitState 为空".to_string());
     }
 
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(b64)
-        .map_err(|e| {
-            format!(
-                "jetskiStateSync.agentManagerInitState Base64 解码失败(len={}): {}",
-                b64.len(),
-                e
-            )
-        })?;
+    let bytes = decode_state_bytes(b64)?;
 
-    let msg = crate::proto::SessionResponse::decode(bytes.as_slice()).map_err(|e| {
+    crate::proto::SessionResponse::decode(bytes.as_slice()).map_err(|e| {
         format!(
             "jetskiStateSync.agentManagerInitState Protobuf 解码失败(len={}): {}",
             bytes.len(),
             e
         )
-    })?;
+    })
+}
 
-    Ok(session_response_to_json(&msg))
+/// Base64 解码 agentManagerInitState 得到原始 protobuf 字节
+pub fn decode_state_bytes(b64: &str) -> Result<Vec<u8>, String> {
+    if b64.len() > MAX_AGENT_STATE_LEN {
+        return Err(format!(
+            "jetskiStateSync.agentManagerInitState 过大(len={})，上限为 {} 字节",
+            b64.len(),
+            MAX_AGENT_STATE_LEN
+        ));
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(b64.trim())
+        .map_err(|e| {
+            format!(
+                "jetskiStateSync.agentManagerInitState Base64 解码失败(len={}): {}",
+                b64.len(),
+                e
+            )
+        })
 }
 
-fn session_response_to_json(msg: &crate::proto::SessionResponse) -> Value {
+/// 不依赖 proto 定义逐字段解析 agentManagerInitState，用于排查 SessionResponse 中未建模的字段
+pub fn inspect_jetski_state_wire(
+    b64: &str,
+) -> Result<Vec<crate::utils::protobuf_wire::WireField>, String> {
+    if b64.trim().is_empty() {
+        return Err("jetskiStateSync.agentManagerInitState 为空".to_string());
+    }
+    crate::utils::protobuf_wire::decode_message(&decode_state_bytes(b64)?)
+}
+
+/// SessionResponse 转为 JSON（未建模的字节字段以 `*_base64` 输出），供内部比较与调试使用
+pub fn session_response_to_json(msg: &crate::proto::SessionResponse) -> Value {
     use crate::proto::*;
 
     let b64 = |data: &Vec<u8>| {
@@ -126,3 +157,141 @@ fn session_response_to_json(msg: &crate::proto::SessionResponse) -> Value {
         "context": context,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::{AuthInfo, HistoryEntry, HistoryWrapper, SessionResponse, UserContext};
+    use crate::utils::protobuf_wire::{RawMessage, WireValue};
+    use base64::engine::general_purpose::STANDARD;
+    use proptest::prelude::*;
+
+    const FULL_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/session_unknown_fields.b64"
+    ));
+
+    /// 对任意输入执行全部解码路径；只要求不 panic
+    fn decode_all(bytes: &[u8]) {
+        let b64 = STANDARD.encode(bytes);
+        if let Ok(msg) = decode_session_response(&b64) {
+            session_response_to_json(&msg);
+        }
+        let _ = inspect_jetski_state_wire(&b64);
+        if let Ok(raw) = RawMessage::decode(bytes) {
+            assert_eq!(raw.encode(), bytes);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_never_panic(bytes in prop::collection::vec(any::<u8>(), 0..4096)) {
+            decode_all(&bytes);
+        }
+
+        #[test]
+        fn arbitrary_text_never_panics(text in ".{0,512}") {
+            let _ = decode_jetski_state_proto(&text);
+            let _ = inspect_jetski_state_wire(&text);
+        }
+
+        #[test]
+        fn mutated_fixture_never_panics(
+            index in any::<prop::sample::Index>(),
+            byte in any::<u8>(),
+        ) {
+            let mut bytes = decode_state_bytes(FULL_FIXTURE).unwrap();
+            let i = index.index(bytes.len());
+            bytes[i] = byte;
+            decode_all(&bytes);
+        }
+
+        #[test]
+        fn encoded_sessions_round_trip(
+            email in ".{0,64}",
+            access_token in ".{0,64}",
+            session_ids in prop::collection::vec(".{0,16}", 0..64),
+            f7 in prop::collection::vec(any::<u8>(), 0..64),
+        ) {
+            let msg = SessionResponse {
+                history: Some(HistoryWrapper {
+                    items: session_ids
+                        .iter()
+                        .map(|id| HistoryEntry {
+                            session_id: id.clone(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                }),
+                auth: Some(AuthInfo {
+                    access_token: access_token.clone(),
+                    ..Default::default()
+                }),
+                f7,
+                context: Some(UserContext {
+                    email: email.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            let json = decode_jetski_state_proto(&STANDARD.encode(msg.encode_to_vec())).unwrap();
+            prop_assert_eq!(json["context"]["email"].as_str(), Some(email.as_str()));
+            prop_assert_eq!(json["auth"]["access_token"].as_str(), Some(access_token.as_str()));
+            prop_assert_eq!(json["history"].as_array().map(Vec::len), Some(session_ids.len()));
+        }
+    }
+
+    #[test]
+    fn truncated_fixture_never_panics() {
+        let bytes = decode_state_bytes(FULL_FIXTURE).unwrap();
+        for len in 0..bytes.len() {
+            decode_all(&bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn oversized_length_prefix_is_rejected() {
+        // context（标签 19，长度前缀）声称长度接近 u64::MAX，但实际没有任何内容
+        let mut bytes = vec![0x9a, 0x01];
+        bytes.extend_from_slice(&[0xff; 9]);
+        bytes.push(0x01);
+        let b64 = STANDARD.encode(&bytes);
+        assert!(decode_session_response(&b64).is_err());
+        assert!(inspect_jetski_state_wire(&b64).is_err());
+        assert!(RawMessage::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn huge_repeated_field_is_bounded_by_input() {
+        let msg = SessionResponse {
+            history: Some(HistoryWrapper {
+                items: vec![HistoryEntry::default(); 100_000],
+            }),
+            ..Default::default()
+        };
+        let bytes = msg.encode_to_vec();
+        let decoded = decode_session_response(&STANDARD.encode(&bytes)).unwrap();
+        assert_eq!(decoded.history.map(|h| h.items.len()), Some(100_000));
+        decode_all(&bytes);
+    }
+
+    #[test]
+    fn invalid_utf8_in_string_field() {
+        // context { email(7) = 0xff 0xfe }
+        let bytes = [0x9a, 0x01, 0x04, 0x3a, 0x02, 0xff, 0xfe];
+        let b64 = STANDARD.encode(bytes);
+        assert!(decode_session_response(&b64).is_err());
+
+        let fields = inspect_jetski_state_wire(&b64).unwrap();
+        let WireValue::Message { fields: context } = &fields[0].value else {
+            panic!("context 应解析为嵌套消息: {:?}", fields[0].value);
+        };
+        assert!(matches!(context[0].value, WireValue::Bytes { .. }));
+    }
+
+    #[test]
+    fn oversized_state_is_rejected_before_decoding() {
+        let b64 = "A".repeat(MAX_AGENT_STATE_LEN + 4);
+        assert!(decode_state_bytes(&b64).is_err());
+    }
+}
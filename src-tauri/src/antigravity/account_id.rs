@@ -0,0 +1,259 @@
+//! 账户标识
+//! 所有按账户定位备份文件的地方都必须先得到一个经过校验的 AccountId，
+//! 禁止把调用方传入的字符串直接拼接到账户目录下
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 账户标识的最大长度（与邮箱地址上限一致）
+const MAX_ACCOUNT_ID_LEN: usize = 254;
+
+/// 经过校验的账户标识（由邮箱派生，见 [`AccountId::from_email`]）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(try_from = "String", into = "String")]
+#[specta(transparent)]
+pub struct AccountId(String);
+
+impl AccountId {
+    /// 校验并构建账户标识
+    ///
+    /// 只允许邮箱中常见的字符，不能以 `.` 开头，也不能包含 `..`，
+    /// 因此拼接到任何目录下都只会得到该目录中的一个文件
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+
+        if raw.is_empty() {
+            return Err("账户标识不能为空".to_string());
+        }
+        if raw.len() > MAX_ACCOUNT_ID_LEN {
+            return Err(format!("账户标识过长（{} 字符）", raw.len()));
+        }
+        if raw.starts_with('.') || raw.contains("..") {
+            return Err(format!("非法的账户标识: {}", raw));
+        }
+        if let Some(c) = raw.chars().find(|c| !is_id_char(*c)) {
+            return Err(format!("账户标识包含非法字符 {:?}: {}", c, raw));
+        }
+
+        Ok(Self(raw.to_string()))
+    }
+
+    /// 由邮箱派生
+    ///
+    /// 标识字符集之外的字符（如 `'`、空白、非 ASCII 字符）以及 `%` 本身按 UTF-8 字节百分号编码，
+    /// 开头的 `.` 和紧跟在 `.` 之后的 `.` 也会被编码，因此任何合法邮箱都能得到唯一的标识
+    pub fn from_email(email: &str) -> Result<Self, String> {
+        let mut encoded = String::with_capacity(email.len());
+        let mut prev = None;
+        for c in email.trim().chars() {
+            let dot_needs_escape = c == '.' && matches!(prev, None | Some('.'));
+            if is_id_char(c) && c != '%' && !dot_needs_escape {
+                encoded.push(c);
+            } else {
+                let mut buf = [0u8; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    encoded.push_str(&format!("%{:02X}", byte));
+                }
+            }
+            prev = Some(c);
+        }
+        Self::parse(&encoded)
+    }
+
+    /// 由备份文件名（`{id}.json`）还原
+    pub fn from_file_name(file_name: &str) -> Result<Self, String> {
+        let stem = file_name
+            .strip_suffix(".json")
+            .ok_or_else(|| format!("不是账户备份文件: {}", file_name))?;
+        Self::parse(stem)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 备份文件名
+    pub fn file_name(&self) -> String {
+        format!("{}.json", self.0)
+    }
+
+    /// 在指定目录下定位该账户的备份文件
+    ///
+    /// 标识中不含路径分隔符，结果总是该目录下的一个文件名；该位置是符号链接时一律拒绝
+    /// （包括指向目录外或已失效的链接），避免随后的写入跟随链接落到目录外
+    pub fn resolve_in(&self, dir: &Path) -> Result<PathBuf, String> {
+        let path = dir.join(self.file_name());
+
+        match fs::symlink_metadata(&path) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                Err(format!("账户文件是符号链接，已拒绝: {}", self.0))
+            }
+            Ok(_) => Ok(path),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(path),
+            Err(e) => Err(format!("读取账户文件信息失败 {}: {}", path.display(), e)),
+        }
+    }
+
+    /// 当前账户备份文件路径（antigravity-accounts/{id}.json）
+    pub fn backup_path(&self) -> Result<PathBuf, String> {
+        self.resolve_in(&crate::directories::get_accounts_directory())
+    }
+}
+
+/// 账户标识允许的字符
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '-' | '+' | '%')
+}
+
+impl fmt::Display for AccountId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for AccountId {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<AccountId> for String {
+    fn from(id: AccountId) -> Self {
+        id.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn parse_accepts_email_addresses() {
+        for raw in [
+            "user@example.com",
+            "first.last+tag@mail.example.org",
+            "a_b-c%d@x.io",
+        ] {
+            assert_eq!(AccountId::parse(raw).unwrap().as_str(), raw);
+        }
+        assert_eq!(
+            AccountId::parse("  user@example.com\n").unwrap().as_str(),
+            "user@example.com"
+        );
+        assert_eq!(
+            AccountId::from_file_name("user@example.com.json")
+                .unwrap()
+                .as_str(),
+            "user@example.com"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_path_tricks() {
+        let overlong = format!("{}@example.com", "a".repeat(MAX_ACCOUNT_ID_LEN));
+        let rejected = [
+            "",
+            "   ",
+            ".",
+            "..",
+            "../user@example.com",
+            "user@example..com",
+            ".hidden@example.com",
+            "dir/user@example.com",
+            "dir\\user@example.com",
+            "/etc/passwd",
+            "C:user@example.com",
+            "user\0@example.com",
+            "user @example.com",
+            overlong.as_str(),
+            // Unicode 相似字符：全角句点、斜杠及西里尔字母
+            "user@example\u{ff0e}com",
+            "user\u{2215}x@example.com",
+            "\u{0430}dmin@example.com",
+        ];
+        for raw in rejected {
+            assert!(AccountId::parse(raw).is_err(), "应拒绝 {:?}", raw);
+        }
+        assert!(AccountId::from_file_name("user@example.com").is_err());
+        assert!(AccountId::from_file_name("../user@example.com.json").is_err());
+    }
+
+    #[test]
+    fn from_email_encodes_instead_of_rejecting() {
+        let id = |email: &str| AccountId::from_email(email).unwrap().as_str().to_string();
+
+        assert_eq!(id("user@example.com"), "user@example.com");
+        assert_eq!(id("o'brien@example.com"), "o%27brien@example.com");
+        assert_eq!(
+            id("\"john doe\"@example.com"),
+            "%22john%20doe%22@example.com"
+        );
+        assert_eq!(id("用户@example.com"), "%E7%94%A8%E6%88%B7@example.com");
+        // `%` 本身也编码，保证不同邮箱不会得到相同的标识
+        assert_eq!(id("a%27b@example.com"), "a%2527b@example.com");
+        assert_ne!(id("a%27b@example.com"), id("a'b@example.com"));
+        // 路径分隔符、开头的点和连续的点都被编码
+        assert_eq!(id(".user@example.com"), "%2Euser@example.com");
+        assert_eq!(id("a..b@example.com"), "a.%2Eb@example.com");
+        assert_eq!(id("../../etc/passwd"), "%2E%2E%2F.%2E%2Fetc%2Fpasswd");
+        assert_eq!(id("a\\b\0@example.com"), "a%5Cb%00@example.com");
+
+        for email in [
+            "o'brien@example.com",
+            "../../x",
+            "a...b@example.com",
+            "用户@例子.com",
+        ] {
+            let encoded = AccountId::from_email(email).unwrap();
+            assert!(AccountId::parse(encoded.as_str()).is_ok(), "{}", encoded);
+            assert!(!encoded.as_str().contains(".."));
+        }
+
+        assert!(AccountId::from_email("").is_err());
+        assert!(AccountId::from_email(&"'".repeat(MAX_ACCOUNT_ID_LEN)).is_err());
+    }
+
+    #[test]
+    fn resolve_in_stays_inside_directory() {
+        let root =
+            std::env::temp_dir().join(format!("antigravity-account-id-{}", std::process::id()));
+        let dir = root.join("accounts");
+        fs::create_dir_all(&dir).unwrap();
+
+        let id = AccountId::parse("user@example.com").unwrap();
+        let path = id.resolve_in(&dir).unwrap();
+        assert_eq!(path.parent(), Some(dir.as_path()));
+        assert_eq!(path.file_name().unwrap(), "user@example.com.json");
+
+        // 已存在的文件按真实路径确认
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(id.resolve_in(&dir).unwrap(), path);
+
+        // 符号链接一律拒绝：指向目录外、已失效或指向目录内的都不例外
+        #[cfg(unix)]
+        {
+            let outside = root.join("outside.json");
+            fs::write(&outside, "{}").unwrap();
+            let targets = [
+                ("outside@example.com", outside.clone()),
+                ("dangling@example.com", root.join("missing.json")),
+                ("inside@example.com", path.clone()),
+            ];
+            for (email, target) in targets {
+                let link_id = AccountId::parse(email).unwrap();
+                std::os::unix::fs::symlink(&target, dir.join(link_id.file_name())).unwrap();
+                assert!(link_id.resolve_in(&dir).is_err(), "{}", email);
+            }
+            assert!(!root.join("missing.json").exists());
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
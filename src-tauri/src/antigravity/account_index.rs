@@ -0,0 +1,244 @@
+//! 账户索引
+//! 缓存每个备份文件的解码结果，按文件修改时间/大小判断是否需要重新读取，
+//! 内容哈希未变时跳过 protobuf 解码，账户很多时列表也能立即返回
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::antigravity::account::{decode_session_response, session_response_to_json};
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::account_view::AccountProfile;
+use crate::antigravity::backup::{load_backup_file, AccountBackup};
+use crate::antigravity::quarantine::{self, AccountFileIssue};
+use crate::antigravity::vault;
+use crate::state::AntigravityAccount;
+
+/// 一次同步的统计结果
+#[derive(Debug, Default, Clone)]
+pub struct RefreshStats {
+    /// 重新解码的文件数
+    pub decoded: usize,
+    /// 修改时间变化但内容哈希相同、只更新了缓存键的文件数
+    pub touched: usize,
+    /// 已从索引中移除的账户数
+    pub removed: usize,
+    /// 账户集合是否发生变化（新增或删除）
+    pub membership_changed: bool,
+    /// 本次发现的问题文件（已隔离的和仅跳过的）
+    pub issues: Vec<AccountFileIssue>,
+}
+
+/// 账户索引（键为账户标识）
+#[derive(Default)]
+pub struct AccountIndex {
+    entries: HashMap<AccountId, AntigravityAccount>,
+}
+
+// 索引中包含访问令牌，Debug 输出只保留数量，避免被 #[instrument] 写进日志
+impl fmt::Debug for AccountIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountIndex")
+            .field("accounts", &self.entries.len())
+            .finish()
+    }
+}
+
+impl AccountIndex {
+    /// 与账户目录同步：新增或变化的文件重新读取，已删除的文件移出索引；
+    /// 单个文件确认损坏（见 [`quarantine::is_corrupt`]）时将其隔离并记录问题，不影响其余账户。
+    /// 暂时无法读取的文件（读取失败、格式版本较新等）只跳过并保留已缓存的条目，下次同步时重试；
+    /// 文件名无效的文件只报告问题，不移动
+    pub fn refresh(&mut self, dir: &Path) -> Result<RefreshStats, String> {
+        let mut stats = RefreshStats::default();
+
+        // 保险库锁定时无法读取任何文件，不能把它们当成损坏文件隔离
+        vault::ensure_readable()?;
+
+        if !dir.exists() {
+            stats.removed = self.entries.len();
+            stats.membership_changed = !self.entries.is_empty();
+            self.entries.clear();
+            return Ok(stats);
+        }
+
+        let mut seen = HashSet::new();
+        let entries = fs::read_dir(dir).map_err(|e| format!("读取备份目录失败: {}", e))?;
+
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+            let path = entry.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            // 文件名不合法不代表内容损坏，只报告问题，不移动文件
+            let id = match AccountId::from_file_name(&file_name) {
+                Ok(id) => id,
+                Err(e) => {
+                    tracing::warn!(target: "account::index", file = %file_name, error = %e, "跳过文件名无效的备份");
+                    stats.issues.push(AccountFileIssue {
+                        file_name,
+                        reason: e,
+                        quarantined_as: None,
+                    });
+                    continue;
+                }
+            };
+
+            let (modified, len) = match file_stamp(&path) {
+                Ok(stamp) => stamp,
+                Err(e) => {
+                    // 文件信息都读不到时无法移动，只报告问题并保留已缓存的条目
+                    tracing::warn!(target: "account::index", account = %id, error = %e, "跳过无法访问的备份");
+                    seen.insert(id);
+                    stats.issues.push(AccountFileIssue {
+                        file_name,
+                        reason: e,
+                        quarantined_as: None,
+                    });
+                    continue;
+                }
+            };
+            if let Some(cached) = self.entries.get(&id) {
+                if cached.file_modified == modified && cached.file_len == len {
+                    seen.insert(id);
+                    continue;
+                }
+            }
+
+            tracing::debug!(target: "account::index", account = %id, "📄 正在解析备份文件");
+
+            match self.load_entry(&id, &path) {
+                Ok(Loaded::Touched) => stats.touched += 1,
+                Ok(Loaded::Decoded { is_new }) => {
+                    stats.decoded += 1;
+                    stats.membership_changed |= is_new;
+                }
+                Err(e) if quarantine::is_corrupt(&path) => {
+                    stats.issues.push(quarantine::report_issue(&path, e));
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(target: "account::index", account = %id, error = %e, "跳过暂时无法读取的备份");
+                    stats.issues.push(AccountFileIssue {
+                        file_name,
+                        reason: e,
+                        quarantined_as: None,
+                    });
+                }
+            }
+            seen.insert(id);
+        }
+
+        let before = self.entries.len();
+        self.entries.retain(|id, _| seen.contains(id));
+        stats.removed = before - self.entries.len();
+        if stats.removed > 0 {
+            stats.membership_changed = true;
+        }
+
+        Ok(stats)
+    }
+
+    /// 读取单个备份文件并更新索引条目；内容哈希未变时跳过解码
+    fn load_entry(&mut self, id: &AccountId, path: &Path) -> Result<Loaded, String> {
+        // 读取备份信封（旧版单键文件会被自动升级，升级后重新取一次文件时间）
+        let backup: AccountBackup = load_backup_file(path)?;
+        let (modified, len) = file_stamp(path)?;
+
+        if let Some(cached) = self.entries.get_mut(id) {
+            if cached.content_hash == backup.content_hash {
+                cached.file_modified = modified;
+                cached.file_len = len;
+                cached.saved_at = backup.saved_at;
+                cached.metadata = backup.metadata;
+                return Ok(Loaded::Touched);
+            }
+        }
+
+        let msg = decode_session_response(&backup.agent_state)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let profile = AccountProfile::from_session(&msg);
+        if profile.token_email_mismatch() {
+            tracing::warn!(target: "account::index", account = %id, "id_token 中的邮箱与会话邮箱不一致");
+        }
+        let previous = self.entries.insert(
+            id.clone(),
+            AntigravityAccount {
+                id: id.clone(),
+                metadata: backup.metadata,
+                saved_at: backup.saved_at,
+                content_hash: backup.content_hash,
+                session: session_response_to_json(&msg),
+                profile,
+                file_modified: modified,
+                file_len: len,
+            },
+        );
+        Ok(Loaded::Decoded {
+            is_new: previous.is_none(),
+        })
+    }
+
+    /// 所有账户，按保存时间排序（最新的在前）
+    pub fn accounts(&self) -> Vec<&AntigravityAccount> {
+        let mut accounts: Vec<&AntigravityAccount> = self.entries.values().collect();
+        accounts.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(a.id.cmp(&b.id)));
+        accounts
+    }
+
+    /// id_token `sub` 相同的账户（邮箱变更后同一账户可能以不同邮箱保存了多份）
+    pub fn by_subject(&self, subject: &str) -> Vec<&AntigravityAccount> {
+        self.accounts()
+            .into_iter()
+            .filter(|account| account.profile.subject() == Some(subject))
+            .collect()
+    }
+
+    /// 所有账户标识（与 accounts() 顺序一致）
+    pub fn ids(&self) -> Vec<AccountId> {
+        self.accounts().into_iter().map(|a| a.id.clone()).collect()
+    }
+
+    pub fn get(&self, id: &AccountId) -> Option<&AntigravityAccount> {
+        self.entries.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// 单个文件的加载结果
+enum Loaded {
+    /// 内容未变，只更新了缓存键
+    Touched,
+    /// 重新解码；is_new 表示此前不在索引中
+    Decoded { is_new: bool },
+}
+
+/// 文件的缓存键：修改时间 + 大小
+fn file_stamp(path: &Path) -> Result<(SystemTime, u64), String> {
+    let meta =
+        fs::metadata(path).map_err(|e| format!("读取文件信息失败 {}: {}", path.display(), e))?;
+    Ok((
+        meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+        meta.len(),
+    ))
+}
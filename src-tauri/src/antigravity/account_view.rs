@@ -0,0 +1,216 @@
+//! 账户视图模型
+//! 命令返回给前端的账户数据只由这里的强类型结构组成（TypeScript 类型由 specta 生成），
+//! 不再直接暴露解码后的 SessionResponse JSON，前端因此不依赖 `f7_base64` 之类未建模的字段
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use specta::Type;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::expiry::ExpiryInfo;
+use crate::antigravity::id_token::{decode_id_token, IdTokenClaims};
+use crate::proto::SessionResponse;
+
+/// 套餐信息
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPlan {
+    pub slug: String,
+    pub name: String,
+    pub description: String,
+    pub upgrade_url: String,
+    pub upgrade_msg: String,
+}
+
+/// 可用模型
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountModels {
+    /// 账户可用的模型名称（保持会话中的顺序）
+    pub available: Vec<String>,
+    /// Antigravity 推荐的模型名称
+    pub recommended: Vec<String>,
+}
+
+/// 调用 Cloud Code API 所需的凭据
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAuth {
+    pub access_token: String,
+    pub id_token: String,
+    pub token_type: String,
+    /// 从 id_token 本地解码出的声明（未校验签名，无法解码时为空）
+    pub id_token_claims: Option<IdTokenClaims>,
+}
+
+/// SessionResponse 中视图模型需要的字段（账户索引按内容哈希缓存）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountProfile {
+    pub email: String,
+    /// 套餐显示名称，套餐配置中没有时取 UserContext.plan_name
+    pub plan_name: String,
+    pub plan: Option<AccountPlan>,
+    pub models: AccountModels,
+    pub auth: AccountAuth,
+    /// 令牌过期时间（Unix 秒）
+    pub expiry_timestamp: Option<i64>,
+    pub user_status: i32,
+    pub history_count: usize,
+}
+
+impl AccountProfile {
+    pub fn from_session(msg: &SessionResponse) -> Self {
+        let context = msg.context.as_ref();
+        let plan = context.and_then(|c| c.plan.as_ref()).map(|p| AccountPlan {
+            slug: p.slug.clone(),
+            name: p.name.clone(),
+            description: p.description.clone(),
+            upgrade_url: p.upgrade_url.clone(),
+            upgrade_msg: p.upgrade_msg.clone(),
+        });
+        let plan_name = plan
+            .as_ref()
+            .map(|p| p.name.clone())
+            .filter(|name| !name.is_empty())
+            .or_else(|| context.map(|c| c.plan_name.clone()))
+            .unwrap_or_default();
+
+        let models = context
+            .and_then(|c| c.models.as_ref())
+            .map(|m| AccountModels {
+                available: m.items.iter().map(|item| item.name.clone()).collect(),
+                recommended: m
+                    .recommended
+                    .as_ref()
+                    .map(|r| r.names.clone())
+                    .unwrap_or_default(),
+            })
+            .unwrap_or_default();
+
+        let auth = msg.auth.as_ref();
+        Self {
+            email: context.map(|c| c.email.clone()).unwrap_or_default(),
+            plan_name,
+            plan,
+            models,
+            auth: auth
+                .map(|a| AccountAuth {
+                    access_token: a.access_token.clone(),
+                    id_token: a.id_token.clone(),
+                    token_type: a.r#type.clone(),
+                    id_token_claims: decode_id_token(&a.id_token).ok(),
+                })
+                .unwrap_or_default(),
+            expiry_timestamp: auth
+                .and_then(|a| a.meta.as_ref())
+                .map(|m| m.expiry_timestamp)
+                .filter(|ts| *ts > 0),
+            user_status: context.map(|c| c.status).unwrap_or_default(),
+            history_count: msg.history.as_ref().map_or(0, |h| h.items.len()),
+        }
+    }
+
+    /// id_token 中的账户稳定标识
+    pub fn subject(&self) -> Option<&str> {
+        self.auth.id_token_claims.as_ref()?.sub.as_deref()
+    }
+
+    /// id_token 中的邮箱与 UserContext.email 不一致
+    pub fn token_email_mismatch(&self) -> bool {
+        self.auth
+            .id_token_claims
+            .as_ref()
+            .is_some_and(|claims| claims.email_mismatch(&self.email))
+    }
+}
+
+/// 账户列表中的一项（已保存的账户）
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub id: AccountId,
+    pub email: String,
+    pub plan_name: String,
+    pub plan: Option<AccountPlan>,
+    pub models: AccountModels,
+    pub auth: AccountAuth,
+    pub expiry: ExpiryInfo,
+    /// id_token 中的邮箱与会话邮箱不一致
+    pub token_email_mismatch: bool,
+    /// 是否为 Antigravity 当前正在使用的账户
+    pub active: bool,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl AccountSummary {
+    pub fn new(
+        id: AccountId,
+        profile: &AccountProfile,
+        expiry: ExpiryInfo,
+        active: bool,
+        saved_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id,
+            email: profile.email.clone(),
+            plan_name: profile.plan_name.clone(),
+            plan: profile.plan.clone(),
+            models: profile.models.clone(),
+            auth: profile.auth.clone(),
+            expiry,
+            token_email_mismatch: profile.token_email_mismatch(),
+            active,
+            saved_at,
+        }
+    }
+}
+
+/// Antigravity 当前登录账户的详情（来自实时数据库）
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDetail {
+    /// 保存时使用的账户标识
+    pub id: AccountId,
+    pub email: String,
+    pub plan_name: String,
+    pub plan: Option<AccountPlan>,
+    pub models: AccountModels,
+    pub auth: AccountAuth,
+    pub expiry: ExpiryInfo,
+    /// id_token 中的邮箱与会话邮箱不一致
+    pub token_email_mismatch: bool,
+    /// 已保存且备份内容与实时会话一致（未保存或备份已过时为 false）
+    pub active: bool,
+    /// 已保存备份的保存时间（尚未保存时为空）
+    pub saved_at: Option<DateTime<Utc>>,
+    /// UserContext.status
+    pub user_status: i32,
+    /// 会话中的历史记录条数
+    pub history_count: usize,
+}
+
+impl AccountDetail {
+    pub fn new(
+        id: AccountId,
+        profile: AccountProfile,
+        expiry: ExpiryInfo,
+        active: bool,
+        saved_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let token_email_mismatch = profile.token_email_mismatch();
+        Self {
+            id,
+            email: profile.email,
+            plan_name: profile.plan_name,
+            plan: profile.plan,
+            models: profile.models,
+            auth: profile.auth,
+            expiry,
+            token_email_mismatch,
+            active,
+            saved_at,
+            user_status: profile.user_status,
+            history_count: profile.history_count,
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! 当前活动账户识别
+//! 解码 Antigravity 正在使用的 state.vscdb 会话，与已保存的账户逐一比对，
+//! 得出当前使用的是哪个账户，以及保存的副本是否已落后于实时数据
+
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::antigravity::account::decode_jetski_state_proto;
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::account_index::AccountIndex;
+use crate::antigravity::backup::content_hash;
+use crate::antigravity::id_token::decode_id_token;
+use crate::antigravity::state_store::{SqliteStateStore, StateStore};
+use crate::constants::database;
+use crate::state::AntigravityAccount;
+
+/// 匹配依据（按可信度从高到低，声明顺序即优先级）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchKind {
+    /// id_token 的 `sub` 一致（邮箱变更后仍能匹配）
+    Subject,
+    /// 邮箱与 user_id_raw 均一致
+    UserId,
+    /// 邮箱一致且访问令牌一致
+    AccessToken,
+    /// 仅邮箱一致
+    Email,
+}
+
+/// 活动账户识别结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveAccountStatus {
+    /// 匹配到的已保存账户（未登录或未保存时为空）
+    pub account_id: Option<AccountId>,
+    /// 实时会话中的邮箱（未登录时为空）
+    pub live_email: Option<String>,
+    pub matched_by: Option<MatchKind>,
+    /// 保存的副本与实时会话内容不一致
+    pub stale: bool,
+    /// 实时会话的访问令牌与保存的副本不同（令牌已轮换）
+    pub token_rotated: bool,
+}
+
+/// 活动账户变化时推送给前端的事件（`active-account-changed`）
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct ActiveAccountChanged(pub ActiveAccountStatus);
+
+/// 实时会话中用于匹配的字段
+struct SessionKeys<'a> {
+    email: Option<&'a str>,
+    user_id: Option<&'a str>,
+    access_token: Option<&'a str>,
+}
+
+impl<'a> SessionKeys<'a> {
+    fn from_session(session: &'a Value) -> Self {
+        let non_empty = |v: Option<&'a Value>| v.and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+        Self {
+            email: non_empty(session.pointer("/context/email")),
+            user_id: non_empty(session.get("user_id_raw_base64")),
+            access_token: non_empty(session.pointer("/auth/access_token")),
+        }
+    }
+}
+
+/// 获取实时 state.vscdb 路径
+pub fn live_db_path() -> Result<PathBuf, String> {
+    crate::platform::get_antigravity_db_path()
+        .or_else(|| {
+            crate::platform::get_all_antigravity_db_paths()
+                .into_iter()
+                .next()
+        })
+        .ok_or_else(|| "未找到Antigravity安装位置".to_string())
+}
+
+/// 以只读方式读取实时的 agentManagerInitState（数据库或键不存在时返回 None）
+pub fn read_live_agent_state() -> Result<Option<String>, String> {
+    let db_path = live_db_path()?;
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    read_agent_state(&db_path)
+}
+
+/// 以只读方式从指定 state.vscdb 读取 agentManagerInitState（键不存在时返回 None）
+pub fn read_agent_state(db_path: &Path) -> Result<Option<String>, String> {
+    SqliteStateStore::open_read_only(db_path)?.get(database::AGENT_STATE)
+}
+
+/// 将实时会话与已保存账户比对
+pub fn match_active_account(
+    index: &AccountIndex,
+    live_state: &str,
+) -> Result<ActiveAccountStatus, String> {
+    match_accounts(index.accounts(), live_state)
+}
+
+/// 在给定账户（按索引顺序）中找出与实时会话最匹配的一个
+///
+/// 邮箱变更后同一 `sub` 可能以多个邮箱保存，此时优先选择邮箱一致的，再依次比较 user_id 和访问令牌
+fn match_accounts(
+    accounts: Vec<&AntigravityAccount>,
+    live_state: &str,
+) -> Result<ActiveAccountStatus, String> {
+    let live_session = decode_jetski_state_proto(live_state)?;
+    let live = SessionKeys::from_session(&live_session);
+    let live_subject = live_session
+        .pointer("/auth/id_token")
+        .and_then(|v| v.as_str())
+        .and_then(|token| decode_id_token(token).ok())
+        .and_then(|claims| claims.sub);
+
+    let Some(live_email) = live.email else {
+        // 没有邮箱视为未登录
+        return Ok(ActiveAccountStatus::default());
+    };
+
+    // 返回匹配依据及同一依据下的排序键（越小越优先）
+    let score = |account: &AntigravityAccount| -> Option<(MatchKind, [bool; 3])> {
+        let saved = SessionKeys::from_session(&account.session);
+        let same_email = saved
+            .email
+            .is_some_and(|email| email.eq_ignore_ascii_case(live_email));
+        let same_user_id = live.user_id.is_some() && saved.user_id == live.user_id;
+        let same_token = live.access_token.is_some() && saved.access_token == live.access_token;
+        let tie_break = [!same_email, !same_user_id, !same_token];
+
+        let kind = if live_subject.is_some() && account.profile.subject() == live_subject.as_deref()
+        {
+            MatchKind::Subject
+        } else if !same_email {
+            return None;
+        } else if same_user_id {
+            MatchKind::UserId
+        } else if same_token {
+            MatchKind::AccessToken
+        } else {
+            MatchKind::Email
+        };
+        Some((kind, tie_break))
+    };
+
+    let best = accounts
+        .into_iter()
+        .filter_map(|account| score(account).map(|key| (key, account)))
+        .min_by_key(|(key, _)| *key);
+
+    let Some(((kind, _), account)) = best else {
+        return Ok(ActiveAccountStatus {
+            live_email: Some(live_email.to_string()),
+            ..Default::default()
+        });
+    };
+
+    let saved = SessionKeys::from_session(&account.session);
+    Ok(ActiveAccountStatus {
+        account_id: Some(account.id.clone()),
+        live_email: Some(live_email.to_string()),
+        matched_by: Some(kind),
+        stale: account.content_hash != content_hash(live_state),
+        token_rotated: saved.access_token != live.access_token,
+    })
+}
+
+/// 重新识别活动账户：同步账户索引、更新 AppState；活动账户变化或保存的副本过期时推送事件
+pub fn refresh_active_account(app: &AppHandle) -> Result<ActiveAccountStatus, String> {
+    let state = app.state::<crate::AppState>();
+
+    let status = match read_live_agent_state()? {
+        Some(live_state) => {
+            let mut index = state.antigravity_accounts.lock().unwrap();
+            index.refresh(&state.accounts_dir())?;
+            match_active_account(&index, &live_state)?
+        }
+        None => ActiveAccountStatus::default(),
+    };
+
+    let changed = {
+        let mut current = state.current_account_id.lock().unwrap();
+        let changed = *current != status.account_id;
+        current.clone_from(&status.account_id);
+        changed
+    };
+
+    if changed || status.stale {
+        tracing::info!(
+            target: "account::active",
+            account = ?status.account_id.as_ref().map(|id| id.as_str()),
+            matched_by = ?status.matched_by,
+            stale = status.stale,
+            "活动账户状态已更新"
+        );
+        if let Err(e) = ActiveAccountChanged(status.clone()).emit(app) {
+            tracing::error!(target: "account::active", error = %e, "推送活动账户事件失败");
+        }
+    }
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::account::session_response_to_json;
+    use crate::antigravity::account_view::AccountProfile;
+    use crate::antigravity::backup::BackupMetadata;
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+    use base64::Engine;
+    use chrono::{TimeZone, Utc};
+    use prost::Message;
+    use std::time::SystemTime;
+
+    fn agent_state(email: &str, subject: &str, access_token: &str) -> String {
+        let id_token = format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.signature",
+            URL_SAFE_NO_PAD.encode(format!(r#"{{"sub":"{}"}}"#, subject))
+        );
+        let msg = crate::proto::SessionResponse {
+            auth: Some(crate::proto::AuthInfo {
+                access_token: access_token.to_string(),
+                id_token,
+                ..Default::default()
+            }),
+            context: Some(crate::proto::UserContext {
+                email: email.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        STANDARD.encode(msg.encode_to_vec())
+    }
+
+    fn saved_account(
+        email: &str,
+        subject: &str,
+        access_token: &str,
+        saved_secs: i64,
+    ) -> AntigravityAccount {
+        let state = agent_state(email, subject, access_token);
+        let msg = crate::antigravity::account::decode_session_response(&state).unwrap();
+        AntigravityAccount {
+            id: AccountId::from_email(email).unwrap(),
+            metadata: BackupMetadata {
+                email: email.to_string(),
+                plan_name: None,
+                plan_slug: None,
+                expiry_timestamp: None,
+            },
+            saved_at: Utc.timestamp_opt(saved_secs, 0).unwrap(),
+            content_hash: content_hash(&state),
+            session: session_response_to_json(&msg),
+            profile: AccountProfile::from_session(&msg),
+            file_modified: SystemTime::UNIX_EPOCH,
+            file_len: 0,
+        }
+    }
+
+    #[test]
+    fn subject_aliases_prefer_the_live_email() {
+        // 邮箱变更前后各保存了一份，旧邮箱的副本保存得更晚
+        let renamed = saved_account("new@example.com", "sub-1", "token-a", 1_000);
+        let previous = saved_account("old@example.com", "sub-1", "token-b", 2_000);
+        let other = saved_account("other@example.com", "sub-2", "token-c", 3_000);
+
+        let live = agent_state("new@example.com", "sub-1", "token-b");
+        let status = match_accounts(vec![&other, &previous, &renamed], &live).unwrap();
+        assert_eq!(status.account_id, Some(renamed.id.clone()));
+        assert_eq!(status.matched_by, Some(MatchKind::Subject));
+
+        // 没有邮箱一致的副本时仍按 sub 匹配，并优先选择令牌一致的
+        let live = agent_state("newest@example.com", "sub-1", "token-b");
+        let status = match_accounts(vec![&renamed, &previous], &live).unwrap();
+        assert_eq!(status.account_id, Some(previous.id.clone()));
+    }
+}
@@ -0,0 +1,209 @@
+//! 备份审计记录
+//! 后台自动改写备份文件的操作都追加到 backup_audit.jsonl（每行一条 JSON），
+//! 用户可以追溯某个备份是何时、因何被更新的。
+//! 文件超过上限后轮转为 backup_audit.jsonl.1（只保留一份），读取时从文件末尾倒序扫描
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// 审计文件的大小上限，超过后轮转
+const MAX_AUDIT_FILE_LEN: u64 = 1024 * 1024;
+
+/// 倒序读取时每次读入的字节数
+const READ_CHUNK_LEN: u64 = 64 * 1024;
+
+/// 单条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub at: DateTime<Utc>,
+    /// 操作类型，例如 `auto_refresh`
+    pub action: String,
+    pub account: String,
+    pub previous_hash: Option<String>,
+    pub new_hash: String,
+    pub previous_expiry: Option<i64>,
+    pub new_expiry: Option<i64>,
+}
+
+/// 追加一条审计记录
+pub fn append(entry: &AuditEntry) -> Result<(), String> {
+    append_to(
+        &crate::directories::get_backup_audit_file(),
+        entry,
+        MAX_AUDIT_FILE_LEN,
+    )
+}
+
+/// 读取最近的审计记录（最新的在前），无法解析的行会被跳过
+pub fn read_recent(limit: usize) -> Result<Vec<AuditEntry>, String> {
+    read_recent_from(&crate::directories::get_backup_audit_file(), limit)
+}
+
+/// 轮转后的旧审计文件
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+fn append_to(path: &Path, entry: &AuditEntry, max_len: u64) -> Result<(), String> {
+    let line = serde_json::to_string(entry).map_err(|e| format!("序列化审计记录失败: {}", e))?;
+
+    if fs::metadata(path).is_ok_and(|m| m.len() >= max_len) {
+        fs::rename(path, rotated_path(path))
+            .map_err(|e| format!("轮转审计文件失败 {}: {}", path.display(), e))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("打开审计文件失败 {}: {}", path.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("写入审计记录失败: {}", e))
+}
+
+fn read_recent_from(path: &Path, limit: usize) -> Result<Vec<AuditEntry>, String> {
+    let mut entries = read_tail(path, limit, READ_CHUNK_LEN)?;
+    if entries.len() < limit {
+        let remaining = limit - entries.len();
+        entries.extend(read_tail(&rotated_path(path), remaining, READ_CHUNK_LEN)?);
+    }
+    Ok(entries)
+}
+
+/// 从文件末尾倒序读取至多 `limit` 条记录，只读入所需的部分
+fn read_tail(path: &Path, limit: usize, chunk_len: u64) -> Result<Vec<AuditEntry>, String> {
+    let mut entries = Vec::new();
+    if limit == 0 {
+        return Ok(entries);
+    }
+
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(entries),
+        Err(e) => return Err(format!("读取审计文件失败 {}: {}", path.display(), e)),
+    };
+    let read_err = |e: io::Error| format!("读取审计文件失败 {}: {}", path.display(), e);
+
+    let mut pos = file.metadata().map_err(read_err)?.len();
+    // 尚未处理的部分：文件开头方向的一段不完整的行
+    let mut pending: Vec<u8> = Vec::new();
+
+    while pos > 0 && entries.len() < limit {
+        let len = chunk_len.min(pos);
+        pos -= len;
+
+        let mut chunk = vec![0u8; len as usize];
+        file.seek(SeekFrom::Start(pos)).map_err(read_err)?;
+        file.read_exact(&mut chunk).map_err(read_err)?;
+        chunk.extend_from_slice(&pending);
+        pending = chunk;
+
+        // 没读到文件开头时，第一个换行之前的内容可能只是某行的后半段
+        let start = if pos == 0 {
+            0
+        } else {
+            match pending.iter().position(|b| *b == b'\n') {
+                Some(i) => i + 1,
+                None => continue,
+            }
+        };
+        let complete = pending.split_off(start);
+
+        entries.extend(
+            complete
+                .split(|b| *b == b'\n')
+                .rev()
+                .filter_map(|line| serde_json::from_slice::<AuditEntry>(line).ok())
+                .take(limit - entries.len()),
+        );
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(n: i64) -> AuditEntry {
+        AuditEntry {
+            at: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            action: "auto_refresh".to_string(),
+            account: "user@example.com".to_string(),
+            previous_hash: None,
+            new_hash: format!("hash-{}", n),
+            previous_expiry: None,
+            new_expiry: Some(n),
+        }
+    }
+
+    fn expiries(entries: &[AuditEntry]) -> Vec<i64> {
+        entries.iter().filter_map(|e| e.new_expiry).collect()
+    }
+
+    #[test]
+    fn log_rotates_and_reads_newest_first() {
+        let dir = std::env::temp_dir().join(format!("antigravity-audit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("backup_audit.jsonl");
+
+        let line_len = serde_json::to_string(&entry(0)).unwrap().len() as u64 + 1;
+        for n in 0..10 {
+            append_to(&path, &entry(n), line_len * 4).unwrap();
+        }
+        // 已轮转两次，只保留最近一份旧文件，0..3 已被丢弃
+        assert!(fs::metadata(&path).unwrap().len() < line_len * 4);
+        assert_eq!(expiries(&read_tail(&path, 10, 16).unwrap()), vec![9, 8]);
+        assert_eq!(
+            expiries(&read_tail(&rotated_path(&path), 10, 16).unwrap()),
+            vec![7, 6, 5, 4]
+        );
+
+        assert_eq!(
+            expiries(&read_recent_from(&path, 5).unwrap()),
+            vec![9, 8, 7, 6, 5]
+        );
+        assert_eq!(expiries(&read_recent_from(&path, 1).unwrap()), vec![9]);
+        assert!(read_recent_from(&path, 0).unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn tail_skips_unparsable_lines() {
+        let dir =
+            std::env::temp_dir().join(format!("antigravity-audit-tail-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("backup_audit.jsonl");
+
+        let mut content = String::new();
+        for n in 0..3 {
+            content.push_str(&serde_json::to_string(&entry(n)).unwrap());
+            content.push_str("\n{broken\n\n");
+        }
+        fs::write(&path, content).unwrap();
+
+        for chunk_len in [1, 7, 4096] {
+            assert_eq!(
+                expiries(&read_tail(&path, 10, chunk_len).unwrap()),
+                vec![2, 1, 0]
+            );
+            assert_eq!(
+                expiries(&read_tail(&path, 2, chunk_len).unwrap()),
+                vec![2, 1]
+            );
+        }
+        assert!(read_tail(&dir.join("missing.jsonl"), 10, 16)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
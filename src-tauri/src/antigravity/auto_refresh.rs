@@ -0,0 +1,123 @@
+//! 备份自动刷新
+//! Antigravity 会在数据库中轮换 access_token 和过期时间，而保存的备份仍是旧令牌；
+//! 数据库监控发现同一邮箱的会话发生变化时，用实时会话覆盖该账户的备份
+
+use chrono::Utc;
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::active::{live_db_path, ActiveAccountStatus};
+use crate::antigravity::audit::{self, AuditEntry};
+use crate::antigravity::backup::{capture_backup, load_backup_file, write_backup_file};
+use crate::antigravity::state_store::{SqliteStateStore, StateStore};
+use crate::constants::database;
+
+/// 自动刷新的审计操作名
+const AUDIT_ACTION: &str = "auto_refresh";
+
+/// 备份被自动刷新后推送给前端的事件（`account-backup-refreshed`）
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBackupRefreshed {
+    pub account_id: AccountId,
+    pub expiry_timestamp: Option<i64>,
+}
+
+/// 用实时会话覆盖指定账户的备份；实时会话不属于该账户或内容相同时不做任何事
+///
+/// 与手动保存走同一条路径（`capture_backup`）：写入保护打开或会话校验失败时拒绝
+pub fn refresh_backup_from_live(
+    account: &AccountId,
+    snapshot_depth: usize,
+) -> Result<Option<AuditEntry>, String> {
+    let db_path = live_db_path()?;
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let store = SqliteStateStore::open_read_only(&db_path)?;
+    if store.get(database::AGENT_STATE)?.is_none() {
+        return Ok(None);
+    }
+    let incoming = capture_backup(&store, &db_path)?;
+
+    // 只在同一邮箱下刷新，绝不把一个账户的会话写进另一个账户的备份
+    if AccountId::from_email(&incoming.metadata.email)? != *account {
+        tracing::debug!(target: "backup::auto_refresh", account = %account, "实时会话已属于其他账户，跳过");
+        return Ok(None);
+    }
+
+    let account_file = account.backup_path()?;
+    let previous = load_backup_file(&account_file).ok();
+    if previous
+        .as_ref()
+        .is_some_and(|p| p.content_hash == incoming.content_hash)
+    {
+        return Ok(None);
+    }
+
+    crate::antigravity::snapshot::archive_before_overwrite(
+        account,
+        &account_file,
+        &incoming,
+        snapshot_depth,
+    )?;
+    write_backup_file(&account_file, &incoming)?;
+
+    let entry = AuditEntry {
+        at: Utc::now(),
+        action: AUDIT_ACTION.to_string(),
+        account: account.to_string(),
+        previous_hash: previous.as_ref().map(|p| p.content_hash.clone()),
+        new_hash: incoming.content_hash.clone(),
+        previous_expiry: previous.and_then(|p| p.metadata.expiry_timestamp),
+        new_expiry: incoming.metadata.expiry_timestamp,
+    };
+    if let Err(e) = audit::append(&entry) {
+        tracing::warn!(target: "backup::auto_refresh", error = %e, "写入审计记录失败");
+    }
+
+    tracing::info!(
+        target: "backup::auto_refresh",
+        account = %account,
+        new_expiry = ?entry.new_expiry,
+        "已用实时会话刷新账户备份"
+    );
+    Ok(Some(entry))
+}
+
+/// 数据库监控在识别出活动账户后调用：设置允许且保存的副本已过期时自动刷新
+pub fn maybe_refresh_active_backup(app: &AppHandle, status: &ActiveAccountStatus) {
+    let Some(account) = status.account_id.as_ref() else {
+        return;
+    };
+    if !status.stale {
+        return;
+    }
+
+    let settings = app
+        .state::<crate::app_settings::AppSettingsManager>()
+        .get_settings();
+    if !settings.auto_refresh_backups {
+        tracing::debug!(target: "backup::auto_refresh", "自动刷新备份已关闭");
+        return;
+    }
+
+    match refresh_backup_from_live(account, settings.snapshot_depth) {
+        Ok(Some(entry)) => {
+            let payload = AccountBackupRefreshed {
+                account_id: account.clone(),
+                expiry_timestamp: entry.new_expiry,
+            };
+            if let Err(e) = payload.emit(app) {
+                tracing::error!(target: "backup::auto_refresh", error = %e, "推送备份刷新事件失败");
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::warn!(target: "backup::auto_refresh", account = %account, error = %e, "自动刷新备份失败");
+        }
+    }
+}
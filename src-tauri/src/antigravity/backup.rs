@@ -0,0 +1,329 @@
+//! 账户备份文件格式
+//! 在原始 jetskiStateSync.agentManagerInitState 之外包一层带版本号的元数据信封，
+//! 读取方无需再解码 protobuf 即可拿到邮箱、套餐和过期时间
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+use crate::antigravity::account::decode_session_response;
+use crate::antigravity::compat;
+use crate::antigravity::state_store::StateStore;
+use crate::antigravity::validation;
+use crate::antigravity::vault;
+use crate::constants::database;
+
+/// 当前备份格式版本（旧版单键文件视为版本 0）
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+/// 从 SessionResponse 中提取的账户元数据
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupMetadata {
+    pub email: String,
+    pub plan_name: Option<String>,
+    pub plan_slug: Option<String>,
+    /// 访问令牌过期时间（Unix 秒）
+    pub expiry_timestamp: Option<i64>,
+}
+
+/// 账户备份文件（版本化信封）
+///
+/// 原始状态仍保存在顶层 `jetskiStateSync.agentManagerInitState` 键下，旧版本程序也能读取
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountBackup {
+    pub schema_version: u32,
+    pub saved_at: DateTime<Utc>,
+    /// 来源 state.vscdb 路径（旧版文件升级而来时为空）
+    pub source_db: Option<String>,
+    /// 写入该文件的 Antigravity Agent 版本
+    pub agent_version: String,
+    pub metadata: BackupMetadata,
+    /// agentManagerInitState 的 SHA-256（十六进制）
+    pub content_hash: String,
+    #[serde(rename = "jetskiStateSync.agentManagerInitState")]
+    pub agent_state: String,
+}
+
+impl AccountBackup {
+    /// 由原始 agentManagerInitState 构建新的备份
+    pub fn new(agent_state: String, source_db: Option<&Path>) -> Result<Self, String> {
+        Self::build(
+            agent_state,
+            Utc::now(),
+            source_db.map(|p| p.display().to_string()),
+        )
+    }
+
+    fn build(
+        agent_state: String,
+        saved_at: DateTime<Utc>,
+        source_db: Option<String>,
+    ) -> Result<Self, String> {
+        let agent_state = agent_state.trim().to_string();
+        validation::validate_agent_state(&agent_state)?;
+        let metadata = extract_metadata(&agent_state)?;
+
+        Ok(Self {
+            schema_version: BACKUP_SCHEMA_VERSION,
+            saved_at,
+            source_db,
+            agent_version: env!("CARGO_PKG_VERSION").to_string(),
+            metadata,
+            content_hash: content_hash(&agent_state),
+            agent_state,
+        })
+    }
+
+    /// 从 JSON 解析备份；旧版单键格式会被升级，返回值第二项表示是否发生了升级
+    ///
+    /// `legacy_saved_at` 用作旧版文件的保存时间（通常取文件修改时间）
+    pub fn from_value(
+        value: Value,
+        legacy_saved_at: DateTime<Utc>,
+    ) -> Result<(Self, bool), String> {
+        match value.get("schema_version").and_then(|v| v.as_u64()) {
+            Some(version) if version > BACKUP_SCHEMA_VERSION as u64 => Err(format!(
+                "备份格式版本 {} 高于当前支持的版本 {}，请升级 Antigravity Agent",
+                version, BACKUP_SCHEMA_VERSION
+            )),
+            Some(_) => {
+                let backup: Self = serde_json::from_value(value)
+                    .map_err(|e| format!("解析备份信封失败: {}", e))?;
+                validation::validate_backup(&backup)?;
+                Ok((backup, false))
+            }
+            None => {
+                let agent_state = value
+                    .get(database::AGENT_STATE)
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| format!("备份缺少 {}", database::AGENT_STATE))?;
+                Self::build(agent_state.to_string(), legacy_saved_at, None)
+                    .map(|backup| (backup, true))
+            }
+        }
+    }
+
+    /// 序列化为 JSON 值
+    pub fn to_value(&self) -> Result<Value, String> {
+        serde_json::to_value(self).map_err(|e| format!("序列化备份失败: {}", e))
+    }
+}
+
+/// 由状态库中的实时会话构建备份（保存当前账户时使用）
+///
+/// 会话格式变化、写入保护打开时拒绝，避免把无法识别的会话保存为备份
+pub fn capture_backup(store: &dyn StateStore, db_path: &Path) -> Result<AccountBackup, String> {
+    compat::ensure_writes_allowed()?;
+    let agent_state = store
+        .get(database::AGENT_STATE)?
+        .ok_or_else(|| format!("未找到 {}", database::AGENT_STATE))?;
+    AccountBackup::new(agent_state, Some(db_path))
+}
+
+/// 解码 agentManagerInitState 并提取元数据（邮箱必需）
+fn extract_metadata(agent_state: &str) -> Result<BackupMetadata, String> {
+    let msg = decode_session_response(agent_state)?;
+
+    let context = msg.context.as_ref();
+    let email = context
+        .map(|c| c.email.clone())
+        .filter(|email| !email.is_empty())
+        .ok_or_else(|| "jetskiStateSync 中未找到邮箱字段".to_string())?;
+
+    let non_empty = |s: &String| (!s.is_empty()).then(|| s.clone());
+    let plan = context.and_then(|c| c.plan.as_ref());
+
+    Ok(BackupMetadata {
+        email,
+        plan_name: plan
+            .and_then(|p| non_empty(&p.name))
+            .or_else(|| context.and_then(|c| non_empty(&c.plan_name))),
+        plan_slug: plan.and_then(|p| non_empty(&p.slug)),
+        expiry_timestamp: msg
+            .auth
+            .as_ref()
+            .and_then(|a| a.meta.as_ref())
+            .map(|m| m.expiry_timestamp)
+            .filter(|ts| *ts > 0),
+    })
+}
+
+/// 计算 agentManagerInitState 的 SHA-256
+pub fn content_hash(agent_state: &str) -> String {
+    format!("{:x}", Sha256::digest(agent_state.trim().as_bytes()))
+}
+
+/// 读取备份文件；旧版单键文件会被透明升级并写回磁盘，
+/// 保险库已解锁时明文文件也会被写回为加密文件
+pub fn load_backup_file(path: &Path) -> Result<AccountBackup, String> {
+    let (backup, upgraded, encrypted) = parse_backup_file(path)?;
+
+    if !upgraded && !encrypted && vault::is_enabled() && vault::is_unlocked() {
+        if let Err(e) = write_backup_file(path, &backup) {
+            tracing::warn!(target: "account::vault", file = %path.display(), error = %e, "加密明文备份失败");
+        }
+    }
+
+    if upgraded {
+        match write_backup_file(path, &backup) {
+            Ok(()) => tracing::info!(
+                target: "backup::upgrade",
+                file = %path.display(),
+                schema_version = BACKUP_SCHEMA_VERSION,
+                "已升级旧版备份文件"
+            ),
+            Err(e) => tracing::warn!(
+                target: "backup::upgrade",
+                file = %path.display(),
+                error = %e,
+                "旧版备份文件升级后写回失败（本次仍使用升级后的内容）"
+            ),
+        }
+    }
+
+    Ok(backup)
+}
+
+/// 只读地读取备份文件：旧版文件在内存中升级，不写回磁盘（用于检查隔离区等不应改动文件的场景）
+pub fn read_backup_file(path: &Path) -> Result<AccountBackup, String> {
+    parse_backup_file(path).map(|(backup, _, _)| backup)
+}
+
+/// 读取并解析备份文件，返回（备份, 是否由旧版升级, 文件是否已加密）
+fn parse_backup_file(path: &Path) -> Result<(AccountBackup, bool, bool), String> {
+    let (content, encrypted) = vault::read_account_file(path)?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("解析 JSON 失败 {}: {}", path.display(), e))?;
+
+    let modified: DateTime<Utc> = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(DateTime::from)
+        .unwrap_or_else(|_| Utc::now());
+
+    let (backup, upgraded) = AccountBackup::from_value(value, modified)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok((backup, upgraded, encrypted))
+}
+
+/// 写入备份文件（保险库开启时加密保存）
+pub fn write_backup_file(path: &Path, backup: &AccountBackup) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(backup).map_err(|e| format!("序列化备份失败: {}", e))?;
+    vault::write_account_file(path, &json)
+}
+
+/// 构造测试用的 agentManagerInitState（只包含邮箱、访问令牌和过期时间）
+#[cfg(test)]
+pub fn test_agent_state(email: &str, expiry_timestamp: i64) -> String {
+    use base64::Engine;
+    use prost::Message;
+
+    let msg = crate::proto::SessionResponse {
+        auth: Some(crate::proto::AuthInfo {
+            access_token: format!("token-{}", email),
+            meta: Some(crate::proto::AuthMetadata { expiry_timestamp }),
+            ..Default::default()
+        }),
+        context: Some(crate::proto::UserContext {
+            email: email.to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    base64::engine::general_purpose::STANDARD.encode(msg.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn legacy_saved_at() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_600_000_000, 0).unwrap()
+    }
+
+    #[test]
+    fn new_backup_carries_metadata_and_hash() {
+        let state = test_agent_state("alice@example.com", 1_700_000_000);
+        let backup = AccountBackup::new(
+            format!("  {}\n", state),
+            Some(Path::new("/tmp/state.vscdb")),
+        )
+        .unwrap();
+
+        assert_eq!(backup.schema_version, BACKUP_SCHEMA_VERSION);
+        assert_eq!(backup.agent_state, state);
+        assert_eq!(backup.content_hash, content_hash(&state));
+        assert_eq!(backup.source_db.as_deref(), Some("/tmp/state.vscdb"));
+        assert_eq!(
+            backup.metadata,
+            BackupMetadata {
+                email: "alice@example.com".to_string(),
+                plan_name: None,
+                plan_slug: None,
+                expiry_timestamp: Some(1_700_000_000),
+            }
+        );
+        assert!(AccountBackup::new(test_agent_state("", 0), None).is_err());
+    }
+
+    #[test]
+    fn envelope_round_trips() {
+        let backup = AccountBackup::new(test_agent_state("alice@example.com", 0), None).unwrap();
+        let value = backup.to_value().unwrap();
+        assert_eq!(value["schema_version"], json!(BACKUP_SCHEMA_VERSION));
+        // 旧版程序仍能从顶层键读取原始状态
+        assert_eq!(value[database::AGENT_STATE], json!(backup.agent_state));
+
+        let (parsed, upgraded) = AccountBackup::from_value(value, legacy_saved_at()).unwrap();
+        assert!(!upgraded);
+        assert_eq!(parsed.saved_at, backup.saved_at);
+        assert_eq!(parsed.metadata, backup.metadata);
+        assert_eq!(parsed.content_hash, backup.content_hash);
+        assert_eq!(parsed.metadata.expiry_timestamp, None);
+    }
+
+    #[test]
+    fn legacy_single_key_files_are_upgraded() {
+        let state = test_agent_state("bob@example.com", 1_700_000_000);
+        let (backup, upgraded) =
+            AccountBackup::from_value(json!({ database::AGENT_STATE: state }), legacy_saved_at())
+                .unwrap();
+
+        assert!(upgraded);
+        assert_eq!(backup.schema_version, BACKUP_SCHEMA_VERSION);
+        assert_eq!(backup.saved_at, legacy_saved_at());
+        assert_eq!(backup.source_db, None);
+        assert_eq!(backup.metadata.email, "bob@example.com");
+        assert_eq!(backup.content_hash, content_hash(&state));
+
+        assert!(AccountBackup::from_value(json!({}), legacy_saved_at()).is_err());
+    }
+
+    #[test]
+    fn tampered_or_newer_envelopes_are_rejected() {
+        let backup = AccountBackup::new(test_agent_state("alice@example.com", 0), None).unwrap();
+
+        let mut tampered = backup.to_value().unwrap();
+        tampered["content_hash"] = json!(content_hash("other"));
+        assert!(AccountBackup::from_value(tampered, legacy_saved_at()).is_err());
+
+        let mut renamed = backup.to_value().unwrap();
+        renamed["metadata"]["email"] = json!("mallory@example.com");
+        assert!(AccountBackup::from_value(renamed, legacy_saved_at()).is_err());
+
+        let mut newer = backup.to_value().unwrap();
+        newer["schema_version"] = json!(BACKUP_SCHEMA_VERSION + 1);
+        let err = AccountBackup::from_value(newer, legacy_saved_at()).unwrap_err();
+        assert!(err.contains("高于当前支持的版本"));
+    }
+
+    #[test]
+    fn content_hash_ignores_surrounding_whitespace() {
+        assert_eq!(content_hash("abc"), content_hash(" abc\n"));
+        assert_ne!(content_hash("abc"), content_hash("abd"));
+    }
+}
@@ -0,0 +1,496 @@
+//! 可移植导出包
+//! 导出包自带清单（格式版本、Agent 版本、账户列表及每个条目的 SHA-256），
+//! 由后端直接读写磁盘；导入时逐条校验，个别条目损坏不影响其余条目
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::{load_backup_file, AccountBackup};
+use crate::antigravity::inventory;
+use crate::utils::crypto;
+
+/// 导出包格式标识
+pub const BUNDLE_FORMAT: &str = "antigravity-agent-bundle";
+
+/// 当前导出包格式版本
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 导出包清单
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format: String,
+    pub format_version: u32,
+    /// 生成该导出包的 Antigravity Agent 版本
+    pub agent_version: String,
+    pub created_at: DateTime<Utc>,
+    pub accounts: Vec<BundleManifestEntry>,
+}
+
+/// 清单中的单个账户
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifestEntry {
+    pub file_name: String,
+    pub email: String,
+    pub plan_name: Option<String>,
+    pub expiry_timestamp: Option<i64>,
+    /// 条目内容（紧凑 JSON）的 SHA-256
+    pub sha256: String,
+}
+
+/// 导出包中的单个条目（内容即备份信封）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleEntry {
+    pub file_name: String,
+    pub content: Value,
+}
+
+/// 导出包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportBundle {
+    pub manifest: BundleManifest,
+    pub entries: Vec<BundleEntry>,
+}
+
+/// 读取导出包后单个条目的校验结果
+#[derive(Debug)]
+pub struct BundleItem {
+    pub file_name: String,
+    pub backup: Result<AccountBackup, String>,
+}
+
+/// 计算条目内容的校验和
+///
+/// serde_json 按确定的键顺序输出，同一个值解析后再序列化得到的字节不变
+fn entry_checksum(content: &Value) -> Result<String, String> {
+    let bytes = serde_json::to_vec(content).map_err(|e| format!("序列化导出包条目失败: {}", e))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// 读取账户备份，按文件名排序
+///
+/// `selected` 为空时读取目录中所有可读取的备份（跳过损坏文件）；
+/// 指定账户时任何一个读取失败都会返回错误
+pub fn collect_backups(
+    dir: &Path,
+    selected: Option<&[AccountId]>,
+) -> Result<Vec<(String, AccountBackup)>, String> {
+    let mut backups = Vec::new();
+
+    match selected {
+        Some(accounts) => {
+            for account in accounts {
+                let path = account.resolve_in(dir)?;
+                if !path.exists() {
+                    return Err(format!("账户备份不存在: {}", account));
+                }
+                backups.push((account.file_name(), load_backup_file(&path)?));
+            }
+        }
+        None if dir.exists() => {
+            for entry in fs::read_dir(dir).map_err(|e| format!("读取用户目录失败: {}", e))?
+            {
+                let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                match load_backup_file(&path) {
+                    Ok(backup) => backups.push((file_name.to_string(), backup)),
+                    Err(e) => {
+                        tracing::warn!(target: "backup::bundle", file = %file_name, error = %e, "跳过损坏的备份文件")
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+
+    backups.sort_by(|a, b| a.0.cmp(&b.0));
+    backups.dedup_by(|a, b| a.0 == b.0);
+    Ok(backups)
+}
+
+/// 收集账户备份生成导出包（`selected` 含义同 [`collect_backups`]）
+pub fn build(dir: &Path, selected: Option<&[AccountId]>) -> Result<ExportBundle, String> {
+    let backups = collect_backups(dir, selected)?;
+
+    let mut accounts = Vec::with_capacity(backups.len());
+    let mut entries = Vec::with_capacity(backups.len());
+    for (file_name, backup) in backups {
+        let content = backup.to_value()?;
+        accounts.push(BundleManifestEntry {
+            file_name: file_name.clone(),
+            email: backup.metadata.email,
+            plan_name: backup.metadata.plan_name,
+            expiry_timestamp: backup.metadata.expiry_timestamp,
+            sha256: entry_checksum(&content)?,
+        });
+        entries.push(BundleEntry { file_name, content });
+    }
+
+    Ok(ExportBundle {
+        manifest: BundleManifest {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_VERSION,
+            agent_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            accounts,
+        },
+        entries,
+    })
+}
+
+/// 把导出包写入磁盘；提供口令时整包加密
+pub fn write(path: &Path, bundle: &ExportBundle, password: Option<&str>) -> Result<(), String> {
+    let json =
+        serde_json::to_string_pretty(bundle).map_err(|e| format!("序列化导出包失败: {}", e))?;
+    let content = match password {
+        Some("") => return Err("密码不能为空".to_string()),
+        Some(password) => crypto::encrypt_with_password(json.as_bytes(), password)?,
+        None => json,
+    };
+    fs::write(path, content).map_err(|e| format!("写入导出包失败 {}: {}", path.display(), e))
+}
+
+/// 读取文件得到导出包的 JSON 文本（处理加密信封与旧版 XOR 加密）
+fn read_plaintext(path: &Path, password: Option<&str>) -> Result<String, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("读取导出包失败 {}: {}", path.display(), e))?;
+    let password = password.filter(|p| !p.is_empty());
+
+    if crypto::is_envelope(&raw) {
+        let password = password.ok_or_else(|| "该导出包已加密，请输入密码".to_string())?;
+        let plaintext = crypto::decrypt_envelope(&raw, password)?;
+        return String::from_utf8(plaintext).map_err(|_| "解密失败，数据可能已损坏".to_string());
+    }
+    if serde_json::from_str::<Value>(&raw).is_ok() {
+        return Ok(raw);
+    }
+
+    // 旧版 XOR 没有完整性校验，只能通过能否解析为 JSON 判断密码是否正确
+    let password = password.ok_or_else(|| "导出包格式无效".to_string())?;
+    let plaintext = crypto::decrypt_legacy_xor(&raw, password)?;
+    tracing::warn!(target: "backup::bundle", "导入了旧版 XOR 加密的配置文件");
+    String::from_utf8(plaintext)
+        .ok()
+        .filter(|data| serde_json::from_str::<Value>(data).is_ok())
+        .ok_or_else(|| "解密失败：密码错误或数据已损坏".to_string())
+}
+
+/// 读取并逐条校验导出包
+///
+/// 只有清单本身无法解析时才整体失败；条目缺失、校验和不符或内容无效都只记录在对应条目上。
+/// 旧版 `{version, backups}` 格式没有清单，条目不做校验和检查
+pub fn read(
+    path: &Path,
+    password: Option<&str>,
+) -> Result<(BundleManifest, Vec<BundleItem>), String> {
+    let text = read_plaintext(path, password)?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| format!("解析导出包失败: {}", e))?;
+
+    if value.get("format").and_then(|f| f.as_str()) == Some(inventory::INVENTORY_FORMAT) {
+        return Err("这是脱敏的账户清单，不包含凭据，无法导入".to_string());
+    }
+    if value.get("manifest").is_none() {
+        if let Some(backups) = value.get("backups").and_then(|b| b.as_array()) {
+            return Ok(read_legacy(backups));
+        }
+        return Err("导出包缺少清单".to_string());
+    }
+
+    let manifest: BundleManifest = serde_json::from_value(value["manifest"].clone())
+        .map_err(|e| format!("解析导出包清单失败: {}", e))?;
+    if manifest.format != BUNDLE_FORMAT {
+        return Err("不是 Antigravity Agent 导出包".to_string());
+    }
+    if manifest.format_version > BUNDLE_VERSION {
+        return Err(format!(
+            "导出包格式版本 {} 高于当前支持的版本 {}，请升级 Antigravity Agent",
+            manifest.format_version, BUNDLE_VERSION
+        ));
+    }
+
+    // 条目逐个解析，单个条目结构损坏时只丢弃它自己
+    let mut contents: HashMap<String, Value> = HashMap::new();
+    for entry in value
+        .get("entries")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+    {
+        match serde_json::from_value::<BundleEntry>(entry.clone()) {
+            Ok(entry) => {
+                contents.insert(entry.file_name, entry.content);
+            }
+            Err(e) => {
+                tracing::warn!(target: "backup::bundle", error = %e, "跳过结构无效的导出包条目")
+            }
+        }
+    }
+
+    let mut items = Vec::with_capacity(manifest.accounts.len());
+    for account in &manifest.accounts {
+        let backup = match contents.remove(&account.file_name) {
+            None => Err("导出包中缺少该条目".to_string()),
+            Some(content) => verify_entry(account, content, manifest.created_at),
+        };
+        items.push(BundleItem {
+            file_name: account.file_name.clone(),
+            backup,
+        });
+    }
+
+    // 清单之外的条目无法校验，不予导入
+    for file_name in contents.into_keys() {
+        items.push(BundleItem {
+            file_name,
+            backup: Err("条目不在清单中".to_string()),
+        });
+    }
+
+    Ok((manifest, items))
+}
+
+/// 校验单个条目：校验和、文件名与内容中的邮箱一致
+fn verify_entry(
+    account: &BundleManifestEntry,
+    content: Value,
+    created_at: DateTime<Utc>,
+) -> Result<AccountBackup, String> {
+    let checksum = entry_checksum(&content)?;
+    if checksum != account.sha256 {
+        return Err("校验和不匹配，条目可能已损坏".to_string());
+    }
+
+    let file_id = AccountId::from_file_name(&account.file_name)?;
+    let (backup, _) = AccountBackup::from_value(content, created_at)?;
+    if AccountId::from_email(&backup.metadata.email)? != file_id {
+        return Err(format!(
+            "条目内容属于 {}，与文件名不符",
+            backup.metadata.email
+        ));
+    }
+    Ok(backup)
+}
+
+/// 旧版导出格式：前端生成的 `{version, backupCount, backups: [{filename, content, timestamp}]}`
+fn read_legacy(backups: &[Value]) -> (BundleManifest, Vec<BundleItem>) {
+    let items: Vec<BundleItem> = backups
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let file_name = entry
+                .get("filename")
+                .and_then(|f| f.as_str())
+                .map(|f| f.to_string())
+                .unwrap_or_else(|| format!("#{}", i + 1));
+            let imported_at = entry
+                .get("timestamp")
+                .and_then(|t| t.as_i64())
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .unwrap_or_else(Utc::now);
+            let backup = AccountId::from_file_name(&file_name).and_then(|_| {
+                let content = entry
+                    .get("content")
+                    .cloned()
+                    .ok_or_else(|| "条目缺少内容".to_string())?;
+                AccountBackup::from_value(content, imported_at).map(|(backup, _)| backup)
+            });
+            BundleItem { file_name, backup }
+        })
+        .collect();
+
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        format_version: 0,
+        agent_version: String::new(),
+        created_at: Utc::now(),
+        accounts: Vec::new(),
+    };
+    (manifest, items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::backup::{test_agent_state, write_backup_file};
+    use std::path::PathBuf;
+
+    /// 临时目录中写入两个账户的备份
+    fn temp_accounts(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "antigravity-bundle-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let accounts = dir.join("accounts");
+        fs::create_dir_all(&accounts).unwrap();
+        for email in ["a@example.com", "b@example.com"] {
+            let backup = AccountBackup::new(test_agent_state(email, 0), None).unwrap();
+            write_backup_file(&accounts.join(format!("{}.json", email)), &backup).unwrap();
+        }
+        dir
+    }
+
+    /// 文件名及读出的邮箱（或错误）
+    type Checked = Vec<(String, Result<String, String>)>;
+
+    /// 写出导出包 JSON 后读回，返回每个条目的校验结果
+    fn read_value(dir: &Path, value: &Value) -> Result<Checked, String> {
+        let path = dir.join("bundle.json");
+        fs::write(&path, value.to_string()).unwrap();
+        let (_, items) = read(&path, None)?;
+        Ok(items
+            .into_iter()
+            .map(|item| (item.file_name, item.backup.map(|b| b.metadata.email)))
+            .collect())
+    }
+
+    fn bundle_value(dir: &Path) -> Value {
+        serde_json::to_value(build(&dir.join("accounts"), None).unwrap()).unwrap()
+    }
+
+    fn error_of(items: &Checked, file_name: &str) -> String {
+        items
+            .iter()
+            .find(|(name, _)| name == file_name)
+            .and_then(|(_, result)| result.clone().err())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn round_trip_verifies_every_entry() {
+        let dir = temp_accounts("round-trip");
+        let items = read_value(&dir, &bundle_value(&dir)).unwrap();
+        assert_eq!(
+            items,
+            vec![
+                (
+                    "a@example.com.json".to_string(),
+                    Ok("a@example.com".to_string())
+                ),
+                (
+                    "b@example.com.json".to_string(),
+                    Ok("b@example.com".to_string())
+                ),
+            ]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn encrypted_round_trip_requires_the_password() {
+        let dir = temp_accounts("encrypted");
+        let bundle = build(&dir.join("accounts"), None).unwrap();
+        let path = dir.join("bundle.json");
+        write(&path, &bundle, Some("correct horse")).unwrap();
+
+        let (manifest, items) = read(&path, Some("correct horse")).unwrap();
+        assert_eq!(manifest.accounts.len(), 2);
+        assert!(items.iter().all(|item| item.backup.is_ok()));
+        assert!(read(&path, None).is_err());
+        assert!(read(&path, Some("wrong")).is_err());
+        assert!(write(&path, &bundle, Some("")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn damaged_entries_fail_individually() {
+        let dir = temp_accounts("damaged");
+        let mut value = bundle_value(&dir);
+
+        // a 的内容被改动；b 的条目丢失；多出一个清单之外的条目
+        value["entries"][0]["content"]["agent_version"] = Value::from("tampered");
+        let extra = value["entries"][1].clone();
+        value["entries"][1]["fileName"] = Value::from("c@example.com.json");
+        value["entries"].as_array_mut().unwrap().push(
+            serde_json::json!({ "fileName": "d@example.com.json", "content": extra["content"] }),
+        );
+
+        let items = read_value(&dir, &value).unwrap();
+        assert!(error_of(&items, "a@example.com.json").contains("校验和不匹配"));
+        assert!(error_of(&items, "b@example.com.json").contains("缺少该条目"));
+        assert!(error_of(&items, "c@example.com.json").contains("不在清单中"));
+        assert!(error_of(&items, "d@example.com.json").contains("不在清单中"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn entry_must_belong_to_its_file_name() {
+        let dir = temp_accounts("mismatch");
+        let mut value = bundle_value(&dir);
+
+        // 清单与条目同时改名，校验和仍然匹配，但内容属于 a
+        value["manifest"]["accounts"][0]["fileName"] = Value::from("z@example.com.json");
+        value["entries"][0]["fileName"] = Value::from("z@example.com.json");
+
+        let items = read_value(&dir, &value).unwrap();
+        assert!(error_of(&items, "z@example.com.json").contains("与文件名不符"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unsupported_bundles_are_rejected_as_a_whole() {
+        let dir = temp_accounts("unsupported");
+
+        let mut newer = bundle_value(&dir);
+        newer["manifest"]["formatVersion"] = Value::from(BUNDLE_VERSION + 1);
+        assert!(read_value(&dir, &newer)
+            .unwrap_err()
+            .contains("高于当前支持的版本"));
+
+        let mut foreign = bundle_value(&dir);
+        foreign["manifest"]["format"] = Value::from("something-else");
+        assert!(read_value(&dir, &foreign).is_err());
+
+        assert!(read_value(&dir, &serde_json::json!({ "entries": [] })).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_bundles_are_read_without_manifest() {
+        let dir = temp_accounts("legacy");
+        let state = test_agent_state("a@example.com", 0);
+        let value = serde_json::json!({
+            "version": "1.0",
+            "backupCount": 3,
+            "backups": [
+                {
+                    "filename": "a@example.com.json",
+                    "content": { crate::constants::database::AGENT_STATE: state },
+                    "timestamp": 1_700_000_000
+                },
+                { "filename": "b@example.com.json", "timestamp": 1_700_000_000 },
+                { "content": {} }
+            ]
+        });
+
+        let items = read_value(&dir, &value).unwrap();
+        assert_eq!(
+            items[0],
+            (
+                "a@example.com.json".to_string(),
+                Ok("a@example.com".to_string())
+            )
+        );
+        assert!(error_of(&items, "b@example.com.json").contains("缺少内容"));
+        assert!(items[2].1.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,47 +1,56 @@
 // Antigravity 用户数据清除模块
 // 负责清除 Antigravity 应用的所有用户认证和设置信息
 
-use rusqlite::{params, Connection};
 use std::path::Path;
 
+use crate::antigravity::compat;
+use crate::antigravity::state_store::{
+    apply_with_snapshot, KeySnapshot, SqliteStateStore, StateStore,
+};
+use crate::constants::database;
 // 导入 platform_utils 模块
 use crate::platform;
 
-fn clear_database(db_path: &Path, db_name: &str) -> Result<usize, String> {
-    tracing::info!(target: "cleanup::database", db_name = %db_name, "开始清理数据库");
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+/// 清理时会修改的键
+pub const CLEARED_KEYS: [&str; 3] = [
+    database::AGENT_STATE,
+    database::AUTH_STATUS,
+    database::ONBOARDING,
+];
 
+/// 清除单个状态库中的登录信息，返回受影响的项数
+/// 任何一步失败都直接返回错误，由调用方的事务撤销已做的修改
+pub fn clear_state(store: &mut dyn StateStore) -> Result<usize, String> {
     // 删除 jetskiStateSync.agentManagerInitState
-    let key = "jetskiStateSync.agentManagerInitState";
-    let rows = conn
-        .execute("DELETE FROM ItemTable WHERE key = ?", [key])
-        .unwrap_or(0);
+    let rows = store.delete(database::AGENT_STATE)?;
 
     // 根据用户报告, 有些情况不删除 antigravityAuthStatus, Antigravity 不会生成新的
-    let antigravity_auth_status_key = "antigravityAuthStatus";
-    let antigravity_auth_status_rows = conn
-        .execute("DELETE FROM ItemTable WHERE key = ?", [antigravity_auth_status_key])
-        .unwrap_or(0);
+    let antigravity_auth_status_rows = store.delete(database::AUTH_STATUS)?;
 
     // 把 antigravityOnboarding 设置为布尔值 true（写为字符串 "true"） 以跳过首次启动引导
-    let onboarding_key = "antigravityOnboarding";
-    let onboarding_rows = conn
-        .execute(
-            "INSERT OR REPLACE INTO ItemTable (key, value) VALUES (?, ?)",
-            params![onboarding_key, "true"],
-        )
-        .unwrap_or(0);
+    store.put(database::ONBOARDING, "true")?;
 
     if rows > 0 {
-        tracing::debug!(target: "cleanup::database", key = %key, "已删除字段");
+        tracing::debug!(target: "cleanup::database", key = %database::AGENT_STATE, "已删除字段");
     }
 
-    Ok(rows + onboarding_rows + antigravity_auth_status_rows)
+    Ok(rows + 1 + antigravity_auth_status_rows)
+}
+
+/// 在一个事务中清理单个数据库文件，返回受影响的项数和修改前的原值
+fn clear_database(db_path: &Path, db_name: &str) -> Result<(usize, KeySnapshot), String> {
+    tracing::info!(target: "cleanup::database", db_name = %db_name, "开始清理数据库");
+    let mut store = SqliteStateStore::open(db_path)?;
+    apply_with_snapshot(&mut store, &CLEARED_KEYS, clear_state)
+        .map_err(|e| format!("清理 {} 失败: {}", db_name, e))
 }
 
 pub async fn clear_all_antigravity_data() -> Result<String, String> {
     tracing::info!(target: "cleanup::main", "开始清除 Antigravity 用户认证数据（保留设备指纹）");
 
+    // 会话格式变化时不修改 Antigravity 数据库
+    compat::ensure_writes_allowed()?;
+
     let app_data = match platform::get_antigravity_db_path() {
         Some(p) => p,
         None => {
@@ -62,15 +71,12 @@ pub async fn clear_all_antigravity_data() -> Result<String, String> {
 
     let mut msg = String::new();
 
+    // 主库清理与备份库删除要么都完成，要么都不生效
     // 清理主库
     tracing::info!(target: "cleanup::main", "步骤1: 清除 state.vscdb 数据库");
-    match clear_database(&app_data, "state.vscdb") {
-        Ok(c) => {
-            tracing::info!(target: "cleanup::main", cleaned_count = %c, "主数据库已清除");
-            msg.push_str(&format!("主库清理 {} 项", c));
-        }
-        Err(e) => return Err(e),
-    }
+    let (c, main_snapshot) = clear_database(&app_data, "state.vscdb")?;
+    tracing::info!(target: "cleanup::main", cleaned_count = %c, "主数据库已清除");
+    msg.push_str(&format!("主库清理 {} 项", c));
 
     // 删除备份库
     tracing::info!(target: "cleanup::main", "步骤2: 删除 state.vscdb.backup（如存在）");
@@ -82,7 +88,15 @@ pub async fn clear_all_antigravity_data() -> Result<String, String> {
                 msg.push_str("; 备份库文件已删除");
             }
             Err(e) => {
-                tracing::warn!(target: "cleanup::main", file = %backup_db.display(), error = %e, "删除备份数据库文件失败")
+                // 撤销主库已提交的修改
+                tracing::error!(target: "cleanup::main", file = %backup_db.display(), error = %e, "删除备份数据库文件失败，撤销主库修改");
+                let e = format!("删除备份数据库文件失败: {}", e);
+                return match SqliteStateStore::open(&app_data)
+                    .and_then(|mut store| main_snapshot.restore(&mut store))
+                {
+                    Ok(()) => Err(format!("{}（主库已撤销，未做任何修改）", e)),
+                    Err(undo) => Err(format!("{}；撤销主库修改失败: {}", e, undo)),
+                };
             }
         }
     } else {
@@ -91,3 +105,36 @@ pub async fn clear_all_antigravity_data() -> Result<String, String> {
 
     Ok(format!("✅ 登出成功: {}", msg))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::state_store::MemoryStateStore;
+
+    fn live_store() -> MemoryStateStore {
+        MemoryStateStore::with_items([
+            (database::AGENT_STATE, "session"),
+            (database::AUTH_STATUS, "{}"),
+            ("other", "kept"),
+        ])
+    }
+
+    #[test]
+    fn clear_removes_session_and_skips_onboarding() {
+        let mut store = live_store();
+        let (count, snapshot) =
+            apply_with_snapshot(&mut store, &CLEARED_KEYS, clear_state).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(store.get(database::AGENT_STATE).unwrap(), None);
+        assert_eq!(store.get(database::AUTH_STATUS).unwrap(), None);
+        assert_eq!(
+            store.get(database::ONBOARDING).unwrap().as_deref(),
+            Some("true")
+        );
+        assert_eq!(store.get("other").unwrap().as_deref(), Some("kept"));
+
+        // 撤销后连原本不存在的 antigravityOnboarding 也一并移除
+        snapshot.restore(&mut store).unwrap();
+        assert_eq!(store, live_store());
+    }
+}
@@ -0,0 +1,517 @@
+//! 会话格式兼容性自检
+//! Antigravity 升级后 SessionResponse 的结构可能变化（例如邮箱不再位于 UserContext 的标签 7），
+//! 此时保存会失败，切换则可能把不兼容的会话注入 state.vscdb。
+//! 启动时和每次切换前解码实时数据库与备份，按关键字段的出现情况与预期的格式指纹比较；
+//! 实时会话不兼容时打开写入保护，阻止保存、导入、恢复（包括快照回滚和隔离文件重新导入）、
+//! 精简会话、自动刷新、切换和登出，直到重新检查通过；只读查询和 dry-run 不受影响
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use crate::antigravity::account::{decode_session_response, decode_state_bytes};
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::{active, path_config, vault};
+use crate::constants::database;
+use crate::path_utils::AppPaths;
+use crate::utils::protobuf_wire::{self, WireField, WireValue};
+
+/// 实时会话的来源名称
+const LIVE_SOURCE: &str = "state.vscdb";
+
+/// proto 中已建模的 SessionResponse 顶层标签
+const MODELED_ROOT_TAGS: &[u32] = &[1, 5, 6, 7, 9, 11, 15, 18, 19];
+
+/// 最近一次检查结果（写入保护以此为准）
+static LAST_REPORT: Mutex<Option<SessionFormatReport>> = Mutex::new(None);
+
+/// 预期格式中的关键字段
+struct KeyField {
+    /// 从根消息开始的标签路径
+    path: &'static str,
+    name: &'static str,
+    wire_type: u8,
+    /// 缺失时视为格式已变化
+    required: bool,
+}
+
+const fn key(path: &'static str, name: &'static str, wire_type: u8, required: bool) -> KeyField {
+    KeyField {
+        path,
+        name,
+        wire_type,
+        required,
+    }
+}
+
+/// 预期的格式指纹：账户识别、保存和切换依赖的字段
+const KEY_FIELDS: &[KeyField] = &[
+    key("6", "auth", 2, true),
+    key("6.1", "auth.access_token", 2, true),
+    key("6.4.1", "auth.meta.expiry_timestamp", 0, false),
+    key("19", "context", 2, true),
+    key("19.3", "context.plan_name", 2, false),
+    key("19.7", "context.email", 2, true),
+    key("19.33", "context.models", 2, false),
+    key("19.36", "context.plan", 2, false),
+];
+
+/// 单个会话的检查结果
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFormatCheck {
+    /// 实时数据库或备份文件名
+    pub source: String,
+    pub compatible: bool,
+    /// 会话中包含 auth 或 context（未登录的实时会话不参与兼容性判断）
+    pub signed_in: bool,
+    /// 关键字段出现情况的指纹（8 位十六进制），无法解码时为空
+    pub fingerprint: Option<String>,
+    /// 缺失或线类型不符的必需字段，例如 `context.email (19.7)`
+    pub missing: Vec<String>,
+    /// proto 中未建模的顶层标签（仅供参考，不影响兼容性）
+    pub unknown_tags: Vec<u32>,
+    /// 无法解码的原因
+    pub error: Option<String>,
+}
+
+/// 一次完整的兼容性检查
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFormatReport {
+    /// 实时会话兼容（未登录时视为兼容）
+    pub compatible: bool,
+    /// 检测到的 Antigravity 版本
+    pub antigravity_version: Option<String>,
+    /// 实时会话的检查结果（未登录或数据库不存在时为空）
+    pub live: Option<SessionFormatCheck>,
+    pub backups: Vec<SessionFormatCheck>,
+    /// 保险库锁定，未检查备份
+    pub backups_skipped: bool,
+    /// 写入保护已打开
+    pub writes_blocked: bool,
+    /// 格式变化的诊断信息
+    pub diagnostic: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// 写入保护状态变化或仍处于打开状态时推送给前端的事件（`session-format-changed`）
+#[derive(Debug, Clone, Serialize, Type, Event)]
+pub struct SessionFormatChanged(pub SessionFormatReport);
+
+/// 按标签路径展开全部字段，记录线类型
+fn collect_paths(fields: &[WireField], paths: &mut HashMap<String, u8>) {
+    for field in fields {
+        paths.entry(field.path.clone()).or_insert(field.wire_type);
+        if let WireValue::Message { fields } = &field.value {
+            collect_paths(fields, paths);
+        }
+    }
+}
+
+/// 检查一段 agentManagerInitState 是否符合预期格式
+pub fn check_agent_state(source: &str, agent_state: &str) -> SessionFormatCheck {
+    let mut check = SessionFormatCheck {
+        source: source.to_string(),
+        compatible: false,
+        signed_in: true,
+        fingerprint: None,
+        missing: Vec::new(),
+        unknown_tags: Vec::new(),
+        error: None,
+    };
+
+    let fields = match decode_state_bytes(agent_state)
+        .and_then(|bytes| protobuf_wire::decode_message(&bytes))
+    {
+        Ok(fields) => fields,
+        Err(e) => {
+            check.error = Some(e);
+            return check;
+        }
+    };
+
+    let mut paths = HashMap::new();
+    collect_paths(&fields, &mut paths);
+    check.signed_in = paths.contains_key("6") || paths.contains_key("19");
+
+    let mut present = Vec::new();
+    for key in KEY_FIELDS {
+        let matches = paths.get(key.path) == Some(&key.wire_type);
+        if matches {
+            present.push(key.path);
+        } else if key.required {
+            check.missing.push(format!("{} ({})", key.name, key.path));
+        }
+    }
+    let digest = Sha256::digest(present.join(",").as_bytes());
+    check.fingerprint = Some(format!("{:x}", digest)[..8].to_string());
+
+    check.unknown_tags = fields
+        .iter()
+        .map(|field| field.tag)
+        .filter(|tag| !MODELED_ROOT_TAGS.contains(tag))
+        .collect();
+    check.unknown_tags.sort_unstable();
+    check.unknown_tags.dedup();
+
+    // 线格式符合后再用 proto 定义解码一次，确认字段内容本身可用
+    if check.missing.is_empty() {
+        match decode_session_response(agent_state) {
+            Ok(msg) => {
+                let email = msg.context.as_ref().map(|c| c.email.as_str());
+                if !email.is_some_and(|email| email.contains('@')) {
+                    check.missing.push("context.email (19.7)".to_string());
+                }
+            }
+            Err(e) => check.error = Some(e),
+        }
+    }
+
+    check.compatible = check.missing.is_empty() && check.error.is_none();
+    check
+}
+
+/// 只读检查一个备份文件（不升级、不加密写回）
+fn check_backup_file(path: &Path) -> SessionFormatCheck {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let agent_state = vault::read_account_file(path).and_then(|(content, _)| {
+        let value: Value =
+            serde_json::from_str(&content).map_err(|e| format!("解析 JSON 失败: {}", e))?;
+        value
+            .get(database::AGENT_STATE)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| format!("备份缺少 {}", database::AGENT_STATE))
+    });
+
+    match agent_state {
+        Ok(agent_state) => check_agent_state(&file_name, &agent_state),
+        Err(e) => SessionFormatCheck {
+            source: file_name,
+            compatible: false,
+            signed_in: true,
+            fingerprint: None,
+            missing: Vec::new(),
+            unknown_tags: Vec::new(),
+            error: Some(e),
+        },
+    }
+}
+
+/// 账户目录中的备份文件
+fn backup_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("读取备份目录失败: {}", e))? {
+        let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+        if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 从安装目录的 package.json 读取 Antigravity 版本（找不到时为空）
+pub fn detect_antigravity_version() -> Option<String> {
+    let mut executables: Vec<PathBuf> = path_config::get_custom_executable_path()
+        .ok()
+        .flatten()
+        .map(PathBuf::from)
+        .into_iter()
+        .collect();
+    executables.extend(AppPaths::antigravity_executable_paths());
+    executables.push(PathBuf::from("/usr/share/antigravity/antigravity"));
+
+    executables.iter().find_map(|exe| {
+        let resources = if exe.extension().is_some_and(|ext| ext == "app") {
+            exe.join("Contents").join("Resources")
+        } else {
+            exe.parent()?.join("resources")
+        };
+        let content = fs::read_to_string(resources.join("app").join("package.json")).ok()?;
+        let value: Value = serde_json::from_str(&content).ok()?;
+        value.get("version")?.as_str().map(str::to_string)
+    })
+}
+
+/// 生成格式变化的诊断信息
+fn diagnostic(version: Option<&str>, check: &SessionFormatCheck) -> String {
+    let detail = match &check.error {
+        Some(e) => format!("无法解码（{}）", e),
+        None => format!("缺少 {}", check.missing.join("、")),
+    };
+    format!(
+        "检测到 Antigravity 会话格式变化（Antigravity 版本 {}，格式指纹 {}）：{}。\
+         为避免注入不兼容的会话，已暂停所有改写账户备份和 Antigravity 数据库的操作（保存、导入、恢复、切换、登出等），请更新 Antigravity Agent",
+        version.unwrap_or("未知"),
+        check.fingerprint.as_deref().unwrap_or("无"),
+        detail
+    )
+}
+
+/// 检查实时会话；数据库无法读取不代表格式变化，与未登录一样跳过
+fn check_live() -> Option<SessionFormatCheck> {
+    match active::read_live_agent_state() {
+        Ok(state) => state
+            .map(|s| check_agent_state(LIVE_SOURCE, &s))
+            .filter(|check| check.signed_in),
+        Err(e) => {
+            tracing::warn!(target: "account::compat", error = %e, "读取实时会话失败，跳过实时检查");
+            None
+        }
+    }
+}
+
+/// 按实时会话的检查结果更新报告中的兼容性和写入保护
+fn apply_live(report: &mut SessionFormatReport, live: Option<SessionFormatCheck>) {
+    let incompatible = live.as_ref().filter(|check| !check.compatible);
+    report.compatible = incompatible.is_none();
+    report.writes_blocked = incompatible.is_some();
+    report.diagnostic =
+        incompatible.map(|check| diagnostic(report.antigravity_version.as_deref(), check));
+    report.live = live;
+    report.checked_at = Utc::now();
+}
+
+/// 检查实时数据库和账户目录中的全部备份，不更新写入保护
+pub fn check_all(accounts_dir: &Path) -> SessionFormatReport {
+    let backups_skipped = vault::ensure_readable().is_err();
+    let backups = if backups_skipped {
+        Vec::new()
+    } else {
+        match backup_files(accounts_dir) {
+            Ok(files) => files.iter().map(|path| check_backup_file(path)).collect(),
+            Err(e) => {
+                tracing::warn!(target: "account::compat", error = %e, "读取备份目录失败，跳过备份检查");
+                Vec::new()
+            }
+        }
+    };
+
+    let mut report = SessionFormatReport {
+        compatible: true,
+        antigravity_version: detect_antigravity_version(),
+        live: None,
+        backups,
+        backups_skipped,
+        writes_blocked: false,
+        diagnostic: None,
+        checked_at: Utc::now(),
+    };
+    apply_live(&mut report, check_live());
+    report
+}
+
+/// 保存检查结果，返回此前是否处于写入保护状态
+fn replace_report(report: &SessionFormatReport) -> bool {
+    LAST_REPORT
+        .lock()
+        .unwrap()
+        .replace(report.clone())
+        .is_some_and(|last| last.writes_blocked)
+}
+
+/// 记录检查结果并更新写入保护；保护状态变化时推送事件
+fn record(app: &AppHandle, report: &SessionFormatReport) {
+    let was_blocked = replace_report(report);
+
+    let incompatible_backups: Vec<&str> = report
+        .backups
+        .iter()
+        .filter(|check| !check.compatible)
+        .map(|check| check.source.as_str())
+        .collect();
+    if !incompatible_backups.is_empty() {
+        tracing::warn!(
+            target: "account::compat",
+            files = ?incompatible_backups,
+            "部分备份不符合预期的会话格式，切换到这些账户会被拒绝"
+        );
+    }
+
+    match &report.diagnostic {
+        Some(diagnostic) => tracing::error!(
+            target: "account::compat",
+            antigravity_version = ?report.antigravity_version,
+            diagnostic = %diagnostic,
+            "会话格式不兼容，已打开写入保护"
+        ),
+        None => tracing::info!(
+            target: "account::compat",
+            antigravity_version = ?report.antigravity_version,
+            backups = report.backups.len(),
+            "会话格式检查通过"
+        ),
+    }
+
+    if was_blocked != report.writes_blocked || report.writes_blocked {
+        if let Err(e) = SessionFormatChanged(report.clone()).emit(app) {
+            tracing::error!(target: "account::compat", error = %e, "推送会话格式事件失败");
+        }
+    }
+}
+
+/// 执行完整检查并更新写入保护
+pub fn run_check(app: &AppHandle, accounts_dir: &Path) -> SessionFormatReport {
+    let report = check_all(accounts_dir);
+    record(app, &report);
+    report
+}
+
+/// 最近一次检查结果
+pub fn last_report() -> Option<SessionFormatReport> {
+    LAST_REPORT.lock().unwrap().clone()
+}
+
+/// 写入保护打开时返回诊断信息；所有改写账户备份或 state.vscdb 的操作之前调用，只读查询不调用
+pub fn ensure_writes_allowed() -> Result<(), String> {
+    match LAST_REPORT.lock().unwrap().as_ref() {
+        Some(report) if report.writes_blocked => Err(report
+            .diagnostic
+            .clone()
+            .unwrap_or_else(|| "会话格式不兼容，已暂停写入".to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// 切换前重新检查实时会话和目标备份；任一不兼容都拒绝切换
+///
+/// 备份部分沿用上次完整检查的结果，只有目标备份会重新检查
+pub fn check_before_switch(app: &AppHandle, account: &AccountId) -> Result<(), String> {
+    let mut report = match last_report() {
+        Some(mut report) => {
+            report.antigravity_version = detect_antigravity_version();
+            report
+        }
+        None => check_all(&crate::directories::get_accounts_directory()),
+    };
+    apply_live(&mut report, check_live());
+    record(app, &report);
+    ensure_writes_allowed()?;
+
+    let target = check_backup_file(&account.backup_path()?);
+    if !target.compatible {
+        let reason = match &target.error {
+            Some(e) => e.clone(),
+            None => format!("缺少 {}", target.missing.join("、")),
+        };
+        return Err(format!(
+            "账户 {} 的备份不符合当前支持的会话格式（格式指纹 {}）：{}",
+            account,
+            target.fingerprint.as_deref().unwrap_or("无"),
+            reason
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::account_view::AccountProfile;
+    use crate::antigravity::backup::capture_backup;
+    use crate::antigravity::state_store::{MemoryStateStore, StateStore};
+    use base64::Engine;
+    use prost::Message;
+
+    const FULL_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/session_unknown_fields.b64"
+    ));
+    const MINIMAL_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/session_minimal.b64"
+    ));
+
+    #[test]
+    fn fixtures_match_expected_format() {
+        let full = check_agent_state("full", FULL_FIXTURE);
+        assert!(full.compatible, "{:?}", full);
+        assert_eq!(full.unknown_tags, vec![25, 26, 27, 30]);
+
+        let minimal = check_agent_state("minimal", MINIMAL_FIXTURE);
+        assert!(minimal.compatible, "{:?}", minimal);
+        assert_eq!(minimal.unknown_tags, vec![99]);
+        assert_ne!(full.fingerprint, minimal.fingerprint);
+    }
+
+    #[test]
+    fn moved_email_is_reported() {
+        // 模拟邮箱移出 UserContext 标签 7 的新格式
+        let msg = crate::proto::SessionResponse {
+            auth: Some(crate::proto::AuthInfo {
+                access_token: "ya29.moved".to_string(),
+                ..Default::default()
+            }),
+            context: Some(crate::proto::UserContext {
+                plan_name: "moved@example.com".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let agent_state = base64::engine::general_purpose::STANDARD.encode(msg.encode_to_vec());
+
+        let check = check_agent_state("moved", &agent_state);
+        assert!(check.signed_in);
+        assert!(!check.compatible);
+        assert_eq!(check.missing, vec!["context.email (19.7)".to_string()]);
+    }
+
+    #[test]
+    fn blocked_report_rejects_save_but_allows_read() {
+        let store = MemoryStateStore::with_items([(database::AGENT_STATE, FULL_FIXTURE.trim())]);
+        let db_path = Path::new("state.vscdb");
+        let mut report = SessionFormatReport {
+            compatible: false,
+            antigravity_version: None,
+            live: Some(check_agent_state(LIVE_SOURCE, "not base64!")),
+            backups: Vec::new(),
+            backups_skipped: false,
+            writes_blocked: true,
+            diagnostic: Some("会话格式变化".to_string()),
+            checked_at: Utc::now(),
+        };
+        replace_report(&report);
+
+        assert_eq!(capture_backup(&store, db_path).unwrap_err(), "会话格式变化");
+        // 查询当前账户只读取数据库，不受写入保护影响
+        let agent_state = store.get(database::AGENT_STATE).unwrap().unwrap();
+        let profile = AccountProfile::from_session(&decode_session_response(&agent_state).unwrap());
+        assert!(!profile.email.is_empty());
+
+        report.writes_blocked = false;
+        report.diagnostic = None;
+        replace_report(&report);
+        assert_eq!(
+            capture_backup(&store, db_path).unwrap().metadata.email,
+            profile.email
+        );
+    }
+
+    #[test]
+    fn signed_out_and_undecodable_sessions() {
+        let signed_out = check_agent_state("empty", "");
+        assert!(!signed_out.signed_in);
+
+        let broken = check_agent_state("broken", "not base64!");
+        assert!(broken.signed_in);
+        assert!(!broken.compatible);
+        assert!(broken.error.is_some());
+    }
+}
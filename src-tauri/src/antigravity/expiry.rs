@@ -0,0 +1,254 @@
+//! 令牌过期跟踪
+//! 根据备份中的 AuthMetadata.expiry_timestamp 计算每个账户的过期状态，
+//! 后台定时检查并在令牌即将过期或已过期时推送 account-expiring 事件
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tauri_specta::Event;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::vault;
+
+/// 后台检查间隔（秒）
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+/// 过期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ExpiryStatus {
+    Valid,
+    ExpiringSoon,
+    Expired,
+    /// 备份中没有过期时间
+    Unknown,
+}
+
+/// 账户的过期信息
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiryInfo {
+    pub status: ExpiryStatus,
+    /// 令牌过期时间（Unix 秒）
+    pub expiry_timestamp: Option<i64>,
+    /// 距离过期的秒数（已过期为负数）
+    pub seconds_remaining: Option<i64>,
+}
+
+impl ExpiryInfo {
+    /// 按给定时间和提前提醒窗口计算过期状态
+    pub fn compute(expiry_timestamp: Option<i64>, now: i64, warning_secs: i64) -> Self {
+        let seconds_remaining = expiry_timestamp.map(|ts| ts.saturating_sub(now));
+        let status = match seconds_remaining {
+            None => ExpiryStatus::Unknown,
+            Some(remaining) if remaining <= 0 => ExpiryStatus::Expired,
+            Some(remaining) if remaining <= warning_secs => ExpiryStatus::ExpiringSoon,
+            Some(_) => ExpiryStatus::Valid,
+        };
+        Self {
+            status,
+            expiry_timestamp,
+            seconds_remaining,
+        }
+    }
+
+    /// 按当前时间和设置中的提醒窗口计算
+    pub fn now(expiry_timestamp: Option<i64>, warning_minutes: u64) -> Self {
+        Self::compute(
+            expiry_timestamp,
+            Utc::now().timestamp(),
+            warning_secs(warning_minutes),
+        )
+    }
+}
+
+/// 提醒窗口换算为秒；设置值过大时视为无限长，不会溢出
+fn warning_secs(warning_minutes: u64) -> i64 {
+    i64::try_from(warning_minutes)
+        .ok()
+        .and_then(|m| m.checked_mul(60))
+        .unwrap_or(i64::MAX)
+}
+
+/// 账户令牌即将过期或已过期时推送给前端的事件（`account-expiring`）
+#[derive(Debug, Clone, Serialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountExpiring {
+    pub account_id: AccountId,
+    #[serde(flatten)]
+    pub expiry: ExpiryInfo,
+}
+
+/// 已提醒过的账户：状态 + 当时的过期时间
+type Notified = HashMap<AccountId, (ExpiryStatus, Option<i64>)>;
+
+/// 记录本次检查结果，返回是否需要提醒
+///
+/// 只有即将过期和已过期需要提醒；同一过期时间在每种状态下只提醒一次，
+/// 令牌被刷新（过期时间变化）或恢复有效后重新计算
+fn needs_notice(notified: &mut Notified, account_id: &AccountId, expiry: &ExpiryInfo) -> bool {
+    if !matches!(
+        expiry.status,
+        ExpiryStatus::ExpiringSoon | ExpiryStatus::Expired
+    ) {
+        notified.remove(account_id);
+        return false;
+    }
+
+    let key = (expiry.status, expiry.expiry_timestamp);
+    notified.insert(account_id.clone(), key) != Some(key)
+}
+
+/// 同步账户索引并取出每个账户的过期时间（在阻塞线程中执行，磁盘读取不占用异步运行时）
+async fn load_expiries(app: &AppHandle) -> Result<Vec<(AccountId, Option<i64>)>, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<crate::AppState>();
+        let mut index = state.antigravity_accounts.lock().unwrap();
+        index.refresh(&state.accounts_dir())?;
+        Ok(index
+            .accounts()
+            .into_iter()
+            .map(|a| (a.id.clone(), a.metadata.expiry_timestamp))
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("同步账户索引的任务失败: {}", e))?
+}
+
+/// 启动后台过期检查：每个账户的同一过期时间在每种状态下只提醒一次，
+/// 令牌被刷新（过期时间变化）后重新计算；保险库锁定期间跳过检查
+pub fn start_expiry_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut notified = Notified::new();
+        let mut interval =
+            tokio::time::interval(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+
+        loop {
+            interval.tick().await;
+
+            // 锁定时读不到任何备份，等待用户解锁即可，不算错误
+            if vault::ensure_readable().is_err() {
+                tracing::debug!(target: "account::expiry", "保险库已锁定，跳过过期检查");
+                continue;
+            }
+
+            let settings = app
+                .state::<crate::app_settings::AppSettingsManager>()
+                .get_settings();
+
+            let accounts = match load_expiries(&app).await {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    tracing::warn!(target: "account::expiry", error = %e, "同步账户索引失败");
+                    continue;
+                }
+            };
+
+            // 已删除的账户不再跟踪
+            notified.retain(|id, _| accounts.iter().any(|(a, _)| a == id));
+
+            for (account_id, expiry_timestamp) in accounts {
+                let expiry = ExpiryInfo::now(expiry_timestamp, settings.expiry_warning_minutes);
+                if !needs_notice(&mut notified, &account_id, &expiry) {
+                    continue;
+                }
+
+                tracing::info!(
+                    target: "account::expiry",
+                    account = %account_id,
+                    status = ?expiry.status,
+                    seconds_remaining = ?expiry.seconds_remaining,
+                    "账户令牌即将过期或已过期"
+                );
+
+                // 桌面通知只在过期之前提醒，避免启动时为每个旧备份弹窗
+                if settings.expiry_notifications_enabled
+                    && expiry.status == ExpiryStatus::ExpiringSoon
+                {
+                    let minutes = expiry.seconds_remaining.unwrap_or(0) / 60;
+                    let name = if settings.private_mode {
+                        crate::system_tray::tray::mask_email(account_id.as_str())
+                    } else {
+                        account_id.to_string()
+                    };
+                    if let Err(e) = app
+                        .notification()
+                        .builder()
+                        .title("Antigravity 账户即将过期")
+                        .body(format!("{} 的令牌将在 {} 分钟后过期", name, minutes))
+                        .show()
+                    {
+                        tracing::warn!(target: "account::expiry", error = %e, "发送桌面通知失败");
+                    }
+                }
+
+                let payload = AccountExpiring { account_id, expiry };
+                if let Err(e) = payload.emit(&app) {
+                    tracing::error!(target: "account::expiry", error = %e, "推送过期事件失败");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_warning_window_does_not_overflow() {
+        assert_eq!(warning_secs(30), 1800);
+        assert_eq!(warning_secs(u64::MAX), i64::MAX);
+        assert_eq!(warning_secs(i64::MAX as u64), i64::MAX);
+
+        let info = ExpiryInfo::compute(Some(2_000), 1_000, warning_secs(u64::MAX));
+        assert_eq!(info.status, ExpiryStatus::ExpiringSoon);
+        let info = ExpiryInfo::compute(Some(i64::MAX), -10, 60);
+        assert_eq!(info.seconds_remaining, Some(i64::MAX));
+        assert_eq!(info.status, ExpiryStatus::Valid);
+    }
+
+    #[test]
+    fn status_follows_warning_threshold() {
+        let status = |expiry: Option<i64>| ExpiryInfo::compute(expiry, 1_000, 600).status;
+        assert_eq!(status(None), ExpiryStatus::Unknown);
+        assert_eq!(status(Some(900)), ExpiryStatus::Expired);
+        assert_eq!(status(Some(1_000)), ExpiryStatus::Expired);
+        assert_eq!(status(Some(1_001)), ExpiryStatus::ExpiringSoon);
+        assert_eq!(status(Some(1_600)), ExpiryStatus::ExpiringSoon);
+        assert_eq!(status(Some(1_601)), ExpiryStatus::Valid);
+
+        // 提醒窗口为 0 时只在过期后提醒
+        assert_eq!(
+            ExpiryInfo::compute(Some(1_001), 1_000, 0).status,
+            ExpiryStatus::Valid
+        );
+    }
+
+    #[test]
+    fn each_expiry_is_notified_once_per_status() {
+        let account = AccountId::from_email("user@example.com").unwrap();
+        let mut notified = Notified::new();
+        let mut check = |expiry: i64, now: i64| {
+            needs_notice(
+                &mut notified,
+                &account,
+                &ExpiryInfo::compute(Some(expiry), now, 600),
+            )
+        };
+
+        assert!(!check(2_000, 1_000));
+        assert!(check(2_000, 1_500));
+        assert!(!check(2_000, 1_600));
+        // 进入已过期状态再提醒一次
+        assert!(check(2_000, 2_000));
+        assert!(!check(2_000, 2_100));
+        // 令牌刷新后过期时间变化，重新计算
+        assert!(!check(5_000, 2_100));
+        assert!(check(5_000, 4_500));
+    }
+}
@@ -0,0 +1,112 @@
+//! id_token（JWT）声明的本地解码
+//! 只解码载荷、不校验签名：声明仅用于展示和账户识别，不作为任何鉴权依据。
+//! `sub` 是 Google 账户的稳定标识，邮箱变更后仍保持不变
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+
+/// id_token 载荷中的常用声明
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct IdTokenClaims {
+    /// 账户的稳定标识
+    pub sub: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+    /// 签发方
+    pub iss: Option<String>,
+    /// 过期时间（Unix 秒）
+    pub exp: Option<i64>,
+    /// 签发时间（Unix 秒）
+    pub iat: Option<i64>,
+}
+
+/// 解码 JWT 载荷（第二段，Base64URL）中的声明
+pub fn decode_id_token(token: &str) -> Result<IdTokenClaims, String> {
+    let mut segments = token.trim().split('.');
+    let payload = match (segments.next(), segments.next(), segments.next()) {
+        (Some(_), Some(payload), Some(_)) if !payload.is_empty() => payload,
+        _ => return Err("id_token 不是有效的 JWT".to_string()),
+    };
+
+    let bytes = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|e| format!("id_token 载荷 Base64 解码失败: {}", e))?;
+    let value: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("id_token 载荷不是有效的 JSON: {}", e))?;
+    if !value.is_object() {
+        return Err("id_token 载荷不是 JSON 对象".to_string());
+    }
+
+    let string = |key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    // 数值声明偶尔以字符串形式出现
+    let number = |key: &str| match value.get(key)? {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+
+    Ok(IdTokenClaims {
+        sub: string("sub"),
+        email: string("email"),
+        email_verified: match value.get("email_verified") {
+            Some(Value::Bool(b)) => Some(*b),
+            Some(Value::String(s)) => s.parse().ok(),
+            _ => None,
+        },
+        iss: string("iss"),
+        exp: number("exp"),
+        iat: number("iat"),
+    })
+}
+
+impl IdTokenClaims {
+    /// 令牌中的邮箱与会话邮箱不一致（令牌中没有邮箱时不算不一致）
+    pub fn email_mismatch(&self, session_email: &str) -> bool {
+        self.email
+            .as_deref()
+            .is_some_and(|email| !email.eq_ignore_ascii_case(session_email.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(payload: &str) -> String {
+        format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.signature",
+            URL_SAFE_NO_PAD.encode(payload)
+        )
+    }
+
+    #[test]
+    fn decodes_payload_claims() {
+        let claims = decode_id_token(&token(
+            r#"{"iss":"https://accounts.google.com","sub":"1234567890","email":"User@example.com","email_verified":true,"exp":1767225600,"iat":"1767222000"}"#,
+        ))
+        .unwrap();
+        assert_eq!(claims.sub.as_deref(), Some("1234567890"));
+        assert_eq!(claims.iss.as_deref(), Some("https://accounts.google.com"));
+        assert_eq!(claims.email_verified, Some(true));
+        assert_eq!(claims.exp, Some(1767225600));
+        assert_eq!(claims.iat, Some(1767222000));
+        assert!(!claims.email_mismatch("user@example.com"));
+        assert!(claims.email_mismatch("other@example.com"));
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        assert!(decode_id_token("").is_err());
+        assert!(decode_id_token("eyJfixture.id.token").is_err());
+        assert!(decode_id_token(&token("[1, 2]")).is_err());
+    }
+}
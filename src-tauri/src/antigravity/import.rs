@@ -0,0 +1,278 @@
+//! 备份导入
+//! 导入前先与现有备份比较得出计划（新增/相同/较新/较旧/无效/现有备份无法读取），
+//! 再按所选策略决定写入、跳过还是另存为快照；dry-run 只返回计划，不写盘
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::active;
+use crate::antigravity::backup::{load_backup_file, write_backup_file, AccountBackup};
+use crate::antigravity::compat;
+use crate::antigravity::snapshot;
+
+/// 与现有备份冲突时的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStrategy {
+    /// 保留现有备份
+    Skip,
+    /// 用导入内容覆盖（旧内容先归档为快照）
+    #[default]
+    Overwrite,
+    /// 按令牌过期时间保留较新的一份
+    KeepNewer,
+    /// 保留现有备份，导入内容另存为该账户的快照
+    KeepBoth,
+}
+
+/// 导入条目与现有备份的比较结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStatus {
+    New,
+    Identical,
+    Newer,
+    Older,
+    Invalid,
+    /// 现有备份无法读取，没有可比较或归档的内容，为避免丢失数据不覆盖
+    ExistingUnreadable,
+}
+
+/// 对条目实际执行（dry-run 时为将要执行）的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Created,
+    Overwritten,
+    SavedAsSnapshot,
+    Skipped,
+    Unchanged,
+    Failed,
+}
+
+/// 单个条目的导入计划/结果
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEntry {
+    pub file_name: String,
+    pub email: Option<String>,
+    pub status: ImportStatus,
+    pub action: ImportAction,
+    /// 导入内容的令牌过期时间（Unix 秒）
+    pub incoming_expiry: Option<i64>,
+    /// 现有备份的令牌过期时间（Unix 秒）
+    pub existing_expiry: Option<i64>,
+    /// 无效、失败或未覆盖的原因
+    pub reason: Option<String>,
+}
+
+impl ImportEntry {
+    /// 是否真正写入了内容
+    pub fn is_restored(&self) -> bool {
+        matches!(
+            self.action,
+            ImportAction::Created | ImportAction::Overwritten | ImportAction::SavedAsSnapshot
+        )
+    }
+}
+
+/// 比较导入内容与现有备份；过期时间相同时再比较保存时间，完全相同时视为较旧，不覆盖
+fn classify(incoming: &AccountBackup, existing: Option<&AccountBackup>) -> ImportStatus {
+    let Some(existing) = existing else {
+        return ImportStatus::New;
+    };
+    if incoming.content_hash == existing.content_hash {
+        return ImportStatus::Identical;
+    }
+
+    let ordering = incoming
+        .metadata
+        .expiry_timestamp
+        .cmp(&existing.metadata.expiry_timestamp)
+        .then(incoming.saved_at.cmp(&existing.saved_at));
+    if ordering == Ordering::Greater {
+        ImportStatus::Newer
+    } else {
+        ImportStatus::Older
+    }
+}
+
+/// 按比较结果和策略决定操作
+fn decide(status: ImportStatus, strategy: ImportStrategy) -> ImportAction {
+    match (status, strategy) {
+        (ImportStatus::New, _) => ImportAction::Created,
+        (ImportStatus::Identical, _) => ImportAction::Unchanged,
+        (ImportStatus::Invalid, _) => ImportAction::Failed,
+        (ImportStatus::ExistingUnreadable, _) => ImportAction::Skipped,
+        (_, ImportStrategy::Skip) => ImportAction::Skipped,
+        (_, ImportStrategy::Overwrite) => ImportAction::Overwritten,
+        (ImportStatus::Newer, ImportStrategy::KeepNewer) => ImportAction::Overwritten,
+        (_, ImportStrategy::KeepNewer) => ImportAction::Skipped,
+        (_, ImportStrategy::KeepBoth) => ImportAction::SavedAsSnapshot,
+    }
+}
+
+/// 执行写入
+fn apply(
+    action: ImportAction,
+    account: &AccountId,
+    path: &Path,
+    incoming: &AccountBackup,
+    snapshot_depth: usize,
+) -> Result<(), String> {
+    match action {
+        ImportAction::Created => write_backup_file(path, incoming),
+        ImportAction::Overwritten => {
+            snapshot::archive_before_overwrite(account, path, incoming, snapshot_depth)?;
+            write_backup_file(path, incoming)
+        }
+        ImportAction::SavedAsSnapshot => {
+            snapshot::archive_backup(account, incoming)?;
+            // 快照关闭时至少保留一份，否则另存的内容会被立即删除
+            snapshot::prune_snapshots(account, snapshot_depth.max(1)).map(|_| ())
+        }
+        ImportAction::Skipped | ImportAction::Unchanged | ImportAction::Failed => Ok(()),
+    }
+}
+
+/// 从任意 state.vscdb（或 .vscdb.backup）只读提取账户备份
+///
+/// 用于从同事的机器或旧磁盘镜像中直接导入，无需先用它启动 Antigravity
+pub fn backup_from_database(db_path: &Path) -> Result<AccountBackup, String> {
+    if !db_path.is_file() {
+        return Err(format!("数据库文件不存在: {}", db_path.display()));
+    }
+    let file_name = db_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if !(file_name.ends_with(".vscdb") || file_name.ends_with(".vscdb.backup")) {
+        return Err(format!("不是 Antigravity 状态数据库: {}", file_name));
+    }
+
+    let agent_state = active::read_agent_state(db_path)?
+        .ok_or_else(|| format!("数据库中没有已登录的会话: {}", db_path.display()))?;
+    AccountBackup::new(agent_state, Some(db_path))
+}
+
+/// 导入一批已解析的备份
+///
+/// 目标文件以备份内容中的邮箱为准，不信任导入包里的文件名。
+/// 同一批次中重复的账户会与前面条目的结果比较，因此 dry-run 的计划与实际执行一致。
+/// 写入保护打开时只允许 dry-run；覆盖前的快照按 `snapshot_depth` 保留
+pub fn import_backups(
+    items: impl IntoIterator<Item = (String, Result<AccountBackup, String>)>,
+    dir: &Path,
+    strategy: ImportStrategy,
+    dry_run: bool,
+    snapshot_depth: usize,
+) -> Result<Vec<ImportEntry>, String> {
+    if !dry_run {
+        compat::ensure_writes_allowed()?;
+        fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+
+    // 本批次中已决定写入的当前备份
+    let mut pending: HashMap<AccountId, AccountBackup> = HashMap::new();
+    let mut entries = Vec::new();
+
+    for (file_name, backup) in items {
+        let resolved = backup.and_then(|backup| {
+            let account = AccountId::from_email(&backup.metadata.email)?;
+            let path = account.resolve_in(dir)?;
+            Ok((account, path, backup))
+        });
+        let (account, path, incoming) = match resolved {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                entries.push(ImportEntry {
+                    file_name,
+                    email: None,
+                    status: ImportStatus::Invalid,
+                    action: ImportAction::Failed,
+                    incoming_expiry: None,
+                    existing_expiry: None,
+                    reason: Some(e),
+                });
+                continue;
+            }
+        };
+
+        // 现有文件无法读取时既不能比较也无法归档为快照，按冲突处理，不覆盖
+        let (existing, unreadable) = match pending.get(&account) {
+            Some(backup) => (Some(backup.clone()), None),
+            None if path.exists() => match load_backup_file(&path) {
+                Ok(backup) => (Some(backup), None),
+                Err(e) => (None, Some(format!("现有备份无法读取，未覆盖: {}", e))),
+            },
+            None => (None, None),
+        };
+
+        let status = if unreadable.is_some() {
+            ImportStatus::ExistingUnreadable
+        } else {
+            classify(&incoming, existing.as_ref())
+        };
+        let mut action = decide(status, strategy);
+        let mut reason = unreadable;
+
+        if !dry_run {
+            if let Err(e) = apply(action, &account, &path, &incoming, snapshot_depth) {
+                action = ImportAction::Failed;
+                reason = Some(e);
+            }
+        }
+
+        entries.push(ImportEntry {
+            file_name,
+            email: Some(incoming.metadata.email.clone()),
+            status,
+            action,
+            incoming_expiry: incoming.metadata.expiry_timestamp,
+            existing_expiry: existing.and_then(|b| b.metadata.expiry_timestamp),
+            reason,
+        });
+        if matches!(action, ImportAction::Created | ImportAction::Overwritten) {
+            pending.insert(account, incoming);
+        }
+    }
+
+    tracing::info!(
+        target: "account::import",
+        strategy = ?strategy,
+        dry_run = dry_run,
+        total = entries.len(),
+        restored = entries.iter().filter(|e| e.is_restored()).count(),
+        "导入计划已处理"
+    );
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_existing_backup_is_never_replaced() {
+        for strategy in [
+            ImportStrategy::Skip,
+            ImportStrategy::Overwrite,
+            ImportStrategy::KeepNewer,
+            ImportStrategy::KeepBoth,
+        ] {
+            assert_eq!(
+                decide(ImportStatus::ExistingUnreadable, strategy),
+                ImportAction::Skipped
+            );
+        }
+        assert_eq!(
+            decide(ImportStatus::New, ImportStrategy::Skip),
+            ImportAction::Created
+        );
+    }
+}
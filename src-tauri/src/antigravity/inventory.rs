@@ -0,0 +1,273 @@
+//! 脱敏账户清单导出
+//! 只导出账户、套餐和过期时间等元数据，用于审计或分享给同事；
+//! 访问令牌、ID 令牌、原始用户 ID 和所有不透明字节字段都会被移除，导出结果无法作为凭据导入
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+use crate::antigravity::backup::AccountBackup;
+use crate::antigravity::validation;
+
+/// 清单格式标识（导入时据此拒绝）
+pub const INVENTORY_FORMAT: &str = "antigravity-agent-inventory";
+
+/// SessionResponse 中需要移除的凭据字段
+const SECRET_FIELDS: &[&str] = &["access_token", "id_token", "user_id_raw_base64"];
+
+/// 清单输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// 单个账户的脱敏记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryRecord {
+    pub email: String,
+    pub plan_name: Option<String>,
+    pub plan_slug: Option<String>,
+    /// 令牌过期时间（Unix 秒）
+    pub expiry_timestamp: Option<i64>,
+    pub saved_at: DateTime<Utc>,
+    /// 脱敏后的 SessionResponse（仅 JSON 格式输出）
+    pub session: Value,
+}
+
+/// JSON 清单
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Inventory {
+    format: &'static str,
+    redacted: bool,
+    private_mode: bool,
+    agent_version: &'static str,
+    generated_at: DateTime<Utc>,
+    accounts: Vec<InventoryRecord>,
+}
+
+/// 递归移除凭据字段和所有不透明字节字段（`*_base64`）
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, _| {
+                !SECRET_FIELDS.contains(&key.as_str()) && !key.ends_with("_base64")
+            });
+            map.values_mut().for_each(redact);
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// 隐私模式下打码 SessionResponse 中的邮箱
+fn mask_session_email(session: &mut Value) {
+    if let Some(email) = session.pointer_mut("/context/email") {
+        if let Some(masked) = email.as_str().map(crate::system_tray::tray::mask_email) {
+            *email = Value::String(masked);
+        }
+    }
+}
+
+/// 由备份生成脱敏记录
+pub fn record(backup: &AccountBackup, private_mode: bool) -> Result<InventoryRecord, String> {
+    let mut session = validation::validate_backup(backup)?.session;
+    redact(&mut session);
+    if private_mode {
+        mask_session_email(&mut session);
+    }
+
+    let email = if private_mode {
+        crate::system_tray::tray::mask_email(&backup.metadata.email)
+    } else {
+        backup.metadata.email.clone()
+    };
+
+    Ok(InventoryRecord {
+        email,
+        plan_name: backup.metadata.plan_name.clone(),
+        plan_slug: backup.metadata.plan_slug.clone(),
+        expiry_timestamp: backup.metadata.expiry_timestamp,
+        saved_at: backup.saved_at,
+        session,
+    })
+}
+
+/// 以这些字符开头的字段会被电子表格当作公式执行
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// CSV 字段转义；可能被当作公式的字段前加单引号，防止公式注入
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+fn to_csv(records: &[InventoryRecord]) -> String {
+    let mut out = String::from("email,plan_name,plan_slug,expiry_timestamp,expires_at,saved_at\n");
+    for record in records {
+        let expires_at = record
+            .expiry_timestamp
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+        let fields = [
+            record.email.clone(),
+            record.plan_name.clone().unwrap_or_default(),
+            record.plan_slug.clone().unwrap_or_default(),
+            record
+                .expiry_timestamp
+                .map(|ts| ts.to_string())
+                .unwrap_or_default(),
+            expires_at,
+            record.saved_at.to_rfc3339(),
+        ];
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// 生成脱敏清单并写入磁盘，返回导出的账户数
+pub fn export(
+    path: &Path,
+    backups: &[AccountBackup],
+    format: InventoryFormat,
+    private_mode: bool,
+) -> Result<usize, String> {
+    let mut records = Vec::with_capacity(backups.len());
+    for backup in backups {
+        match record(backup, private_mode) {
+            Ok(record) => records.push(record),
+            Err(e) => {
+                tracing::warn!(target: "backup::inventory", error = %e, "跳过无法解析的备份")
+            }
+        }
+    }
+
+    let count = records.len();
+    let content = match format {
+        InventoryFormat::Json => {
+            let inventory = Inventory {
+                format: INVENTORY_FORMAT,
+                redacted: true,
+                private_mode,
+                agent_version: env!("CARGO_PKG_VERSION"),
+                generated_at: Utc::now(),
+                accounts: records,
+            };
+            serde_json::to_string_pretty(&inventory)
+                .map_err(|e| format!("序列化账户清单失败: {}", e))?
+        }
+        InventoryFormat::Csv => to_csv(&records),
+    };
+
+    fs::write(path, content).map_err(|e| format!("写入账户清单失败 {}: {}", path.display(), e))?;
+    tracing::info!(
+        target: "backup::inventory",
+        count = count,
+        format = ?format,
+        private_mode = private_mode,
+        "脱敏账户清单已导出"
+    );
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::backup::test_agent_state;
+    use serde_json::json;
+
+    fn backup(email: &str) -> AccountBackup {
+        AccountBackup::new(test_agent_state(email, 1_700_000_000), None).unwrap()
+    }
+
+    #[test]
+    fn redact_removes_credentials_and_opaque_bytes() {
+        let mut value = json!({
+            "auth": { "access_token": "secret", "id_token": "secret", "meta": { "expiry_timestamp": 1 } },
+            "context": { "email": "a@example.com", "user_id_raw_base64": "AAAA" },
+            "items": [{ "blob_base64": "AAAA", "name": "kept" }],
+            "avatar_base64": "AAAA"
+        });
+        redact(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "auth": { "meta": { "expiry_timestamp": 1 } },
+                "context": { "email": "a@example.com" },
+                "items": [{ "name": "kept" }]
+            })
+        );
+
+        let record = record(&backup("alice@example.com"), false).unwrap();
+        assert!(record.session.pointer("/auth/access_token").is_none());
+        assert_eq!(record.expiry_timestamp, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn private_mode_masks_every_email() {
+        let record = record(&backup("alice@example.com"), true).unwrap();
+        assert_eq!(record.email, "a***e@example.com");
+        assert_eq!(
+            record.session.pointer("/context/email"),
+            Some(&Value::from("a***e@example.com"))
+        );
+        assert!(!serde_json::to_string(&record).unwrap().contains("alice"));
+    }
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        // 公式注入
+        assert_eq!(csv_field(r#"=HYPERLINK("x")"#), r#""'=HYPERLINK(""x"")""#);
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-1"), "'-1");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+    }
+
+    #[test]
+    fn inventory_cannot_be_imported_as_bundle() {
+        let dir =
+            std::env::temp_dir().join(format!("antigravity-inventory-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("inventory.json");
+
+        assert_eq!(
+            export(
+                &path,
+                &[backup("alice@example.com")],
+                InventoryFormat::Json,
+                false
+            )
+            .unwrap(),
+            1
+        );
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("token-alice"));
+
+        let err = crate::antigravity::bundle::read(&path, None).unwrap_err();
+        assert!(err.contains("脱敏的账户清单"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
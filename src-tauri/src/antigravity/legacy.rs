@@ -0,0 +1,87 @@
+//! 旧版账户目录的遗留文件
+//! 目录迁移会把旧目录中的文件改名为 .bak，新目录已有同名文件时不会写入，
+//! 这些凭据因此滞留在旧目录中；这里负责扫描、按导入冲突策略合并，并清理已合并的文件
+
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::antigravity::backup::AccountBackup;
+use crate::antigravity::import::{self, ImportAction, ImportEntry, ImportStrategy};
+
+/// 旧目录中需要处理的文件：迁移留下的 .bak，以及改名失败仍为 .json 的文件
+pub fn legacy_files() -> Result<Vec<PathBuf>, String> {
+    let Some(dir) = crate::directories::get_legacy_accounts_directory() else {
+        return Ok(Vec::new());
+    };
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| format!("读取旧账户目录失败: {}", e))? {
+        let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+        let is_leftover = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("bak") || ext.eq_ignore_ascii_case("json"));
+        if path.is_file() && is_leftover {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 读取遗留文件（旧目录中的文件始终是明文的旧版格式）
+fn read_legacy_file(path: &Path) -> Result<AccountBackup, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取遗留文件失败 {}: {}", path.display(), e))?;
+    let value = serde_json::from_str(&content)
+        .map_err(|e| format!("解析 JSON 失败 {}: {}", path.display(), e))?;
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    AccountBackup::from_value(value, modified).map(|(backup, _)| backup)
+}
+
+/// 扫描或合并遗留文件，返回每个文件与当前账户目录的比较结果
+///
+/// `dry_run` 时只返回计划；`cleanup` 为 true 时删除已合并（或与现有备份相同）的遗留文件，
+/// 被跳过和失败的文件保留，以免丢失尚未保存的凭据
+pub fn merge(
+    accounts_dir: &Path,
+    strategy: ImportStrategy,
+    dry_run: bool,
+    cleanup: bool,
+    snapshot_depth: usize,
+) -> Result<Vec<ImportEntry>, String> {
+    let files = legacy_files()?;
+    let items = files.iter().map(|path| {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        (file_name, read_legacy_file(path))
+    });
+    let entries = import::import_backups(items, accounts_dir, strategy, dry_run, snapshot_depth)?;
+
+    if cleanup && !dry_run {
+        let mut removed = 0;
+        for (path, entry) in files.iter().zip(&entries) {
+            if !(entry.is_restored() || entry.action == ImportAction::Unchanged) {
+                continue;
+            }
+            match fs::remove_file(path) {
+                Ok(()) => removed += 1,
+                Err(e) => {
+                    tracing::warn!(target: "account::legacy", file = %path.display(), error = %e, "删除遗留文件失败")
+                }
+            }
+        }
+        tracing::info!(target: "account::legacy", removed = removed, "已清理合并完成的遗留文件");
+    }
+
+    Ok(entries)
+}
@@ -1,5 +1,26 @@
 pub mod account;
+pub mod account_id;
+pub mod account_index;
+pub mod account_view;
+pub mod active;
+pub mod audit;
+pub mod auto_refresh;
+pub mod backup;
+pub mod bundle;
 pub mod cleanup;
+pub mod compat;
+pub mod expiry;
+pub mod id_token;
+pub mod import;
+pub mod inventory;
+pub mod legacy;
+pub mod model_catalog;
 pub mod path_config;
+pub mod quarantine;
 pub mod restore;
+pub mod session_edit;
+pub mod snapshot;
 pub mod starter;
+pub mod state_store;
+pub mod validation;
+pub mod vault;
@@ -0,0 +1,212 @@
+//! 跨账户的模型目录
+//! 把每个已保存账户的 UserContext.models 与套餐信息整理成统一格式，
+//! 并按模型、按套餐汇总，方便直接挑选能用某个模型的账户，而不必逐个切换查看
+
+use serde::Serialize;
+use specta::Type;
+use std::collections::BTreeMap;
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::account_view::AccountProfile;
+
+/// 单个账户的模型与套餐
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountModelEntry {
+    pub account_id: AccountId,
+    pub email: String,
+    /// 套餐标识（小写），会话中没有套餐配置时为空
+    pub plan_slug: Option<String>,
+    pub plan_name: Option<String>,
+    /// 可用模型（去除首尾空白和重复项，保持会话中的顺序）
+    pub available: Vec<String>,
+    pub recommended: Vec<String>,
+}
+
+/// 某个模型在各账户中的可用情况
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAvailability {
+    pub model: String,
+    /// 可以使用该模型的账户
+    pub accounts: Vec<AccountId>,
+    /// 把该模型列为推荐模型的账户
+    pub recommended_by: Vec<AccountId>,
+}
+
+/// 同一套餐下的账户
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanTier {
+    /// 为空表示会话中没有套餐信息
+    pub plan_slug: Option<String>,
+    pub plan_name: Option<String>,
+    pub accounts: Vec<AccountId>,
+}
+
+/// 模型目录与跨账户对比
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCatalog {
+    pub accounts: Vec<AccountModelEntry>,
+    /// 按可用账户数从多到少排列，数量相同时按模型名称排列
+    pub models: Vec<ModelAvailability>,
+    /// 按套餐标识排列，没有套餐信息的账户排在最后
+    pub plans: Vec<PlanTier>,
+}
+
+/// 去除首尾空白、空项和重复项，保持原有顺序
+fn normalize_names(names: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for name in names.iter().map(|name| name.trim()) {
+        if !name.is_empty() && !normalized.iter().any(|n| n == name) {
+            normalized.push(name.to_string());
+        }
+    }
+    normalized
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl AccountModelEntry {
+    pub fn new(account_id: AccountId, profile: &AccountProfile) -> Self {
+        let plan = profile.plan.as_ref();
+        Self {
+            account_id,
+            email: profile.email.clone(),
+            plan_slug: plan
+                .and_then(|p| non_empty(&p.slug))
+                .map(|slug| slug.to_lowercase()),
+            plan_name: non_empty(&profile.plan_name),
+            available: normalize_names(&profile.models.available),
+            recommended: normalize_names(&profile.models.recommended),
+        }
+    }
+}
+
+impl ModelCatalog {
+    /// 由账户列表（按索引顺序）构建目录
+    pub fn build(accounts: Vec<AccountModelEntry>) -> Self {
+        let mut models: BTreeMap<&str, ModelAvailability> = BTreeMap::new();
+        let mut plans: BTreeMap<Option<&str>, PlanTier> = BTreeMap::new();
+
+        for entry in &accounts {
+            for model in &entry.available {
+                models
+                    .entry(model.as_str())
+                    .or_insert_with(|| ModelAvailability {
+                        model: model.clone(),
+                        accounts: Vec::new(),
+                        recommended_by: Vec::new(),
+                    })
+                    .accounts
+                    .push(entry.account_id.clone());
+            }
+            // 推荐但不在可用列表中的模型不计入可用账户
+            for model in &entry.recommended {
+                if let Some(availability) = models.get_mut(model.as_str()) {
+                    if entry.available.contains(model) {
+                        availability.recommended_by.push(entry.account_id.clone());
+                    }
+                }
+            }
+
+            plans
+                .entry(entry.plan_slug.as_deref())
+                .or_insert_with(|| PlanTier {
+                    plan_slug: entry.plan_slug.clone(),
+                    plan_name: entry.plan_name.clone(),
+                    accounts: Vec::new(),
+                })
+                .accounts
+                .push(entry.account_id.clone());
+        }
+
+        let mut models: Vec<ModelAvailability> = models.into_values().collect();
+        models.sort_by_key(|model| std::cmp::Reverse(model.accounts.len()));
+
+        // BTreeMap 中 None 排在最前，这里移到最后
+        let mut plans: Vec<PlanTier> = plans.into_values().collect();
+        plans.sort_by_key(|plan| plan.plan_slug.is_none());
+
+        Self {
+            accounts,
+            models,
+            plans,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::account_view::{AccountModels, AccountPlan};
+
+    fn entry(
+        email: &str,
+        slug: &str,
+        available: &[&str],
+        recommended: &[&str],
+    ) -> AccountModelEntry {
+        let profile = AccountProfile {
+            email: email.to_string(),
+            plan_name: slug.to_uppercase(),
+            plan: (!slug.is_empty()).then(|| AccountPlan {
+                slug: slug.to_string(),
+                name: slug.to_uppercase(),
+                description: String::new(),
+                upgrade_url: String::new(),
+                upgrade_msg: String::new(),
+            }),
+            models: AccountModels {
+                available: available.iter().map(|s| s.to_string()).collect(),
+                recommended: recommended.iter().map(|s| s.to_string()).collect(),
+            },
+            ..Default::default()
+        };
+        AccountModelEntry::new(AccountId::from_email(email).unwrap(), &profile)
+    }
+
+    #[test]
+    fn names_are_normalized() {
+        let e = entry(
+            "a@example.com",
+            "Pro",
+            &[" gemini-3 ", "gemini-3", "", "claude"],
+            &[],
+        );
+        assert_eq!(e.available, vec!["gemini-3", "claude"]);
+        assert_eq!(e.plan_slug.as_deref(), Some("pro"));
+    }
+
+    #[test]
+    fn catalog_groups_by_model_and_plan() {
+        let catalog = ModelCatalog::build(vec![
+            entry("a@example.com", "pro", &["gemini-3", "claude"], &["claude"]),
+            entry("b@example.com", "free", &["gemini-3"], &["claude"]),
+            entry("c@example.com", "", &[], &[]),
+        ]);
+
+        let ids = |ids: &[AccountId]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        assert_eq!(catalog.models[0].model, "gemini-3");
+        assert_eq!(
+            ids(&catalog.models[0].accounts),
+            vec!["a@example.com", "b@example.com"]
+        );
+        assert_eq!(catalog.models[1].model, "claude");
+        assert_eq!(
+            ids(&catalog.models[1].recommended_by),
+            vec!["a@example.com"]
+        );
+
+        let slugs: Vec<_> = catalog
+            .plans
+            .iter()
+            .map(|p| p.plan_slug.as_deref())
+            .collect();
+        assert_eq!(slugs, vec![Some("free"), Some("pro"), None]);
+    }
+}
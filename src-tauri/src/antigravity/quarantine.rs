@@ -0,0 +1,337 @@
+//! 损坏备份隔离区
+//! 内容确实损坏（无法解析、解密或校验）的备份文件会被移到 antigravity-quarantine/，不再影响账户列表；
+//! 用户修复后可以从这里重新导入。读取失败、保险库锁定、格式版本较新或刚被修改的文件只跳过，不移动
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::antigravity::account::decode_jetski_state_proto;
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::{
+    read_backup_file, write_backup_file, AccountBackup, BACKUP_SCHEMA_VERSION,
+};
+use crate::antigravity::compat;
+use crate::antigravity::vault;
+
+/// 修改时间在此之内的文件可能仍在写入（例如同步盘正在下载），读取失败时先不隔离
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+
+/// 读取账户目录时发现的问题文件
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountFileIssue {
+    /// 原始文件名
+    pub file_name: String,
+    pub reason: String,
+    /// 隔离后的文件名（移动失败时为空，文件仍留在账户目录中）
+    pub quarantined_as: Option<String>,
+}
+
+/// 隔离区中的文件
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedFile {
+    pub file_name: String,
+    pub quarantined_at: DateTime<Utc>,
+    pub size: u64,
+    /// 当前仍无法导入的原因；为空表示文件已修复，可以重新导入
+    pub reason: Option<String>,
+}
+
+/// 隔离区文件名只允许由时间戳前缀和清洗后的原文件名组成
+fn validate_quarantine_name(file_name: &str) -> Result<(), String> {
+    let valid = file_name.ends_with(".json")
+        && !file_name.starts_with('.')
+        && !file_name.contains("..")
+        && file_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '-' | '+' | '%'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的隔离文件名: {}", file_name))
+    }
+}
+
+/// 获取隔离文件路径（文件必须存在）
+pub fn quarantined_path(file_name: &str) -> Result<PathBuf, String> {
+    validate_quarantine_name(file_name)?;
+    let path = crate::directories::get_quarantine_directory().join(file_name);
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(format!("隔离文件不存在: {}", file_name))
+    }
+}
+
+/// 把问题文件移入隔离区，返回隔离后的文件名
+pub fn quarantine_file(path: &Path) -> Result<String, String> {
+    let dir = crate::directories::get_quarantine_directory();
+    fs::create_dir_all(&dir).map_err(|e| format!("创建隔离目录失败: {}", e))?;
+
+    // 原文件名可能本身就不合法，清洗后加上时间戳前缀避免重名
+    let original = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let cleaned: String = original
+        .trim_start_matches('.')
+        .replace("..", "_")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '-' | '+' | '%') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let file_name = format!(
+        "{}-{}.json",
+        Utc::now().format("%Y%m%dT%H%M%S%3fZ"),
+        cleaned
+    );
+
+    fs::rename(path, dir.join(&file_name))
+        .map_err(|e| format!("移动文件到隔离区失败 {}: {}", path.display(), e))?;
+
+    tracing::warn!(
+        target: "account::quarantine",
+        file = %path.display(),
+        quarantined_as = %file_name,
+        "已隔离损坏的备份文件"
+    );
+    Ok(file_name)
+}
+
+/// 读取失败的备份是否确实损坏、应当隔离
+///
+/// 文件读取失败、保险库锁定或未开启、备份或保险库格式版本较新、文件刚被修改时都不算损坏；
+/// 重新读取成功（上次失败是暂时性的）也不算
+pub fn is_corrupt(path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    if !vault::can_decrypt(&content) {
+        return false;
+    }
+    let recently_modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|modified| modified.elapsed().ok().is_none_or(|age| age < SETTLE_TIME))
+        .unwrap_or(true);
+    if recently_modified {
+        return false;
+    }
+
+    // 格式版本较新的备份升级 Antigravity Agent 后即可读取
+    let newer_schema = vault::read_account_file(path)
+        .ok()
+        .and_then(|(plaintext, _)| serde_json::from_str::<Value>(&plaintext).ok())
+        .and_then(|value| value.get("schema_version")?.as_u64())
+        .is_some_and(|version| version > BACKUP_SCHEMA_VERSION as u64);
+    !newer_schema && check_backup_file(path).is_err()
+}
+
+/// 记录问题并尝试隔离文件
+pub fn report_issue(path: &Path, reason: String) -> AccountFileIssue {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let quarantined_as = match quarantine_file(path) {
+        Ok(name) => Some(name),
+        Err(e) => {
+            tracing::error!(target: "account::quarantine", file = %file_name, error = %e, "隔离备份文件失败");
+            None
+        }
+    };
+
+    AccountFileIssue {
+        file_name,
+        reason,
+        quarantined_as,
+    }
+}
+
+/// 读取并完整校验一个备份文件（信封 + protobuf 解码），不会改写文件
+pub fn check_backup_file(path: &Path) -> Result<AccountBackup, String> {
+    let backup = read_backup_file(path)?;
+    decode_jetski_state_proto(&backup.agent_state)?;
+    Ok(backup)
+}
+
+/// 列出隔离区中的文件（最新的在前）
+pub fn list_quarantined() -> Result<Vec<QuarantinedFile>, String> {
+    let dir = crate::directories::get_quarantine_directory();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| format!("读取隔离目录失败: {}", e))? {
+        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if validate_quarantine_name(&file_name).is_err() {
+            continue;
+        }
+
+        let meta = entry
+            .metadata()
+            .map_err(|e| format!("读取文件信息失败 {}: {}", file_name, e))?;
+        files.push(QuarantinedFile {
+            quarantined_at: meta
+                .modified()
+                .map(DateTime::from)
+                .unwrap_or_else(|_| Utc::now()),
+            size: meta.len(),
+            reason: check_backup_file(&path).err(),
+            file_name,
+        });
+    }
+
+    files.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+    Ok(files)
+}
+
+/// 隔离文件名去掉时间戳前缀后的原文件名
+pub fn original_file_name(quarantined_name: &str) -> &str {
+    quarantined_name
+        .split_once('-')
+        .map_or(quarantined_name, |(_, original)| original)
+}
+
+/// 隔离区中的全部文件（包括后台同步时隔离的），作为问题列出
+pub fn quarantined_issues() -> Result<Vec<AccountFileIssue>, String> {
+    Ok(list_quarantined()?
+        .into_iter()
+        .map(|file| AccountFileIssue {
+            file_name: original_file_name(&file.file_name).to_string(),
+            reason: file
+                .reason
+                .unwrap_or_else(|| "文件已修复，可以重新导入".to_string()),
+            quarantined_as: Some(file.file_name),
+        })
+        .collect())
+}
+
+/// 重新导入隔离文件：校验通过后按邮箱写回账户目录，并从隔离区删除
+pub fn repair_quarantined(
+    file_name: &str,
+    accounts_dir: &Path,
+    snapshot_depth: usize,
+) -> Result<AccountId, String> {
+    compat::ensure_writes_allowed()?;
+    let path = quarantined_path(file_name)?;
+    let backup = check_backup_file(&path)?;
+
+    let account = AccountId::from_email(&backup.metadata.email)?;
+    fs::create_dir_all(accounts_dir).map_err(|e| format!("创建账户目录失败: {}", e))?;
+    let account_file = account.resolve_in(accounts_dir)?;
+
+    crate::antigravity::snapshot::archive_before_overwrite(
+        &account,
+        &account_file,
+        &backup,
+        snapshot_depth,
+    )?;
+    write_backup_file(&account_file, &backup)?;
+    fs::remove_file(&path).map_err(|e| format!("删除隔离文件失败: {}", e))?;
+
+    tracing::info!(target: "account::quarantine", file = %file_name, account = %account, "已重新导入隔离文件");
+    Ok(account)
+}
+
+/// 删除隔离文件
+pub fn delete_quarantined(file_name: &str) -> Result<(), String> {
+    let path = quarantined_path(file_name)?;
+    fs::remove_file(&path).map_err(|e| format!("删除隔离文件失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    /// 在系统临时目录中写入测试文件，`settled` 为真时把修改时间调到一分钟前
+    fn temp_file(name: &str, content: &str, settled: bool) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "antigravity-quarantine-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        fs::write(&path, content).unwrap();
+        if settled {
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(60))
+                .unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn only_settled_unreadable_content_is_corrupt() {
+        let garbage = temp_file("garbage", "{ not json", true);
+        assert!(is_corrupt(&garbage));
+
+        // 可能仍在写入
+        let fresh = temp_file("fresh", "{ not json", false);
+        assert!(!is_corrupt(&fresh));
+
+        // 新版本写入的备份
+        let newer = temp_file(
+            "newer",
+            &format!(r#"{{"schema_version": {}}}"#, BACKUP_SCHEMA_VERSION + 1),
+            true,
+        );
+        assert!(!is_corrupt(&newer));
+
+        // 加密文件版本较新（或保险库未解锁）
+        let sealed = temp_file(
+            "sealed",
+            r#"{"format":"antigravity-agent-vault","version":99,"nonce":"","ciphertext":""}"#,
+            true,
+        );
+        assert!(!is_corrupt(&sealed));
+
+        assert!(!is_corrupt(
+            &std::env::temp_dir().join("antigravity-quarantine-missing.json")
+        ));
+
+        for path in [garbage, fresh, newer, sealed] {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn checking_a_legacy_file_does_not_rewrite_it() {
+        let state = crate::antigravity::backup::test_agent_state("user@example.com", 0);
+        let legacy =
+            serde_json::json!({ crate::constants::database::AGENT_STATE: state }).to_string();
+        let path = temp_file("legacy", &legacy, true);
+
+        let backup = check_backup_file(&path).unwrap();
+        assert_eq!(backup.metadata.email, "user@example.com");
+        assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
+        assert!(!is_corrupt(&path));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn original_name_drops_timestamp_prefix() {
+        assert_eq!(
+            original_file_name("20250101T000000000Z-user@example.com.json"),
+            "user@example.com.json"
+        );
+    }
+}
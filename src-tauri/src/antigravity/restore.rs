@@ -1,15 +1,161 @@
 // Antigravity 用户数据恢复模块
 // 负责将备份数据恢复到 Antigravity 应用数据库
 
-use rusqlite::{params, Connection};
-use serde_json::Value;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // 导入相关模块
+use crate::antigravity::backup::{load_backup_file, AccountBackup};
+use crate::antigravity::cleanup::{clear_state, CLEARED_KEYS};
+use crate::antigravity::compat;
+use crate::antigravity::state_store::{apply_with_snapshot, SqliteStateStore, StateStore};
+use crate::antigravity::validation;
 use crate::constants::database;
 use crate::platform;
 
+/// 恢复时会修改的键
+const RESTORED_KEYS: [&str; 2] = [database::AGENT_STATE, database::AUTH_STATUS];
+
+/// 向单个状态库写回会话：写入 AGENT_STATE 并删除 AUTH_STATUS，返回修改的项数
+/// 任何一步失败都直接返回错误，由调用方的事务撤销已做的修改
+pub fn restore_state(store: &mut dyn StateStore, agent_state: &str) -> Result<usize, String> {
+    store.put(database::AGENT_STATE, agent_state)?;
+    tracing::debug!(target: "restore::database", key = %database::AGENT_STATE, "注入数据成功");
+
+    let removed = store.delete(database::AUTH_STATUS)?;
+    tracing::debug!(target: "restore::database", removed, "已删除 antigravityAuthStatus");
+
+    Ok(1 + removed)
+}
+
+/// 切换账户：清除旧登录信息后写入新会话（在同一个事务中执行），返回修改的项数
+pub fn switch_state(store: &mut dyn StateStore, agent_state: &str) -> Result<usize, String> {
+    Ok(clear_state(store)? + restore_state(store, agent_state)?)
+}
+
+/// 在主库和账户库（存在时）上分别以一个事务执行 `ops`，返回两者修改的项数
+///
+/// 两个文件无法放进同一个事务：账户库失败时用主库修改前的原值撤销主库，
+/// 对调用方而言两个文件要么都已修改，要么都保持原样
+fn apply_to_databases(
+    db_path: &Path,
+    keys: &[&str],
+    ops: &dyn Fn(&mut dyn StateStore) -> Result<usize, String>,
+) -> Result<(usize, Option<usize>), String> {
+    let apply = |path: &Path, db_name: &str| {
+        tracing::info!(target: "restore::database", db_name = %db_name, "开始写入数据库");
+        let mut store = SqliteStateStore::open(path)?;
+        apply_with_snapshot(&mut store, keys, |tx| ops(tx))
+            .map_err(|e| format!("写入 {} 失败: {}", db_name, e))
+    };
+
+    let (main_count, main_snapshot) = apply(db_path, "state.vscdb")?;
+
+    let backup_db = db_path.with_extension("vscdb.backup");
+    if !backup_db.exists() {
+        return Ok((main_count, None));
+    }
+    match apply(&backup_db, "state.vscdb.backup") {
+        Ok((count, _)) => Ok((main_count, Some(count))),
+        Err(e) => {
+            // 撤销主库已提交的修改
+            tracing::error!(target: "restore::database", error = %e, "写入账户库失败，撤销主库修改");
+            match SqliteStateStore::open(db_path)
+                .and_then(|mut store| main_snapshot.restore(&mut store))
+            {
+                Ok(()) => Err(format!("{}（主库已撤销，未做任何修改）", e)),
+                Err(undo) => Err(format!("{}；撤销主库修改失败: {}", e, undo)),
+            }
+        }
+    }
+}
+
+/// 已读取并校验、尚未写入数据库的账户恢复
+///
+/// 切换账户时先构建它再关闭 Antigravity，备份无效时不会动到任何数据
+pub struct PendingRestore {
+    backup: AccountBackup,
+    db_path: PathBuf,
+}
+
+impl PendingRestore {
+    /// 读取并校验账户文件，定位 Antigravity 状态数据库
+    pub fn load(account_file_path: &Path) -> Result<Self, String> {
+        println!("📂 账户文件: {}", account_file_path.display());
+
+        if !account_file_path.exists() {
+            return Err(format!("账户文件不存在: {}", account_file_path.display()));
+        }
+
+        // 会话格式已变化时不向数据库写入任何内容
+        compat::ensure_writes_allowed()?;
+
+        let backup = load_backup_file(account_file_path)?;
+
+        // 注入数据库前再校验一次，无效的会话数据绝不能写入 state.vscdb
+        validation::validate_backup(&backup).map_err(|e| format!("拒绝恢复该账户: {}", e))?;
+
+        println!("✅ 账户文件读取成功");
+
+        let db_path = match platform::get_antigravity_db_path() {
+            Some(p) => p,
+            None => {
+                let possible_paths = platform::get_all_antigravity_db_paths();
+                if possible_paths.is_empty() {
+                    return Err("未找到 Antigravity 安装位置".to_string());
+                }
+                possible_paths[0].clone()
+            }
+        };
+
+        Ok(Self { backup, db_path })
+    }
+
+    /// 写回会话：主库和账户库都写入 AGENT_STATE 并删除 AUTH_STATUS
+    pub fn restore(&self) -> Result<String, String> {
+        compat::ensure_writes_allowed()?;
+
+        // 确保数据库目录存在
+        if let Some(parent) = self.db_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建数据库目录失败: {}", e))?;
+        }
+
+        let agent_state = &self.backup.agent_state;
+        let counts = apply_to_databases(&self.db_path, &RESTORED_KEYS, &|tx| {
+            restore_state(tx, agent_state)
+        })?;
+        Ok(format!("✅ 恢复成功! {}", summary(counts)))
+    }
+
+    /// 切换账户：主库和账户库都在各自的一个事务中清除旧登录信息并写入新会话
+    ///
+    /// 任何一步失败都不会留下已登出但未写入新账户的数据库
+    pub fn switch(&self) -> Result<String, String> {
+        compat::ensure_writes_allowed()?;
+
+        if !self.db_path.exists() {
+            return Err(format!(
+                "Antigravity 状态数据库不存在: {}",
+                self.db_path.display()
+            ));
+        }
+
+        let agent_state = &self.backup.agent_state;
+        let counts = apply_to_databases(&self.db_path, &CLEARED_KEYS, &|tx| {
+            switch_state(tx, agent_state)
+        })?;
+        Ok(format!("✅ 切换成功! {}", summary(counts)))
+    }
+}
+
+/// 各数据库修改项数的说明
+fn summary((main_count, backup_count): (usize, Option<usize>)) -> String {
+    match backup_count {
+        Some(count) => format!("主库写入 {} 项; 账户库写入 {} 项", main_count, count),
+        None => format!("主库写入 {} 项; 账户库不存在，跳过", main_count),
+    }
+}
+
 /// 恢复 Antigravity 状态（精简版）
 ///
 /// 从账户文件恢复 jetskiStateSync.agentManagerInitState，并删除 antigravityAuthStatus
@@ -23,98 +169,104 @@ use crate::platform;
 pub async fn save_antigravity_account_to_file(
     account_file_path: PathBuf,
 ) -> Result<String, String> {
-    println!("📂 账户文件: {}", account_file_path.display());
+    PendingRestore::load(&account_file_path)?.restore()
+}
 
-    if !account_file_path.exists() {
-        return Err(format!("账户文件不存在: {}", account_file_path.display()));
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::state_store::MemoryStateStore;
+
+    /// 删除操作总是失败的存储
+    struct FailingDelete(MemoryStateStore);
 
-    let content = fs::read_to_string(&account_file_path).map_err(|e| e.to_string())?;
-    let account_data: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    impl StateStore for FailingDelete {
+        fn get(&self, key: &str) -> Result<Option<String>, String> {
+            self.0.get(key)
+        }
 
-    println!("✅ 账户文件读取成功");
+        fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+            self.0.put(key, value)
+        }
 
-    let app_data = match platform::get_antigravity_db_path() {
-        Some(p) => p,
-        None => {
-            let possible_paths = platform::get_all_antigravity_db_paths();
-            if possible_paths.is_empty() {
-                return Err("未找到 Antigravity 安装位置".to_string());
-            }
-            possible_paths[0].clone()
+        fn delete(&mut self, _key: &str) -> Result<usize, String> {
+            Err("database is locked".to_string())
         }
-    };
 
-    // 确保数据库目录存在
-    if let Some(parent) = app_data.parent() {
-        fs::create_dir_all(parent).map_err(|e| format!("创建数据库目录失败: {}", e))?;
-    }
+        fn keys(&self) -> Result<Vec<String>, String> {
+            self.0.keys()
+        }
 
-    let mut msg = String::new();
-
-    // 内联恢复逻辑：仅写回 AGENT_STATE 并删除 AUTH_STATUS
-    let restore_db = |db_path: &PathBuf, db_name: &str| -> Result<usize, String> {
-        tracing::info!(target: "restore::database", db_name = %db_name, "开始恢复数据库（仅 jetskiStateSync.agentManagerInitState，移除 antigravityAuthStatus）");
-        let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
-
-        let mut restored_count = 0;
-
-        if let Some(val) = account_data.get(database::AGENT_STATE) {
-            if let Some(val_str) = val.as_str() {
-                match conn.execute(
-                    "INSERT OR REPLACE INTO ItemTable (key, value) VALUES (?, ?)",
-                    params![database::AGENT_STATE, val_str],
-                ) {
-                    Ok(_) => {
-                        tracing::debug!(target: "restore::database", key = %database::AGENT_STATE, "注入数据成功");
-                        restored_count += 1;
-                    }
-                    Err(e) => {
-                        tracing::error!(target: "restore::database", key = %database::AGENT_STATE, error = %e, "写入数据失败");
-                    }
-                }
-            } else {
-                tracing::warn!(target: "restore::database", key = %database::AGENT_STATE, "字段不是字符串类型，跳过");
+        fn transaction(
+            &mut self,
+            ops: &mut dyn FnMut(&mut dyn StateStore) -> Result<(), String>,
+        ) -> Result<(), String> {
+            let snapshot = self.0.clone();
+            let result = ops(self);
+            if result.is_err() {
+                self.0 = snapshot;
             }
-        } else {
-            tracing::debug!(target: "restore::database", key = %database::AGENT_STATE, "备份中未找到字段，跳过");
+            result
         }
+    }
 
-        if let Err(e) = conn.execute(
-            "DELETE FROM ItemTable WHERE key = ?",
-            [database::AUTH_STATUS],
-        ) {
-            tracing::warn!(target: "restore::database", error = %e, "删除 antigravityAuthStatus 失败（忽略）");
-        } else {
-            tracing::debug!(target: "restore::database", "已删除 antigravityAuthStatus");
-        }
+    fn live_store() -> MemoryStateStore {
+        MemoryStateStore::with_items([
+            (database::AGENT_STATE, "old"),
+            (database::AUTH_STATUS, "{}"),
+            ("other", "kept"),
+        ])
+    }
 
-        Ok(restored_count)
-    };
+    #[test]
+    fn restore_writes_session_and_drops_auth_status() {
+        let mut store = live_store();
+        let (count, snapshot) =
+            apply_with_snapshot(&mut store, &RESTORED_KEYS, |tx| restore_state(tx, "new")).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            store.entries().unwrap(),
+            vec![
+                (database::AGENT_STATE.to_string(), "new".to_string()),
+                ("other".to_string(), "kept".to_string()),
+            ]
+        );
 
-    // 恢复主库
-    println!("📊 步骤1: 恢复 state.vscdb 数据库");
-    match restore_db(&app_data, "state.vscdb") {
-        Ok(count) => {
-            let status = format!("主库恢复 {} 项", count);
-            println!("  ✅ {}", status);
-            msg.push_str(&status);
-        }
-        Err(e) => return Err(e),
+        snapshot.restore(&mut store).unwrap();
+        assert_eq!(store, live_store());
     }
 
-    // 恢复账户库（如果有）
-    println!("💾 步骤2: 恢复 state.vscdb.backup");
-    let backup_db = app_data.with_extension("vscdb.backup");
-    if backup_db.exists() {
-        if let Ok(count) = restore_db(&backup_db, "state.vscdb.backup") {
-            let status = format!("; 账户库恢复 {} 项", count);
-            println!("  ✅ {}", status);
-            msg.push_str(&status);
-        }
-    } else {
-        println!("  ℹ️ 账户数据库不存在，跳过");
+    #[test]
+    fn switch_replaces_session_in_one_step() {
+        let mut store = live_store();
+        let (_, snapshot) =
+            apply_with_snapshot(&mut store, &CLEARED_KEYS, |tx| switch_state(tx, "new")).unwrap();
+        assert_eq!(
+            store.get(database::AGENT_STATE).unwrap().as_deref(),
+            Some("new")
+        );
+        assert_eq!(store.get(database::AUTH_STATUS).unwrap(), None);
+        assert_eq!(
+            store.get(database::ONBOARDING).unwrap().as_deref(),
+            Some("true")
+        );
+
+        snapshot.restore(&mut store).unwrap();
+        assert_eq!(store, live_store());
+
+        // 清除成功但写入失败时不能停在已登出的状态
+        let mut failing = FailingDelete(live_store());
+        assert!(
+            apply_with_snapshot(&mut failing, &CLEARED_KEYS, |tx| switch_state(tx, "new")).is_err()
+        );
+        assert_eq!(failing.0, live_store());
     }
 
-    Ok(format!("✅ 恢复成功! {}", msg))
+    #[test]
+    fn failed_restore_leaves_store_untouched() {
+        let mut store = FailingDelete(live_store());
+        let result = apply_with_snapshot(&mut store, &RESTORED_KEYS, |tx| restore_state(tx, "new"));
+        assert!(result.is_err());
+        assert_eq!(store.0, live_store());
+    }
 }
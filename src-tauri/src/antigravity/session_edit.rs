@@ -0,0 +1,117 @@
+//! 无损编辑会话数据
+//! prost 解码为 SessionResponse 时会丢弃未建模的字段，重新编码会破坏会话；
+//! 这里基于保留原始字段编码的 RawMessage 修改会话，未改动的部分与原始字节完全一致
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+use crate::antigravity::account::decode_state_bytes;
+use crate::utils::protobuf_wire::RawMessage;
+
+/// SessionResponse.history 的标签号
+const HISTORY_TAG: u32 = 1;
+
+/// 可编辑的 agentManagerInitState
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditableSession {
+    message: RawMessage,
+}
+
+impl EditableSession {
+    /// 从 Base64 的 agentManagerInitState 解析
+    pub fn from_agent_state(agent_state: &str) -> Result<Self, String> {
+        let bytes = decode_state_bytes(agent_state)?;
+        let message = RawMessage::decode(&bytes)
+            .map_err(|e| format!("jetskiStateSync.agentManagerInitState 解析失败: {}", e))?;
+        Ok(Self { message })
+    }
+
+    /// 编码回 Base64 的 agentManagerInitState
+    pub fn to_agent_state(&self) -> String {
+        BASE64.encode(self.message.encode())
+    }
+
+    /// 删除会话历史（通常是会话数据中最大的部分），返回是否有内容被删除
+    pub fn drop_history(&mut self) -> bool {
+        self.message.remove(HISTORY_TAG) > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::account::decode_session_response;
+    use prost::Message;
+
+    const FULL_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/session_unknown_fields.b64"
+    ));
+    const MINIMAL_FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/session_minimal.b64"
+    ));
+
+    #[test]
+    fn raw_message_round_trip_is_byte_identical() {
+        for fixture in [FULL_FIXTURE, MINIMAL_FIXTURE] {
+            let bytes = decode_state_bytes(fixture).unwrap();
+            let message = RawMessage::decode(&bytes).unwrap();
+            assert_eq!(message.encode(), bytes);
+        }
+    }
+
+    #[test]
+    fn agent_state_round_trip_is_identical() {
+        for fixture in [FULL_FIXTURE, MINIMAL_FIXTURE] {
+            let session = EditableSession::from_agent_state(fixture).unwrap();
+            assert_eq!(session.to_agent_state(), fixture.trim());
+        }
+    }
+
+    #[test]
+    fn prost_round_trip_loses_unknown_fields() {
+        // 证明无损表示的必要性：prost 重新编码后与原始字节不同
+        let bytes = decode_state_bytes(FULL_FIXTURE).unwrap();
+        let reencoded = decode_session_response(FULL_FIXTURE)
+            .unwrap()
+            .encode_to_vec();
+        assert_ne!(reencoded, bytes);
+    }
+
+    #[test]
+    fn drop_history_keeps_other_fields_intact() {
+        let mut session = EditableSession::from_agent_state(FULL_FIXTURE).unwrap();
+        assert!(session.drop_history());
+        assert!(!session.drop_history());
+
+        let edited = session.to_agent_state();
+        let decoded = decode_session_response(&edited).unwrap();
+        assert!(decoded.history.is_none());
+        assert_eq!(
+            decoded.context.map(|c| c.email).as_deref(),
+            Some("fixture.user@example.com")
+        );
+
+        // 除 history 外的字段（包括未建模字段）逐字节保留
+        let mut expected = RawMessage::decode(&decode_state_bytes(FULL_FIXTURE).unwrap()).unwrap();
+        expected.remove(HISTORY_TAG);
+        assert_eq!(decode_state_bytes(&edited).unwrap(), expected.encode());
+        for unknown_tag in [25, 26, 27, 30] {
+            assert_eq!(expected.clone().remove(unknown_tag), 1);
+        }
+    }
+
+    #[test]
+    fn drop_history_without_history_is_noop() {
+        let mut session = EditableSession::from_agent_state(MINIMAL_FIXTURE).unwrap();
+        assert!(!session.drop_history());
+        assert_eq!(session.to_agent_state(), MINIMAL_FIXTURE.trim());
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        let bytes = decode_state_bytes(FULL_FIXTURE).unwrap();
+        assert!(RawMessage::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(RawMessage::decode(&[0x00]).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+//! 账户快照模块
+//! 覆盖 `{email}.json` 之前把旧备份归档到 antigravity-snapshots/{email}/，
+//! 以便在误保存（过期或半登录状态）后回滚到较早的凭据
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::{load_backup_file, write_backup_file, AccountBackup};
+use crate::antigravity::compat;
+use crate::antigravity::validation;
+
+/// 快照概要信息
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    /// 快照 ID（即快照文件名去掉 .json）
+    pub id: String,
+    pub saved_at: DateTime<Utc>,
+    pub content_hash: String,
+    pub plan_name: Option<String>,
+    pub expiry_timestamp: Option<i64>,
+}
+
+impl SnapshotInfo {
+    fn from_backup(id: String, backup: &AccountBackup) -> Self {
+        Self {
+            id,
+            saved_at: backup.saved_at,
+            content_hash: backup.content_hash.clone(),
+            plan_name: backup.metadata.plan_name.clone(),
+            expiry_timestamp: backup.metadata.expiry_timestamp,
+        }
+    }
+}
+
+/// 当前设置中的快照保留数量
+pub fn configured_depth(app: &AppHandle) -> usize {
+    app.state::<crate::app_settings::AppSettingsManager>()
+        .get_settings()
+        .snapshot_depth
+}
+
+/// 指定账户的快照目录
+fn account_snapshot_dir(account: &AccountId) -> PathBuf {
+    crate::directories::get_snapshots_directory().join(account.as_str())
+}
+
+/// 快照 ID 只允许由时间戳和哈希前缀组成，避免拼接出目录外的路径
+fn validate_snapshot_id(snapshot_id: &str) -> Result<(), String> {
+    let valid = !snapshot_id.is_empty()
+        && snapshot_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的快照 ID: {}", snapshot_id))
+    }
+}
+
+/// 将备份归档为快照；已存在相同内容的快照时跳过
+///
+/// 返回新快照的 ID（跳过时为 None）
+pub fn archive_backup(
+    account: &AccountId,
+    backup: &AccountBackup,
+) -> Result<Option<String>, String> {
+    archive_into(&account_snapshot_dir(account), backup)
+}
+
+fn archive_into(dir: &Path, backup: &AccountBackup) -> Result<Option<String>, String> {
+    let existing = list_dir(dir)?;
+    if existing
+        .iter()
+        .any(|s| s.content_hash == backup.content_hash)
+    {
+        tracing::debug!(target: "backup::snapshot", "相同内容的快照已存在，跳过归档");
+        return Ok(None);
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("创建快照目录失败: {}", e))?;
+
+    let snapshot_id = format!(
+        "{}-{}",
+        backup.saved_at.format("%Y%m%dT%H%M%S%3fZ"),
+        &backup.content_hash[..backup.content_hash.len().min(8)]
+    );
+    write_backup_file(&dir.join(format!("{}.json", snapshot_id)), backup)?;
+
+    tracing::info!(target: "backup::snapshot", snapshot_id = %snapshot_id, "已归档账户快照");
+    Ok(Some(snapshot_id))
+}
+
+/// 覆盖当前备份前调用：若现有文件内容不同，则先归档为快照并修剪到 `depth` 份（为 0 时不归档）
+pub fn archive_before_overwrite(
+    account: &AccountId,
+    current_file: &Path,
+    incoming: &AccountBackup,
+    depth: usize,
+) -> Result<(), String> {
+    archive_before_overwrite_in(
+        &account_snapshot_dir(account),
+        current_file,
+        incoming,
+        depth,
+    )
+}
+
+fn archive_before_overwrite_in(
+    dir: &Path,
+    current_file: &Path,
+    incoming: &AccountBackup,
+    depth: usize,
+) -> Result<(), String> {
+    if depth == 0 || !current_file.exists() {
+        return Ok(());
+    }
+
+    match load_backup_file(current_file) {
+        Ok(current) if current.content_hash != incoming.content_hash => {
+            archive_into(dir, &current)?;
+            prune_dir(dir, depth)?;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            // 现有文件已损坏，没有可归档的内容
+            tracing::warn!(target: "backup::snapshot", error = %e, "现有备份无法读取，跳过快照归档");
+        }
+    }
+
+    Ok(())
+}
+
+/// 把快照提升为当前备份（当前备份先归档，回滚本身也可以再撤销），返回当前备份文件路径
+///
+/// 快照必须通过完整校验且属于该账户，写入保护打开时拒绝；校验失败时不改动任何文件
+pub fn promote_snapshot(
+    account: &AccountId,
+    snapshot_id: &str,
+    depth: usize,
+) -> Result<PathBuf, String> {
+    let account_file = account.backup_path()?;
+    promote_in(
+        &account_snapshot_dir(account),
+        account,
+        snapshot_id,
+        &account_file,
+        depth,
+    )?;
+    Ok(account_file)
+}
+
+fn promote_in(
+    dir: &Path,
+    account: &AccountId,
+    snapshot_id: &str,
+    account_file: &Path,
+    depth: usize,
+) -> Result<(), String> {
+    validate_snapshot_id(snapshot_id)?;
+    let snapshot_file = dir.join(format!("{}.json", snapshot_id));
+    if !snapshot_file.exists() {
+        return Err(format!("快照不存在: {}", snapshot_id));
+    }
+
+    let backup = load_backup_file(&snapshot_file)?;
+    validation::validate_backup(&backup)
+        .map_err(|e| format!("快照 {} 无效: {}", snapshot_id, e))?;
+    if AccountId::from_email(&backup.metadata.email)? != *account {
+        return Err(format!(
+            "快照 {} 属于账户 {}，不能恢复到 {}",
+            snapshot_id, backup.metadata.email, account
+        ));
+    }
+    compat::ensure_writes_allowed()?;
+
+    archive_before_overwrite_in(dir, account_file, &backup, depth)?;
+    write_backup_file(account_file, &backup)?;
+    tracing::info!(target: "backup::snapshot", snapshot_id = %snapshot_id, "快照已提升为当前备份");
+    Ok(())
+}
+
+/// 列出目录中所有快照文件的 ID（不读取内容，最新的在前）
+///
+/// 快照 ID 以保存时间开头，按 ID 倒序即按保存时间倒序，无法读取的快照也能参与排序
+fn snapshot_ids(dir: &Path) -> Result<Vec<String>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| format!("读取快照目录失败: {}", e))? {
+        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            if let Some(stem) = path.file_stem() {
+                ids.push(stem.to_string_lossy().to_string());
+            }
+        }
+    }
+
+    ids.sort_by(|a, b| b.cmp(a));
+    Ok(ids)
+}
+
+/// 列出账户的所有快照（最新的在前）
+pub fn list_snapshots(account: &AccountId) -> Result<Vec<SnapshotInfo>, String> {
+    list_dir(&account_snapshot_dir(account))
+}
+
+fn list_dir(dir: &Path) -> Result<Vec<SnapshotInfo>, String> {
+    let mut snapshots = Vec::new();
+    for id in snapshot_ids(dir)? {
+        let path = dir.join(format!("{}.json", id));
+        match load_backup_file(&path) {
+            Ok(backup) => snapshots.push(SnapshotInfo::from_backup(id, &backup)),
+            Err(e) => {
+                tracing::warn!(target: "backup::snapshot", snapshot_id = %id, error = %e, "跳过无法读取的快照");
+            }
+        }
+    }
+
+    snapshots.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(b.id.cmp(&a.id)));
+    Ok(snapshots)
+}
+
+/// 删除超出保留数量的旧快照，返回删除数量
+///
+/// 按快照 ID 计数而不读取内容，损坏或暂时无法解密的快照同样会被修剪，不会无限堆积
+pub fn prune_snapshots(account: &AccountId, keep: usize) -> Result<usize, String> {
+    prune_dir(&account_snapshot_dir(account), keep)
+}
+
+fn prune_dir(dir: &Path, keep: usize) -> Result<usize, String> {
+    let mut removed = 0;
+
+    for id in snapshot_ids(dir)?.iter().skip(keep) {
+        let path = dir.join(format!("{}.json", id));
+        fs::remove_file(&path).map_err(|e| format!("删除快照 {} 失败: {}", id, e))?;
+        removed += 1;
+    }
+
+    if removed > 0 {
+        tracing::info!(target: "backup::snapshot", removed = removed, keep = keep, "已修剪旧快照");
+    }
+    Ok(removed)
+}
+
+/// 删除账户的全部快照
+pub fn remove_all_snapshots(account: &AccountId) -> Result<(), String> {
+    let dir = account_snapshot_dir(account);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| format!("删除快照目录失败: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::backup::test_agent_state;
+
+    const EMAIL: &str = "user@example.com";
+
+    /// 测试用的临时目录：快照目录和当前备份文件
+    fn temp_dirs(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!(
+            "antigravity-snapshot-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join("snapshots");
+        fs::create_dir_all(&dir).unwrap();
+        let account_file = root.join(format!("{}.json", EMAIL));
+        (root, dir, account_file)
+    }
+
+    /// 保存时间随 `expiry` 递增，快照 ID 的先后顺序因此确定
+    fn backup(expiry: i64) -> AccountBackup {
+        let mut backup = AccountBackup::new(test_agent_state(EMAIL, expiry), None).unwrap();
+        backup.saved_at = DateTime::from_timestamp(1_700_000_000 + expiry, 0).unwrap();
+        backup
+    }
+
+    fn current_hash(account_file: &Path) -> String {
+        load_backup_file(account_file).unwrap().content_hash
+    }
+
+    #[test]
+    fn promote_replaces_current_backup_and_archives_it() {
+        let (root, dir, account_file) = temp_dirs("promote");
+        let account = AccountId::from_email(EMAIL).unwrap();
+        let (old, current) = (backup(1), backup(2));
+        let old_id = archive_into(&dir, &old).unwrap().unwrap();
+        write_backup_file(&account_file, &current).unwrap();
+
+        promote_in(&dir, &account, &old_id, &account_file, 5).unwrap();
+        assert_eq!(current_hash(&account_file), old.content_hash);
+        let hashes: Vec<String> = list_dir(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.content_hash)
+            .collect();
+        assert!(hashes.contains(&current.content_hash));
+        assert!(hashes.contains(&old.content_hash));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn promote_rejects_invalid_snapshots_without_touching_files() {
+        let (root, dir, account_file) = temp_dirs("promote-invalid");
+        let account = AccountId::from_email(EMAIL).unwrap();
+        let current = backup(2);
+        write_backup_file(&account_file, &current).unwrap();
+
+        // 会话内容被改动，哈希不再匹配
+        let mut tampered = backup(1).to_value().unwrap();
+        tampered["jetskiStateSync.agentManagerInitState"] =
+            serde_json::Value::String(test_agent_state(EMAIL, 3));
+        fs::write(
+            dir.join("20240101T000000000Z-tampered.json"),
+            tampered.to_string(),
+        )
+        .unwrap();
+        assert!(promote_in(
+            &dir,
+            &account,
+            "20240101T000000000Z-tampered",
+            &account_file,
+            5
+        )
+        .is_err());
+
+        // 快照属于其他账户
+        let other = AccountBackup::new(test_agent_state("other@example.com", 1), None).unwrap();
+        let other_id = archive_into(&dir, &other).unwrap().unwrap();
+        assert!(promote_in(&dir, &account, &other_id, &account_file, 5).is_err());
+
+        assert!(promote_in(&dir, &account, "../escape", &account_file, 5).is_err());
+        assert_eq!(current_hash(&account_file), current.content_hash);
+        assert_eq!(snapshot_ids(&dir).unwrap().len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn archive_keeps_configured_depth() {
+        let (root, dir, account_file) = temp_dirs("depth");
+        write_backup_file(&account_file, &backup(0)).unwrap();
+
+        for expiry in 1..=4 {
+            let incoming = backup(expiry);
+            archive_before_overwrite_in(&dir, &account_file, &incoming, 2).unwrap();
+            write_backup_file(&account_file, &incoming).unwrap();
+        }
+        let kept: Vec<Option<i64>> = list_dir(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.expiry_timestamp)
+            .collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.contains(&Some(3)) && kept.contains(&Some(2)));
+
+        // 保留数量为 0 时关闭快照，不再归档
+        archive_before_overwrite_in(&dir, &account_file, &backup(5), 0).unwrap();
+        assert_eq!(snapshot_ids(&dir).unwrap().len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn prune_counts_unreadable_snapshots() {
+        let dir =
+            std::env::temp_dir().join(format!("antigravity-snapshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let ids = [
+            "20240101T000000000Z-aaaaaaaa",
+            "20240201T000000000Z-bbbbbbbb",
+            "20240301T000000000Z-cccccccc",
+            "20240401T000000000Z-dddddddd",
+        ];
+        for id in ids {
+            // 内容无法解析，list_snapshots 会跳过它们
+            fs::write(dir.join(format!("{}.json", id)), "not a backup").unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "kept").unwrap();
+
+        assert_eq!(
+            snapshot_ids(&dir).unwrap(),
+            ids.iter()
+                .rev()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(prune_dir(&dir, 2).unwrap(), 2);
+        assert_eq!(
+            snapshot_ids(&dir).unwrap(),
+            vec![ids[3].to_string(), ids[2].to_string()]
+        );
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(prune_dir(&dir, 2).unwrap(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+//! Antigravity 状态数据库（state.vscdb）的键值存储抽象
+//! ItemTable 本质上是一张键值表；账户相关的读写都通过 StateStore 进行，
+//! 恢复、清理等逻辑因此可以用内存实现做单元测试，而不需要真实的 Antigravity 安装
+
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+#[cfg(test)]
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// ItemTable 键值存储
+pub trait StateStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+
+    /// 写入（已存在时覆盖）
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String>;
+
+    /// 删除，返回删除的行数
+    fn delete(&mut self, key: &str) -> Result<usize, String>;
+
+    /// 全部键（按键名排序）
+    fn keys(&self) -> Result<Vec<String>, String>;
+
+    /// 全部键值（按键名排序）
+    fn entries(&self) -> Result<Vec<(String, String)>, String> {
+        let mut entries = Vec::new();
+        for key in self.keys()? {
+            if let Some(value) = self.get(&key)? {
+                entries.push((key, value));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// 在一个事务中执行 `ops`：返回 Err 时撤销其中的全部修改；不支持嵌套
+    fn transaction(
+        &mut self,
+        ops: &mut dyn FnMut(&mut dyn StateStore) -> Result<(), String>,
+    ) -> Result<(), String>;
+}
+
+/// 若干键在修改前的原值
+/// 跨多个数据库文件的操作无法放进同一个事务，后一个文件失败时用它撤销前一个文件已提交的修改
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySnapshot {
+    values: Vec<(String, Option<String>)>,
+}
+
+impl KeySnapshot {
+    pub fn capture(store: &dyn StateStore, keys: &[&str]) -> Result<Self, String> {
+        let values = keys
+            .iter()
+            .map(|key| Ok((key.to_string(), store.get(key)?)))
+            .collect::<Result<_, String>>()?;
+        Ok(Self { values })
+    }
+
+    /// 在一个事务中写回原值（原来不存在的键会被删除）
+    pub fn restore(&self, store: &mut dyn StateStore) -> Result<(), String> {
+        store.transaction(&mut |tx| {
+            for (key, value) in &self.values {
+                match value {
+                    Some(value) => tx.put(key, value)?,
+                    None => {
+                        tx.delete(key)?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// 在一个事务中执行 `ops`，同时记下 `keys` 修改前的原值
+pub fn apply_with_snapshot<T>(
+    store: &mut dyn StateStore,
+    keys: &[&str],
+    mut ops: impl FnMut(&mut dyn StateStore) -> Result<T, String>,
+) -> Result<(T, KeySnapshot), String> {
+    let mut outcome = None;
+    store.transaction(&mut |tx| {
+        let snapshot = KeySnapshot::capture(tx, keys)?;
+        outcome = Some((ops(tx)?, snapshot));
+        Ok(())
+    })?;
+    outcome.ok_or_else(|| "事务未执行".to_string())
+}
+
+/// 基于 SQLite 的实现（Antigravity 的 state.vscdb）
+pub struct SqliteStateStore {
+    conn: Connection,
+}
+
+impl SqliteStateStore {
+    /// 以读写方式打开
+    pub fn open(db_path: &Path) -> Result<Self, String> {
+        Connection::open(db_path)
+            .map(Self::from_connection)
+            .map_err(|e| format!("连接数据库失败 ({}): {}", db_path.display(), e))
+    }
+
+    /// 以只读方式打开，不会修改数据库也不会创建文件
+    pub fn open_read_only(db_path: &Path) -> Result<Self, String> {
+        Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map(Self::from_connection)
+            .map_err(|e| format!("连接数据库失败 ({}): {}", db_path.display(), e))
+    }
+
+    pub fn from_connection(conn: Connection) -> Self {
+        Self { conn }
+    }
+}
+
+impl StateStore for SqliteStateStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        self.conn
+            .query_row("SELECT value FROM ItemTable WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(|e| format!("查询 {} 失败: {}", key, e))
+    }
+
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO ItemTable (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("写入 {} 失败: {}", key, e))
+    }
+
+    fn delete(&mut self, key: &str) -> Result<usize, String> {
+        self.conn
+            .execute("DELETE FROM ItemTable WHERE key = ?1", [key])
+            .map_err(|e| format!("删除 {} 失败: {}", key, e))
+    }
+
+    fn keys(&self) -> Result<Vec<String>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key FROM ItemTable ORDER BY key")
+            .map_err(|e| format!("查询键列表失败: {}", e))?;
+        let keys = stmt
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("查询键列表失败: {}", e))?;
+        Ok(keys)
+    }
+
+    fn entries(&self) -> Result<Vec<(String, String)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM ItemTable ORDER BY key")
+            .map_err(|e| format!("查询数据失败: {}", e))?;
+        let entries = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("查询数据失败: {}", e))?;
+        Ok(entries)
+    }
+
+    fn transaction(
+        &mut self,
+        ops: &mut dyn FnMut(&mut dyn StateStore) -> Result<(), String>,
+    ) -> Result<(), String> {
+        // IMMEDIATE：开始时即取得写锁，Antigravity 同时写库时尽早失败而不是中途失败
+        self.conn
+            .execute_batch("BEGIN IMMEDIATE")
+            .map_err(|e| format!("开始事务失败: {}", e))?;
+
+        let result = ops(self).and_then(|()| {
+            self.conn
+                .execute_batch("COMMIT")
+                .map_err(|e| format!("提交事务失败: {}", e))
+        });
+        if result.is_err() && !self.conn.is_autocommit() {
+            if let Err(e) = self.conn.execute_batch("ROLLBACK") {
+                tracing::error!(target: "state_store", error = %e, "回滚事务失败");
+            }
+        }
+        result
+    }
+}
+
+/// 内存实现，用于单元测试
+#[cfg(test)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryStateStore {
+    items: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_items<K: Into<String>, V: Into<String>>(
+        items: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        Self {
+            items: items
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl StateStore for MemoryStateStore {
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        Ok(self.items.get(key).cloned())
+    }
+
+    fn put(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.items.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<usize, String> {
+        Ok(usize::from(self.items.remove(key).is_some()))
+    }
+
+    fn keys(&self) -> Result<Vec<String>, String> {
+        Ok(self.items.keys().cloned().collect())
+    }
+
+    fn transaction(
+        &mut self,
+        ops: &mut dyn FnMut(&mut dyn StateStore) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let snapshot = self.items.clone();
+        let result = ops(self);
+        if result.is_err() {
+            self.items = snapshot;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqlite_store() -> SqliteStateStore {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB)",
+        )
+        .unwrap();
+        SqliteStateStore::from_connection(conn)
+    }
+
+    fn exercise(store: &mut dyn StateStore) {
+        store.put("b", "2").unwrap();
+        store.put("a", "1").unwrap();
+        store.put("a", "3").unwrap();
+        assert_eq!(store.get("a").unwrap().as_deref(), Some("3"));
+        assert_eq!(store.get("missing").unwrap(), None);
+        assert_eq!(store.keys().unwrap(), vec!["a", "b"]);
+
+        assert_eq!(store.delete("b").unwrap(), 1);
+        assert_eq!(store.delete("b").unwrap(), 0);
+
+        // 失败的事务不留下任何修改
+        let result = store.transaction(&mut |tx| {
+            tx.put("c", "4")?;
+            tx.delete("a")?;
+            Err("中途失败".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            store.entries().unwrap(),
+            vec![("a".to_string(), "3".to_string())]
+        );
+
+        store
+            .transaction(&mut |tx| {
+                tx.put("c", "4")?;
+                tx.delete("a").map(|_| ())
+            })
+            .unwrap();
+        assert_eq!(
+            store.entries().unwrap(),
+            vec![("c".to_string(), "4".to_string())]
+        );
+    }
+
+    #[test]
+    fn snapshot_undoes_committed_changes() {
+        let mut store = MemoryStateStore::with_items([("a", "1"), ("b", "2")]);
+        let original = store.clone();
+
+        let (_, snapshot) = apply_with_snapshot(&mut store, &["a", "c"], |tx| {
+            tx.put("a", "changed")?;
+            tx.put("c", "new")
+        })
+        .unwrap();
+        assert_eq!(store.get("c").unwrap().as_deref(), Some("new"));
+
+        snapshot.restore(&mut store).unwrap();
+        assert_eq!(store, original);
+    }
+
+    #[test]
+    fn memory_store_behaves_like_item_table() {
+        exercise(&mut MemoryStateStore::new());
+    }
+
+    #[test]
+    fn sqlite_store_behaves_like_item_table() {
+        exercise(&mut sqlite_store());
+    }
+}
@@ -0,0 +1,113 @@
+//! 会话数据校验
+//! 所有导入的备份以及写入 Antigravity 数据库之前的 agentManagerInitState 都必须通过这里的校验，
+//! 避免把无法解码的内容注入 state.vscdb，导致 Antigravity 停留在损坏的会话中
+
+use serde_json::Value;
+
+use crate::antigravity::account::decode_jetski_state_proto;
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::{content_hash, AccountBackup};
+
+/// 校验通过的会话摘要
+#[derive(Debug, Clone)]
+pub struct ValidatedSession {
+    pub email: String,
+    /// 解码后的 SessionResponse
+    pub session: Value,
+}
+
+/// 校验 agentManagerInitState：必须是合法的 Base64、能解码为 SessionResponse，且包含合法邮箱
+pub fn validate_agent_state(agent_state: &str) -> Result<ValidatedSession, String> {
+    let session =
+        decode_jetski_state_proto(agent_state).map_err(|e| format!("会话数据无效: {}", e))?;
+
+    let email = session
+        .pointer("/context/email")
+        .and_then(|v| v.as_str())
+        .filter(|email| !email.is_empty())
+        .ok_or_else(|| "会话数据无效: SessionResponse 中缺少账户邮箱".to_string())?
+        .to_string();
+    if !email.contains('@') {
+        return Err(format!("会话数据无效: 邮箱格式不正确 ({})", email));
+    }
+    AccountId::from_email(&email).map_err(|e| format!("会话数据无效: {}", e))?;
+
+    Ok(ValidatedSession { email, session })
+}
+
+/// 校验备份信封：会话数据本身有效，且与信封中的元数据、内容哈希一致
+pub fn validate_backup(backup: &AccountBackup) -> Result<ValidatedSession, String> {
+    let validated = validate_agent_state(&backup.agent_state)?;
+
+    if validated.email != backup.metadata.email {
+        return Err(format!(
+            "备份元数据不一致: 信封邮箱为 {}，会话数据中的邮箱为 {}",
+            backup.metadata.email, validated.email
+        ));
+    }
+    if content_hash(&backup.agent_state) != backup.content_hash {
+        return Err("备份内容哈希不匹配，数据可能已被修改".to_string());
+    }
+
+    Ok(validated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::antigravity::backup::test_agent_state;
+    use base64::Engine;
+
+    fn encode(bytes: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn assert_rejected(agent_state: &str, expected: &str) {
+        let err = validate_agent_state(agent_state).unwrap_err();
+        assert!(err.contains(expected), "{}", err);
+    }
+
+    #[test]
+    fn valid_session_passes() {
+        let validated = validate_agent_state(&test_agent_state("user@example.com", 0)).unwrap();
+        assert_eq!(validated.email, "user@example.com");
+        assert_eq!(
+            validated.session.pointer("/context/email"),
+            Some(&Value::from("user@example.com"))
+        );
+    }
+
+    #[test]
+    fn undecodable_sessions_are_rejected() {
+        assert_rejected("", "为空");
+        assert_rejected("not base64!", "Base64 解码失败");
+        // 字段 1 声明长度 5，实际只有 1 字节
+        assert_rejected(&encode(&[0x0a, 0x05, 0x01]), "Protobuf 解码失败");
+    }
+
+    #[test]
+    fn sessions_without_a_usable_email_are_rejected() {
+        assert_rejected(&test_agent_state("", 0), "缺少账户邮箱");
+        assert_rejected(&test_agent_state("not-an-email", 0), "邮箱格式不正确");
+    }
+
+    #[test]
+    fn envelope_must_match_session() {
+        let backup = AccountBackup::new(test_agent_state("user@example.com", 0), None).unwrap();
+        assert!(validate_backup(&backup).is_ok());
+
+        let mut mismatched = backup.clone();
+        mismatched.metadata.email = "other@example.com".to_string();
+        let err = validate_backup(&mismatched).unwrap_err();
+        assert!(err.contains("备份元数据不一致"), "{}", err);
+
+        let mut tampered = backup.clone();
+        tampered.content_hash = content_hash("something else");
+        let err = validate_backup(&tampered).unwrap_err();
+        assert!(err.contains("内容哈希不匹配"), "{}", err);
+
+        let mut swapped = backup;
+        swapped.agent_state = test_agent_state("user@example.com", 1);
+        assert!(validate_backup(&swapped).is_err());
+    }
+}
@@ -0,0 +1,623 @@
+//! 账户保险库（静态加密）
+//! 开启后账户备份、快照和隔离文件都以主密钥加密保存；主密钥由主密码派生，
+//! 或从密钥文件读取。解锁后的密钥只保存在内存中，锁定时立即清除。
+//! 每个文件的附加认证数据都绑定所属账户，把一个账户的密文换到另一个账户的位置会解密失败。
+//!
+//! 默认密钥文件 vault.key 与它保护的账户文件位于同一配置目录：这只能防止账户文件被单独泄露
+//! （例如只同步或拷走账户目录），能读取整个配置目录的人同样能读到密钥。
+//! 需要更强的保护时应使用主密码，或把密钥文件放在其他位置（例如可移动设备）
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+use crate::antigravity::quarantine;
+use crate::utils::crypto::{self, KdfParams};
+
+/// 加密文件格式标识
+const VAULT_FORMAT: &str = "antigravity-agent-vault";
+
+/// 当前保险库格式版本（版本 2 起附加认证数据绑定账户标识）
+const VAULT_VERSION: u32 = 2;
+
+/// 附加认证数据未绑定账户的旧版文件
+const UNBOUND_VAULT_VERSION: u32 = 1;
+
+/// 用于校验主密钥是否正确的固定明文
+const VERIFIER_PLAINTEXT: &[u8] = b"antigravity-agent-vault-verifier";
+
+/// 保险库锁定时的统一错误
+pub const VAULT_LOCKED: &str = "账户保险库已锁定，请先解锁";
+
+/// 解锁后的主密钥（进程内共享）
+static UNLOCKED_KEY: Mutex<Option<Zeroizing<[u8; 32]>>> = Mutex::new(None);
+
+/// 主密钥来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyProvider {
+    /// 由主密码经 Argon2id 派生
+    Password,
+    /// 从密钥文件读取（32 字节随机数，Base64）
+    KeyFile,
+}
+
+/// 保险库配置（vault.json，存在即表示已开启）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultConfig {
+    version: u32,
+    provider: KeyProvider,
+    /// 主密码模式下的派生参数
+    kdf: Option<KdfParams>,
+    /// 密钥文件模式下的密钥文件路径
+    key_file: Option<String>,
+    verifier_nonce: String,
+    verifier: String,
+}
+
+/// 加密后的账户文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultFile {
+    format: String,
+    version: u32,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// 保险库状态
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    pub provider: Option<KeyProvider>,
+    pub key_file: Option<String>,
+}
+
+/// 加密/解密一次批量处理的结果；单个文件失败不会中断其余文件
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPassReport {
+    /// 成功处理的文件数
+    pub processed: usize,
+    /// 处理失败的文件，文件保持原样，下次解锁时重试
+    pub failures: Vec<VaultFileFailure>,
+}
+
+/// 批量处理中失败的文件
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFileFailure {
+    /// 文件（或无法读取的目录）路径
+    pub path: String,
+    pub reason: String,
+}
+
+/// 校验主密钥用的附加认证数据（随保险库配置的版本）
+fn verifier_associated_data(version: u32) -> Vec<u8> {
+    format!("{}|{}", VAULT_FORMAT, version).into_bytes()
+}
+
+/// 账户文件的附加认证数据：格式、版本和所属账户标识都参与认证
+fn file_associated_data(version: u32, account: &str) -> Vec<u8> {
+    if version == UNBOUND_VAULT_VERSION {
+        format!("{}|{}", VAULT_FORMAT, version).into_bytes()
+    } else {
+        format!("{}|{}|{}", VAULT_FORMAT, version, account).into_bytes()
+    }
+}
+
+/// 文件所属的账户标识：快照取所在的账户子目录名，隔离文件去掉时间戳前缀，其余取文件名
+fn bound_account(path: &Path) -> String {
+    let stem = |name: &str| name.strip_suffix(".json").unwrap_or(name).to_string();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let parent = path.parent();
+
+    if parent.and_then(Path::parent)
+        == Some(crate::directories::get_snapshots_directory().as_path())
+    {
+        return parent
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+    if parent == Some(crate::directories::get_quarantine_directory().as_path()) {
+        return stem(quarantine::original_file_name(&file_name));
+    }
+    stem(&file_name)
+}
+
+/// 用主密钥加密账户文件内容，返回加密文件的 JSON
+fn seal_file(key: &[u8; 32], account: &str, plaintext: &str) -> Result<String, String> {
+    let (nonce, ciphertext) = crypto::seal(
+        key,
+        plaintext.as_bytes(),
+        &file_associated_data(VAULT_VERSION, account),
+    )?;
+    let vault_file = VaultFile {
+        format: VAULT_FORMAT.to_string(),
+        version: VAULT_VERSION,
+        nonce,
+        ciphertext,
+    };
+    serde_json::to_string_pretty(&vault_file).map_err(|e| format!("序列化加密文件失败: {}", e))
+}
+
+/// 解密账户文件；文件属于其他账户时认证失败
+fn open_file(key: &[u8; 32], account: &str, vault_file: &VaultFile) -> Result<String, String> {
+    let plaintext = crypto::open(
+        key,
+        &vault_file.nonce,
+        &vault_file.ciphertext,
+        &file_associated_data(vault_file.version, account),
+    )?;
+    String::from_utf8(plaintext).map_err(|_| "解密后的内容无效".to_string())
+}
+
+/// 解析加密文件头；明文文件返回 None
+fn parse_vault_file(content: &str) -> Option<VaultFile> {
+    serde_json::from_str::<VaultFile>(content)
+        .ok()
+        .filter(|f| f.format == VAULT_FORMAT)
+}
+
+fn load_config() -> Result<Option<VaultConfig>, String> {
+    let path = crate::directories::get_vault_config_file();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("读取保险库配置失败: {}", e))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("解析保险库配置失败: {}", e))
+}
+
+fn current_key() -> Option<Zeroizing<[u8; 32]>> {
+    UNLOCKED_KEY.lock().unwrap().clone()
+}
+
+/// 保险库是否已开启
+pub fn is_enabled() -> bool {
+    crate::directories::get_vault_config_file().exists()
+}
+
+/// 保险库是否已解锁（未开启时视为无需解锁）
+pub fn is_unlocked() -> bool {
+    current_key().is_some()
+}
+
+/// 保险库开启但未解锁时返回错误；批量读取账户前调用，避免把加密文件当成损坏文件
+pub fn ensure_readable() -> Result<(), String> {
+    if is_enabled() && !is_unlocked() {
+        Err(VAULT_LOCKED.to_string())
+    } else {
+        Ok(())
+    }
+}
+
+/// 获取保险库状态
+pub fn status() -> Result<VaultStatus, String> {
+    let config = load_config()?;
+    Ok(VaultStatus {
+        enabled: config.is_some(),
+        unlocked: is_unlocked(),
+        provider: config.as_ref().map(|c| c.provider),
+        key_file: config.and_then(|c| c.key_file),
+    })
+}
+
+/// 当前能否解密该文件内容：明文文件总是可以；加密文件需要保险库已解锁且文件版本受支持
+pub fn can_decrypt(content: &str) -> bool {
+    match parse_vault_file(content) {
+        Some(file) => file.version <= VAULT_VERSION && is_unlocked(),
+        None => true,
+    }
+}
+
+/// 读取账户文件，返回明文及该文件是否为加密保存
+pub fn read_account_file(path: &Path) -> Result<(String, bool), String> {
+    read_file(path).map(|(content, version)| (content, version.is_some()))
+}
+
+/// 读取账户文件，返回明文及加密文件的格式版本（明文文件为 None）
+fn read_file(path: &Path) -> Result<(String, Option<u32>), String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取备份文件失败 {}: {}", path.display(), e))?;
+
+    let Some(vault_file) = parse_vault_file(&content) else {
+        return Ok((content, None));
+    };
+
+    if vault_file.version > VAULT_VERSION {
+        return Err(format!(
+            "保险库文件版本 {} 高于当前支持的版本 {}",
+            vault_file.version, VAULT_VERSION
+        ));
+    }
+    let key = current_key().ok_or_else(|| VAULT_LOCKED.to_string())?;
+    let plaintext = open_file(&key, &bound_account(path), &vault_file)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok((plaintext, Some(vault_file.version)))
+}
+
+/// 写入账户文件；保险库开启时加密保存
+pub fn write_account_file(path: &Path, plaintext: &str) -> Result<(), String> {
+    write_file(path, plaintext, is_enabled())
+}
+
+/// 写入账户文件；保险库开启但尚未解锁时以明文写入，下次解锁时由 `unlock` 加密
+///
+/// 仅用于启动迁移：它早于密钥文件自动解锁执行，主密码模式下也无法解锁
+pub fn write_migrated_account_file(path: &Path, plaintext: &str) -> Result<(), String> {
+    write_file(path, plaintext, is_enabled() && is_unlocked())
+}
+
+/// 先写临时文件再替换，避免中途失败留下半个文件
+fn write_file(path: &Path, plaintext: &str, encrypt: bool) -> Result<(), String> {
+    let content = if encrypt {
+        let key = current_key().ok_or_else(|| VAULT_LOCKED.to_string())?;
+        seal_file(&key, &bound_account(path), plaintext)?
+    } else {
+        plaintext.to_string()
+    };
+
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content)
+        .map_err(|e| format!("写入备份文件失败 {}: {}", path.display(), e))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("写入备份文件失败 {}: {}", path.display(), e)
+    })
+}
+
+/// 读取或创建密钥文件
+fn load_or_create_key_file(path: &Path) -> Result<Zeroizing<[u8; 32]>, String> {
+    if !path.exists() {
+        let mut key = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(&mut key[..]);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建密钥文件目录失败: {}", e))?;
+        }
+        fs::write(path, BASE64.encode(&key[..]))
+            .map_err(|e| format!("写入密钥文件失败 {}: {}", path.display(), e))?;
+        restrict_permissions(path);
+        tracing::info!(target: "account::vault", key_file = %path.display(), "已生成保险库密钥文件");
+    }
+
+    let content = Zeroizing::new(
+        fs::read_to_string(path)
+            .map_err(|e| format!("读取密钥文件失败 {}: {}", path.display(), e))?,
+    );
+    let bytes = Zeroizing::new(
+        BASE64
+            .decode(content.trim())
+            .map_err(|_| "密钥文件内容无效".to_string())?,
+    );
+    let key: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| "密钥文件长度无效（需要 32 字节）".to_string())?;
+    Ok(Zeroizing::new(key))
+}
+
+/// 密钥文件只允许当前用户读写
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Err(e) = fs::set_permissions(path, fs::Permissions::from_mode(0o600)) {
+        tracing::warn!(target: "account::vault", error = %e, "设置密钥文件权限失败");
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) {}
+
+/// 按配置得到主密钥并校验
+fn derive_and_verify(
+    config: &VaultConfig,
+    password: Option<&str>,
+) -> Result<Zeroizing<[u8; 32]>, String> {
+    let key = match config.provider {
+        KeyProvider::Password => {
+            let password = password
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| "请输入主密码".to_string())?;
+            config
+                .kdf
+                .as_ref()
+                .ok_or_else(|| "保险库配置缺少密钥派生参数".to_string())?
+                .derive_key(password)?
+        }
+        KeyProvider::KeyFile => {
+            let key_file = config
+                .key_file
+                .as_ref()
+                .ok_or_else(|| "保险库配置缺少密钥文件路径".to_string())?;
+            let path = Path::new(key_file);
+            if !path.exists() {
+                return Err(format!("密钥文件不存在: {}", key_file));
+            }
+            load_or_create_key_file(path)?
+        }
+    };
+
+    crypto::open(
+        &key,
+        &config.verifier_nonce,
+        &config.verifier,
+        &verifier_associated_data(config.version),
+    )
+    .map_err(|_| "主密码或密钥文件不正确".to_string())?;
+    Ok(key)
+}
+
+/// 需要加密/解密的所有账户文件（当前备份 + 历史快照 + 隔离文件）
+fn account_files() -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut collect = |dir: &Path| -> Result<(), String> {
+        if !dir.exists() {
+            return Ok(());
+        }
+        for entry in
+            fs::read_dir(dir).map_err(|e| format!("读取目录失败 {}: {}", dir.display(), e))?
+        {
+            let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+            if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    };
+
+    collect(&crate::directories::get_accounts_directory())?;
+    collect(&crate::directories::get_quarantine_directory())?;
+    let snapshots_dir = crate::directories::get_snapshots_directory();
+    if snapshots_dir.exists() {
+        for entry in fs::read_dir(&snapshots_dir).map_err(|e| format!("读取快照目录失败: {}", e))?
+        {
+            let path = entry.map_err(|e| format!("读取目录项失败: {}", e))?.path();
+            if path.is_dir() {
+                collect(&path)?;
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// 把仍为明文（或旧版未绑定账户）的账户文件加密保存
+///
+/// 保险库此时已开启并解锁，单个文件失败只记录下来，下次解锁时重试
+fn encrypt_plaintext_files() -> VaultPassReport {
+    let mut report = VaultPassReport::default();
+    let files = match account_files() {
+        Ok(files) => files,
+        Err(e) => {
+            report.failures.push(VaultFileFailure {
+                path: crate::directories::get_config_directory()
+                    .display()
+                    .to_string(),
+                reason: e,
+            });
+            Vec::new()
+        }
+    };
+
+    for path in files {
+        let result = read_file(&path).and_then(|(content, version)| {
+            if version == Some(VAULT_VERSION) {
+                Ok(false)
+            } else {
+                write_account_file(&path, &content).map(|_| true)
+            }
+        });
+        match result {
+            Ok(true) => report.processed += 1,
+            Ok(false) => {}
+            Err(reason) => report.failures.push(VaultFileFailure {
+                path: path.display().to_string(),
+                reason,
+            }),
+        }
+    }
+
+    if report.processed > 0 {
+        tracing::info!(target: "account::vault", count = report.processed, "已加密现有账户文件");
+    }
+    for failure in &report.failures {
+        tracing::warn!(target: "account::vault", file = %failure.path, error = %failure.reason, "加密账户文件失败");
+    }
+    report
+}
+
+/// 开启保险库并加密现有文件；保险库开启后单个文件加密失败不视为开启失败，记录在返回的报告中
+///
+/// 提供 `password` 时使用主密码；否则使用密钥文件（`key_file` 缺省为配置目录下的 vault.key，
+/// 与账户文件位于同一目录，见模块说明）
+pub fn enable(
+    password: Option<&str>,
+    key_file: Option<PathBuf>,
+) -> Result<VaultPassReport, String> {
+    if is_enabled() {
+        return Err("账户保险库已开启".to_string());
+    }
+
+    let (provider, kdf, key_file, key) = match password.filter(|p| !p.is_empty()) {
+        Some(password) => {
+            let kdf = KdfParams::generate();
+            let key = kdf.derive_key(password)?;
+            (KeyProvider::Password, Some(kdf), None, key)
+        }
+        None => {
+            let path = key_file.unwrap_or_else(crate::directories::get_vault_key_file);
+            let key = load_or_create_key_file(&path)?;
+            (
+                KeyProvider::KeyFile,
+                None,
+                Some(path.display().to_string()),
+                key,
+            )
+        }
+    };
+
+    let (verifier_nonce, verifier) = crypto::seal(
+        &key,
+        VERIFIER_PLAINTEXT,
+        &verifier_associated_data(VAULT_VERSION),
+    )?;
+    let config = VaultConfig {
+        version: VAULT_VERSION,
+        provider,
+        kdf,
+        key_file,
+        verifier_nonce,
+        verifier,
+    };
+
+    *UNLOCKED_KEY.lock().unwrap() = Some(key);
+    let json = serde_json::to_string_pretty(&config)
+        .map_err(|e| format!("序列化保险库配置失败: {}", e))?;
+    fs::write(crate::directories::get_vault_config_file(), json)
+        .map_err(|e| format!("写入保险库配置失败: {}", e))?;
+
+    tracing::info!(target: "account::vault", provider = ?provider, "账户保险库已开启");
+    Ok(encrypt_plaintext_files())
+}
+
+/// 解锁保险库；顺带加密开启后以明文写入的文件（例如启动迁移产生的文件）和旧版加密文件
+///
+/// 密钥校验通过即视为解锁成功，单个文件加密失败记录在返回的报告中
+pub fn unlock(password: Option<&str>) -> Result<VaultPassReport, String> {
+    let config = load_config()?.ok_or_else(|| "账户保险库未开启".to_string())?;
+    let key = derive_and_verify(&config, password)?;
+    *UNLOCKED_KEY.lock().unwrap() = Some(key);
+    tracing::info!(target: "account::vault", "账户保险库已解锁");
+    Ok(encrypt_plaintext_files())
+}
+
+/// 锁定保险库（清除内存中的主密钥）
+pub fn lock() {
+    *UNLOCKED_KEY.lock().unwrap() = None;
+    tracing::info!(target: "account::vault", "账户保险库已锁定");
+}
+
+/// 关闭保险库：解密所有文件（包括隔离区）并删除保险库配置（需要已解锁），返回解密的文件数
+///
+/// 任何一个加密文件无法解密时拒绝关闭，避免留下再也无法解密的文件
+pub fn disable() -> Result<usize, String> {
+    if !is_enabled() {
+        return Err("账户保险库未开启".to_string());
+    }
+    ensure_readable()?;
+
+    // 先全部解密到内存，确认都能读取后再写回，避免半途失败留下混合状态
+    let mut decrypted = Vec::new();
+    for path in account_files()? {
+        let (content, encrypted) = read_account_file(&path)
+            .map_err(|e| format!("无法解密，已取消关闭保险库（可先删除该文件）: {}", e))?;
+        if encrypted {
+            decrypted.push((path, content));
+        }
+    }
+
+    // 配置最后删除：中途失败时剩余文件仍是加密的，保险库保持开启，可以重试
+    for (path, content) in &decrypted {
+        write_file(path, content, false)?;
+    }
+    fs::remove_file(crate::directories::get_vault_config_file())
+        .map_err(|e| format!("删除保险库配置失败: {}", e))?;
+    lock();
+
+    tracing::info!(target: "account::vault", count = decrypted.len(), "账户保险库已关闭");
+    Ok(decrypted.len())
+}
+
+/// 启动时使用密钥文件自动解锁（主密码模式需要用户手动解锁）
+pub fn try_auto_unlock() {
+    match load_config() {
+        Ok(Some(config)) if config.provider == KeyProvider::KeyFile => match unlock(None) {
+            Ok(report) => tracing::info!(
+                target: "account::vault",
+                failures = report.failures.len(),
+                "已使用密钥文件自动解锁保险库"
+            ),
+            Err(e) => {
+                tracing::warn!(target: "account::vault", error = %e, "使用密钥文件自动解锁失败")
+            }
+        },
+        Ok(_) => {}
+        Err(e) => tracing::warn!(target: "account::vault", error = %e, "读取保险库配置失败"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    fn parse(content: &str) -> VaultFile {
+        parse_vault_file(content).unwrap()
+    }
+
+    #[test]
+    fn sealed_file_is_bound_to_its_account() {
+        let sealed = parse(&seal_file(&KEY, "a@example.com", "secret").unwrap());
+        assert_eq!(sealed.version, VAULT_VERSION);
+        assert_eq!(open_file(&KEY, "a@example.com", &sealed).unwrap(), "secret");
+        assert!(open_file(&KEY, "b@example.com", &sealed).is_err());
+        assert!(open_file(&[8u8; 32], "a@example.com", &sealed).is_err());
+    }
+
+    #[test]
+    fn unbound_legacy_files_remain_readable() {
+        let (nonce, ciphertext) = crypto::seal(
+            &KEY,
+            b"legacy",
+            &file_associated_data(UNBOUND_VAULT_VERSION, ""),
+        )
+        .unwrap();
+        let legacy = VaultFile {
+            format: VAULT_FORMAT.to_string(),
+            version: UNBOUND_VAULT_VERSION,
+            nonce,
+            ciphertext,
+        };
+        assert_eq!(
+            open_file(&KEY, "any@example.com", &legacy).unwrap(),
+            "legacy"
+        );
+    }
+
+    #[test]
+    fn bound_account_follows_file_location() {
+        let accounts = crate::directories::get_accounts_directory();
+        assert_eq!(
+            bound_account(&accounts.join("user@example.com.json")),
+            "user@example.com"
+        );
+
+        let snapshot = crate::directories::get_snapshots_directory()
+            .join("user@example.com")
+            .join("20250101T000000Z-abcd.json");
+        assert_eq!(bound_account(&snapshot), "user@example.com");
+
+        let quarantined = crate::directories::get_quarantine_directory()
+            .join("20250101T000000000Z-user@example.com.json");
+        assert_eq!(bound_account(&quarantined), "user@example.com");
+    }
+
+    #[test]
+    fn plaintext_is_not_a_vault_file() {
+        assert!(parse_vault_file(r#"{"schema_version":1}"#).is_none());
+        assert!(can_decrypt("not json"));
+    }
+}
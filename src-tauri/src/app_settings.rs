@@ -16,12 +16,26 @@ pub struct AppSettings {
     pub debug_mode: bool,
     /// 隐私模式：用户信息打码（邮箱/用户名）
     pub private_mode: bool,
+    /// 每个账户保留的历史快照数量（0 表示不保留）
+    pub snapshot_depth: usize,
+    /// Antigravity 轮换令牌后自动刷新对应账户的备份（会在后台改写备份文件，默认关闭）
+    pub auto_refresh_backups: bool,
+    /// 令牌过期前多少分钟开始提醒
+    pub expiry_warning_minutes: u64,
+    /// 令牌即将过期时发送桌面通知
+    pub expiry_notifications_enabled: bool,
 }
 
 fn default_private_mode() -> bool {
     true
 }
 
+/// 默认保留的账户快照数量
+pub const DEFAULT_SNAPSHOT_DEPTH: usize = 5;
+
+/// 默认的令牌过期提醒窗口（分钟）
+pub const DEFAULT_EXPIRY_WARNING_MINUTES: u64 = 10;
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -29,6 +43,10 @@ impl Default for AppSettings {
             silent_start_enabled: false,
             debug_mode: false,
             private_mode: default_private_mode(),
+            snapshot_depth: DEFAULT_SNAPSHOT_DEPTH,
+            auto_refresh_backups: false,
+            expiry_warning_minutes: DEFAULT_EXPIRY_WARNING_MINUTES,
+            expiry_notifications_enabled: false,
         }
     }
 }
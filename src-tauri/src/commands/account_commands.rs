@@ -1,88 +1,146 @@
 //! 账户基础命令：查询、备份、恢复、切换、清理
 
-use crate::antigravity::account::decode_jetski_state_proto;
-use base64::Engine;
-use prost::Message;
-use rusqlite::{Connection, OptionalExtension};
-use serde_json::{from_str, Value};
-use std::fs;
+use crate::antigravity::account::decode_session_response;
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::account_view::{AccountDetail, AccountProfile, AccountSummary};
+use crate::antigravity::active::ActiveAccountStatus;
+use crate::antigravity::backup::{capture_backup, content_hash, write_backup_file};
+use crate::antigravity::compat::{self, SessionFormatReport};
+use crate::antigravity::expiry::{ExpiryInfo, ExpiryStatus};
+use crate::antigravity::model_catalog::{AccountModelEntry, ModelCatalog};
+use crate::antigravity::quarantine::AccountFileIssue;
+use crate::antigravity::restore::PendingRestore;
+use crate::antigravity::state_store::SqliteStateStore;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::Manager;
 use tauri::State;
 use tracing::instrument;
 
-/// 获取所有 Antigravity 账户（解码 jetskiStateSync.agentManagerInitState，返回完整 SessionResponse JSON）
+/// 账户列表排序方式
+#[derive(Deserialize, Debug, Clone, Copy, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountSortKey {
+    /// 按保存时间（最新的在前）
+    #[default]
+    SavedAt,
+    /// 按过期时间（最先过期的在前，没有过期时间的排最后）
+    ExpirySoonest,
+    /// 按过期时间（最晚过期的在前，没有过期时间的排最后）
+    ExpiryLatest,
+}
+
+/// 账户列表结果
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AntigravityAccountList {
+    pub accounts: Vec<AccountSummary>,
+    /// 问题文件：隔离区中的全部文件，以及本次读取时暂时无法读取而跳过的文件
+    pub issues: Vec<AccountFileIssue>,
+}
+
+/// 获取所有 Antigravity 账户（解码 jetskiStateSync.agentManagerInitState，返回账户视图模型）
+///
+/// 解码结果缓存在 AppState 的账户索引中，只有新增或变化的备份文件才会重新解码；
+/// 损坏的文件会被移到隔离区并在 issues 中列出，暂时无法读取的文件只跳过，都不影响其余账户；
+/// 可按过期时间排序，并按过期状态筛选
 #[tauri::command]
-#[instrument]
+#[specta::specta]
+#[instrument(skip(app))]
 pub async fn get_antigravity_accounts(
+    app: tauri::AppHandle,
     state: State<'_, crate::AppState>,
-) -> Result<Vec<Value>, String> {
+    sort_by: Option<AccountSortKey>,
+    expiry_filter: Option<Vec<ExpiryStatus>>,
+) -> Result<AntigravityAccountList, String> {
     tracing::debug!("📋 开始获取所有 Antigravity 账户");
 
     let start_time = std::time::Instant::now();
 
     let result = async {
-        let mut accounts: Vec<(std::time::SystemTime, Value)> = Vec::new();
-
-        // 获取备份目录路径
-        let antigravity_dir = state.config_dir.join("antigravity-accounts");
+        let warning_minutes = app
+            .state::<crate::app_settings::AppSettingsManager>()
+            .get_settings()
+            .expiry_warning_minutes;
+
+        let current = state.current_account_id.lock().unwrap().clone();
+        let (mut accounts, stats, ids) = {
+            let mut index = state.antigravity_accounts.lock().unwrap();
+            let stats = index.refresh(&state.accounts_dir())?;
+            let accounts: Vec<AccountSummary> = index
+                .accounts()
+                .into_iter()
+                .map(|account| {
+                    AccountSummary::new(
+                        account.id.clone(),
+                        &account.profile,
+                        ExpiryInfo::now(account.metadata.expiry_timestamp, warning_minutes),
+                        current.as_ref() == Some(&account.id),
+                        account.saved_at,
+                    )
+                })
+                .filter(|account| {
+                    expiry_filter
+                        .as_ref()
+                        .is_none_or(|statuses| statuses.contains(&account.expiry.status))
+                })
+                .collect();
+            (accounts, stats, index.ids())
+        };
 
-        if !antigravity_dir.exists() {
-            tracing::info!("📂 备份目录不存在，返回空列表");
-            return Ok(Vec::new());
+        // 索引已按保存时间排好，这里用稳定排序只调整过期时间的先后
+        match sort_by.unwrap_or_default() {
+            AccountSortKey::SavedAt => {}
+            AccountSortKey::ExpirySoonest => accounts.sort_by_key(|account| {
+                let ts = account.expiry.expiry_timestamp;
+                (ts.is_none(), ts)
+            }),
+            AccountSortKey::ExpiryLatest => accounts.sort_by_key(|account| {
+                let ts = account.expiry.expiry_timestamp;
+                (ts.is_none(), std::cmp::Reverse(ts))
+            }),
         }
 
-        // 读取目录中的所有 JSON 文件
-        let entries =
-            fs::read_dir(&antigravity_dir).map_err(|e| format!("读取备份目录失败: {}", e))?;
-
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
-            let path = entry.path();
-
-            // 只处理 JSON 文件
-            if path.extension().is_some_and(|ext| ext == "json") {
-                let file_name = match path.file_stem() {
-                    Some(name) => name.to_string_lossy().to_string(),
-                    None => continue,
-                };
-
-                tracing::debug!("📄 正在解析备份文件: {}", file_name);
-
-                // 读取并解析 JSON 文件
-                let content = fs::read_to_string(&path)
-                    .map_err(|e| format!("读取文件失败 {}: {}", file_name, e))?;
-
-                let backup_data: Value = from_str(&content)
-                    .map_err(|e| format!("解析 JSON 失败 {}: {}", file_name, e))?;
-
-                let jetski_state = backup_data
-                    .get("jetskiStateSync.agentManagerInitState")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        format!(
-                            "备份文件 {} 缺少 jetskiStateSync.agentManagerInitState",
-                            file_name
-                        )
-                    })?;
-
-                let decoded = decode_jetski_state_proto(jetski_state)?;
-
-                let modified_time = fs::metadata(&path)
-                    .and_then(|m| m.modified())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+        for issue in &stats.issues {
+            tracing::warn!(
+                file = %issue.file_name,
+                reason = %issue.reason,
+                quarantined_as = ?issue.quarantined_as,
+                "⚠️ 备份文件无法读取，已跳过"
+            );
+        }
 
-                accounts.push((modified_time, decoded));
+        tracing::debug!(
+            decoded = stats.decoded,
+            touched = stats.touched,
+            removed = stats.removed,
+            issues = stats.issues.len(),
+            "🎉 成功加载 {} 个账户",
+            accounts.len()
+        );
 
-                tracing::info!("✅ 成功解析账户: {}", file_name);
+        // 账户集合变化时同步托盘菜单
+        if stats.membership_changed {
+            let emails = ids.iter().map(|id| id.to_string()).collect();
+            if let Err(e) = crate::system_tray::update_tray_menu(&app, emails) {
+                tracing::warn!(error = %e, "同步托盘账户列表失败");
             }
         }
 
-        // 按文件修改时间排序（最新的在前），仅返回解码后的对象
-        accounts.sort_by(|a, b| b.0.cmp(&a.0));
-        let decoded_only: Vec<Value> = accounts.into_iter().map(|(_, decoded)| decoded).collect();
-
-        tracing::debug!("🎉 成功加载 {} 个账户", decoded_only.len());
-
-        Ok(decoded_only)
+        // 隔离区以磁盘为准：后台同步（监控、过期检查等）隔离的文件也要报告给前端
+        let quarantined =
+            crate::antigravity::quarantine::quarantined_issues().unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "读取隔离区失败");
+                Vec::new()
+            });
+        let issues = stats
+            .issues
+            .into_iter()
+            .filter(|issue| issue.quarantined_as.is_none())
+            .chain(quarantined)
+            .collect();
+
+        Ok(AntigravityAccountList { accounts, issues })
     }
     .await;
 
@@ -92,7 +150,7 @@ pub async fn get_antigravity_accounts(
         Ok(accounts) => {
             tracing::debug!(
                 duration_ms = duration.as_millis(),
-                account_count = accounts.len(),
+                account_count = accounts.accounts.len(),
                 "获取账户列表完成"
             );
             Ok(accounts)
@@ -108,10 +166,32 @@ pub async fn get_antigravity_accounts(
     }
 }
 
+/// 汇总所有已保存账户的可用模型与套餐，并按模型、按套餐做跨账户对比
+#[tauri::command]
+#[specta::specta]
+pub async fn get_account_model_catalog(
+    state: State<'_, crate::AppState>,
+) -> Result<ModelCatalog, String> {
+    crate::log_async_command!("get_account_model_catalog", async {
+        let mut index = state.antigravity_accounts.lock().unwrap();
+        index.refresh(&state.accounts_dir())?;
+        let entries = index
+            .accounts()
+            .into_iter()
+            .map(|account| AccountModelEntry::new(account.id.clone(), &account.profile))
+            .collect();
+        Ok(ModelCatalog::build(entries))
+    })
+}
+
 /// 获取当前 Antigravity 账户信息
 #[tauri::command]
-#[instrument]
-pub async fn get_current_antigravity_account_info() -> Result<Value, String> {
+#[specta::specta]
+#[instrument(skip(app))]
+pub async fn get_current_antigravity_account_info(
+    app: tauri::AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<AccountDetail, String> {
     tracing::info!("开始获取当前 Antigravity 信息");
 
     let start_time = std::time::Instant::now();
@@ -137,27 +217,41 @@ pub async fn get_current_antigravity_account_info() -> Result<Value, String> {
             ));
         }
 
-        // 连接到 SQLite 数据库并获取认证信息
-        let conn = Connection::open(&app_data)
-            .map_err(|e| format!("连接数据库失败 ({}): {}", app_data.display(), e))?;
-
-        // jetski 状态（可选）
-        let jetski_state: Option<String> = conn
-            .query_row(
-                "SELECT value FROM ItemTable WHERE key = 'jetskiStateSync.agentManagerInitState'",
-                [],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|e| format!("查询 jetskiStateSync.agentManagerInitState 失败: {}", e))?;
-
-        let state_str = jetski_state
+        // 读取 jetski 状态（必需）
+        let state_str = crate::antigravity::active::read_agent_state(&app_data)?
             .ok_or_else(|| "未找到 jetskiStateSync.agentManagerInitState".to_string())?;
 
         // 解码 jetski 状态（base64 + proto）；失败直接报错
-        let decoded = decode_jetski_state_proto(&state_str)?;
-
-        Ok(serde_json::json!(decoded))
+        let profile = AccountProfile::from_session(&decode_session_response(&state_str)?);
+        if profile.email.is_empty() {
+            return Err("jetskiStateSync 中未找到邮箱字段".to_string());
+        }
+        let id = AccountId::from_email(&profile.email)?;
+
+        // 只查缓存的索引，不为此重新扫描账户目录
+        let saved = state
+            .antigravity_accounts
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|account| (account.saved_at, account.content_hash.clone()));
+        let active = saved
+            .as_ref()
+            .is_some_and(|(_, hash)| *hash == content_hash(&state_str));
+
+        let warning_minutes = app
+            .state::<crate::app_settings::AppSettingsManager>()
+            .get_settings()
+            .expiry_warning_minutes;
+        let expiry = ExpiryInfo::now(profile.expiry_timestamp, warning_minutes);
+
+        Ok(AccountDetail::new(
+            id,
+            profile,
+            expiry,
+            active,
+            saved.map(|(saved_at, _)| saved_at),
+        ))
     }
     .await;
 
@@ -182,10 +276,37 @@ pub async fn get_current_antigravity_account_info() -> Result<Value, String> {
     }
 }
 
+/// 识别 Antigravity 当前正在使用的已保存账户，并判断保存的副本是否过期
+#[tauri::command]
+#[specta::specta]
+pub async fn get_active_antigravity_account(
+    app: tauri::AppHandle,
+) -> Result<ActiveAccountStatus, String> {
+    crate::log_async_command!("get_active_antigravity_account", async {
+        crate::antigravity::active::refresh_active_account(&app)
+    })
+}
+
+/// 检查实时会话和全部备份是否符合预期的会话格式，并据此更新写入保护
+#[tauri::command]
+#[specta::specta]
+pub async fn check_session_format(
+    app: tauri::AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<SessionFormatReport, String> {
+    crate::log_async_command!("check_session_format", async {
+        Ok(compat::run_check(&app, &state.accounts_dir()))
+    })
+}
+
 /// 备份当前 Antigravity 账户
 #[tauri::command]
+#[specta::specta]
 #[instrument]
-pub async fn save_antigravity_current_account() -> Result<String, String> {
+pub async fn save_antigravity_current_account(
+    app: tauri::AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<String, String> {
     tracing::info!("📥 开始保存 jetskiStateSync.agentManagerInitState");
 
     let start_time = std::time::Instant::now();
@@ -211,60 +332,58 @@ pub async fn save_antigravity_current_account() -> Result<String, String> {
             ));
         }
 
-        // 连接到 SQLite 数据库并获取认证信息
-        let conn = Connection::open(&app_data)
-            .map_err(|e| format!("连接数据库失败 ({}): {}", app_data.display(), e))?;
-
-        // jetski 状态（必需）
-        let jetski_state: String = conn
-            .query_row(
-                "SELECT value FROM ItemTable WHERE key = 'jetskiStateSync.agentManagerInitState'",
-                [],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|e| format!("查询 jetskiStateSync.agentManagerInitState 失败: {}", e))?
-            .ok_or_else(|| "未找到 jetskiStateSync.agentManagerInitState".to_string())?;
-
-        // 从 jetski proto 解码邮箱（仅用于文件名）
-        let bytes = base64::engine::general_purpose::STANDARD
-            .decode(jetski_state.trim())
-            .map_err(|e| format!("jetskiStateSync Base64 解码失败: {}", e))?;
-        let msg = crate::proto::SessionResponse::decode(bytes.as_slice())
-            .map_err(|e| format!("jetskiStateSync Protobuf 解码失败: {}", e))?;
+        // 读取 jetski 状态并构建带元数据的备份信封（邮箱同时用作文件名）；会话格式变化时拒绝
+        let backup = capture_backup(&SqliteStateStore::open_read_only(&app_data)?, &app_data)?;
 
-        let email = msg
-            .context
-            .as_ref()
-            .and_then(|c| {
-                if c.email.is_empty() {
-                    None
-                } else {
-                    Some(c.email.as_str())
-                }
-            })
-            .ok_or_else(|| "jetskiStateSync 中未找到邮箱字段，无法确定备份文件名".to_string())?;
-
-        // 直接保存原始字符串，不解码，文件名与原逻辑保持：{email}.json
+        // 文件名与原逻辑保持：{email}.json（邮箱需通过账户标识校验）
+        let account = AccountId::from_email(&backup.metadata.email)?;
         let accounts_dir = crate::directories::get_accounts_directory();
         if let Err(e) = std::fs::create_dir_all(&accounts_dir) {
             return Err(format!("创建账户目录失败: {}", e));
         }
 
-        let account_file = accounts_dir.join(format!("{email}.json"));
-        let content = serde_json::json!({
-            "jetskiStateSync.agentManagerInitState": jetski_state
-        });
-        std::fs::write(
+        let account_file = account.resolve_in(&accounts_dir)?;
+
+        // 覆盖前把内容不同的旧备份归档为快照
+        crate::antigravity::snapshot::archive_before_overwrite(
+            &account,
             &account_file,
-            serde_json::to_string_pretty(&content).unwrap(),
-        )
-        .map_err(|e| format!("写入 jetski 状态失败: {}", e))?;
+            &backup,
+            crate::antigravity::snapshot::configured_depth(&app),
+        )?;
+        write_backup_file(&account_file, &backup)
+            .map_err(|e| format!("写入 jetski 状态失败: {}", e))?;
+
+        // 邮箱变更后，同一账户（id_token sub 相同）以旧邮箱保存的备份仍然存在
+        let profile = AccountProfile::from_session(&decode_session_response(&backup.agent_state)?);
+        let aliases: Vec<String> = profile
+            .subject()
+            .map(|subject| {
+                state
+                    .antigravity_accounts
+                    .lock()
+                    .unwrap()
+                    .by_subject(subject)
+                    .into_iter()
+                    .filter(|saved| saved.id != account)
+                    .map(|saved| saved.id.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !aliases.is_empty() {
+            tracing::warn!(account = %account, aliases = ?aliases, "同一账户还以其他邮箱保存过备份");
+        }
+
+        // 刚保存的就是当前登录的账户
+        state.set_current_account(Some(account));
 
-        let message = format!(
+        let mut message = format!(
             "已保存 jetskiStateSync.agentManagerInitState 到 {}",
             account_file.display()
         );
+        if !aliases.is_empty() {
+            message.push_str(&format!("；该账户还以旧邮箱保存过: {}", aliases.join(", ")));
+        }
         tracing::info!(file = %account_file.display(), "✅ 保存 jetski 状态完成");
         Ok(message)
     }
@@ -294,27 +413,46 @@ pub async fn save_antigravity_current_account() -> Result<String, String> {
 
 /// 清除所有 Antigravity 数据
 #[tauri::command]
+#[specta::specta]
 pub async fn clear_all_antigravity_data() -> Result<String, String> {
     crate::antigravity::cleanup::clear_all_antigravity_data().await
 }
 
 /// 恢复 Antigravity 账户
 #[tauri::command]
+#[specta::specta]
 pub async fn restore_antigravity_account(account_name: String) -> Result<String, String> {
     tracing::debug!(target: "account::restore", account_name = %account_name, "调用 restore_antigravity_account");
 
-    // 1. 构建备份文件路径
-    let accounts_dir = crate::directories::get_accounts_directory();
-    let account_file = accounts_dir.join(format!("{account_name}.json"));
+    // 1. 校验账户标识并定位备份文件
+    let account = AccountId::parse(&account_name)?;
+    let account_file = account.backup_path()?;
 
     // 2. 调用统一的恢复函数
     crate::antigravity::restore::save_antigravity_account_to_file(account_file).await
 }
 
-/// 切换到 Antigravity 账户（调用 restore_antigravity_account）
+/// 切换到 Antigravity 账户
+///
+/// 清除旧登录信息与写入新会话在同一个事务中完成，任何一步失败都不会让用户停在已登出的状态
 #[tauri::command]
-pub async fn switch_to_antigravity_account(account_name: String) -> Result<String, String> {
+#[specta::specta]
+pub async fn switch_to_antigravity_account(
+    app: tauri::AppHandle,
+    account_name: String,
+    state: State<'_, crate::AppState>,
+) -> Result<String, String> {
     crate::log_async_command!("switch_to_antigravity_account", async {
+        // 0. 先读取并校验账户备份，避免关闭进程后才发现账户无效
+        let account = AccountId::parse(&account_name)?;
+        let account_file = account.backup_path()?;
+        if !account_file.exists() {
+            return Err(format!("账户备份不存在: {}", account));
+        }
+        // 会话格式变化时绝不能清库后注入不兼容的数据
+        compat::check_before_switch(&app, &account)?;
+        let pending = PendingRestore::load(&account_file)?;
+
         // 1. 关闭 Antigravity 进程 (如果存在)
         let kill_result = match crate::platform::kill_antigravity_processes() {
             Ok(result) => {
@@ -340,26 +478,23 @@ pub async fn switch_to_antigravity_account(account_name: String) -> Result<Strin
         // 等待一秒确保进程完全关闭
         tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
 
-        // 2. 清除原来的数据库
-        clear_all_antigravity_data().await?;
-        tracing::warn!(target: "account::switch::step2", "Antigravity 数据库清除完成");
-
-        // 3. 恢复指定账户到 Antigravity 数据库
-        let restore_result = restore_antigravity_account(account_name.clone()).await?;
-        tracing::debug!(target: "account::switch::step3", result = %restore_result, "账户数据恢复完成");
+        // 2. 清除原来的登录信息并写入指定账户（主库和账户库要么都完成，要么都保持原样）
+        let restore_result = pending.switch()?;
+        tracing::debug!(target: "account::switch::step2", result = %restore_result, "账户数据写入完成");
+        state.set_current_account(Some(account.clone()));
 
         // 等待一秒确保数据库操作完成
         tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
 
-        // 4. 重新启动 Antigravity 进程
+        // 3. 重新启动 Antigravity 进程
         let start_result = crate::antigravity::starter::start_antigravity();
         let start_message = match start_result {
             Ok(result) => {
-                tracing::debug!(target: "account::switch::step4", result = %result, "Antigravity 启动成功");
+                tracing::debug!(target: "account::switch::step3", result = %result, "Antigravity 启动成功");
                 result
             }
             Err(e) => {
-                tracing::warn!(target: "account::switch::step4", error = %e, "Antigravity 启动失败");
+                tracing::warn!(target: "account::switch::step3", error = %e, "Antigravity 启动失败");
                 format!("启动失败: {}", e)
             }
         };
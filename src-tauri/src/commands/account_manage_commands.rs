@@ -1,14 +1,26 @@
 //! 账户备份/导入导出与加解密命令
 
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::{load_backup_file, write_backup_file, AccountBackup};
+use crate::antigravity::bundle::{self, BundleManifest};
+use crate::antigravity::import::{self, ImportAction, ImportEntry, ImportStrategy};
+use crate::antigravity::inventory::{self, InventoryFormat};
+use crate::antigravity::legacy;
+use crate::antigravity::session_edit::EditableSession;
+use crate::antigravity::snapshot;
 use crate::log_async_command;
+use crate::utils::crypto;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use specta::Type;
 use std::fs;
+use std::path::Path;
 use std::time::SystemTime;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 /// 备份数据收集结构
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Type)]
 pub struct AccountExportedData {
     filename: String,
     #[serde(rename = "content")]
@@ -18,14 +30,42 @@ pub struct AccountExportedData {
 }
 
 /// 恢复结果
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Debug, Type)]
 pub struct RestoreResult {
     #[serde(rename = "restoredCount")]
     restored_count: u32,
     failed: Vec<FailedAccountExportedData>,
+    /// 本次使用的冲突处理策略
+    strategy: ImportStrategy,
+    /// 为 true 时只生成计划，未写入任何文件
+    #[serde(rename = "dryRun")]
+    dry_run: bool,
+    /// 每个条目的比较结果与执行的操作
+    entries: Vec<ImportEntry>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl RestoreResult {
+    fn new(entries: Vec<ImportEntry>, strategy: ImportStrategy, dry_run: bool) -> Self {
+        let failed = entries
+            .iter()
+            .filter(|entry| entry.action == ImportAction::Failed)
+            .map(|entry| FailedAccountExportedData {
+                filename: entry.file_name.clone(),
+                error: entry.reason.clone().unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            restored_count: entries.iter().filter(|e| e.is_restored()).count() as u32,
+            failed,
+            strategy,
+            dry_run,
+            entries,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Type)]
 pub struct FailedAccountExportedData {
     filename: String,
     error: String,
@@ -33,13 +73,17 @@ pub struct FailedAccountExportedData {
 
 /// 收集所有账户文件的完整内容, 用于导出
 #[tauri::command]
+#[specta::specta]
 pub async fn collect_account_contents(
     state: State<'_, crate::AppState>,
 ) -> Result<Vec<AccountExportedData>, String> {
     let mut backups_with_content = Vec::new();
 
+    // 保险库锁定时无法读取账户文件
+    crate::antigravity::vault::ensure_readable()?;
+
     // 读取Antigravity账户目录中的JSON文件
-    let antigravity_dir = state.config_dir.join("antigravity-accounts");
+    let antigravity_dir = state.accounts_dir();
 
     if !antigravity_dir.exists() {
         return Ok(backups_with_content);
@@ -61,25 +105,20 @@ pub async fn collect_account_contents(
                 continue;
             }
 
-            match fs::read_to_string(&path).map_err(|e| format!("读取文件失败 {}: {}", filename, e))
-            {
-                Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
-                    Ok(json_value) => {
-                        backups_with_content.push(AccountExportedData {
-                            filename,
-                            content: json_value,
-                            timestamp: SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs(),
-                        });
-                    }
-                    Err(e) => {
-                        tracing::warn!(target: "backup::scan", filename = %filename, error = %e, "跳过损坏的备份文件");
-                    }
-                },
-                Err(_) => {
-                    tracing::warn!(target: "backup::scan", filename = %filename, "跳过无法读取的文件");
+            // 统一经由备份信封读取，旧版单键文件导出前会先被升级
+            match load_backup_file(&path) {
+                Ok(backup) => {
+                    backups_with_content.push(AccountExportedData {
+                        filename,
+                        content: backup.to_value()?,
+                        timestamp: SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs(),
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(target: "backup::scan", filename = %filename, error = %e, "跳过损坏的备份文件");
                 }
             }
         }
@@ -89,61 +128,300 @@ pub async fn collect_account_contents(
 }
 
 /// 恢复备份文件到本地
+///
+/// `strategy` 缺省为覆盖；`dry_run` 为 true 时只返回每个条目的导入计划
 #[tauri::command]
+#[specta::specta]
 pub async fn restore_backup_files(
     account_file_data: Vec<AccountExportedData>,
+    strategy: Option<ImportStrategy>,
+    dry_run: Option<bool>,
+    app: AppHandle,
     state: State<'_, crate::AppState>,
 ) -> Result<RestoreResult, String> {
-    let mut results = RestoreResult {
-        restored_count: 0,
-        failed: Vec::new(),
-    };
+    // 保险库锁定时无法写入账户文件
+    crate::antigravity::vault::ensure_readable()?;
+
+    // 导入内容同样转换为当前备份信封格式（旧版单键内容在此升级）
+    let items = account_file_data.into_iter().map(|account_file| {
+        let imported_at =
+            DateTime::from_timestamp(account_file.timestamp as i64, 0).unwrap_or_else(Utc::now);
+        let backup = AccountId::from_file_name(&account_file.filename)
+            .and_then(|_| AccountBackup::from_value(account_file.content, imported_at))
+            .map(|(backup, _)| backup);
+        (account_file.filename, backup)
+    });
+
+    let strategy = strategy.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(false);
+    let entries = import::import_backups(
+        items,
+        &state.accounts_dir(),
+        strategy,
+        dry_run,
+        snapshot::configured_depth(&app),
+    )?;
+    Ok(RestoreResult::new(entries, strategy, dry_run))
+}
 
-    // 获取目标目录
-    let antigravity_dir = state.config_dir.join("antigravity-accounts");
+/// 校验前端传入的账户选择（为空表示全部账户）
+fn parse_selection(accounts: Option<Vec<String>>) -> Result<Option<Vec<AccountId>>, String> {
+    accounts
+        .map(|accounts| accounts.iter().map(|a| AccountId::parse(a)).collect())
+        .transpose()
+}
 
-    // 确保目录存在
-    if let Err(e) = fs::create_dir_all(&antigravity_dir) {
-        return Err(format!("创建目录失败: {}", e));
-    }
+/// 导出账户为可移植导出包并直接写入磁盘
+///
+/// `accounts` 为空时导出全部账户；提供 `password` 时整包加密
+#[tauri::command]
+#[specta::specta]
+pub async fn export_accounts_bundle(
+    path: String,
+    accounts: Option<Vec<String>>,
+    password: Option<String>,
+    state: State<'_, crate::AppState>,
+) -> Result<BundleManifest, String> {
+    log_async_command!("export_accounts_bundle", async {
+        // 保险库锁定时无法读取账户文件
+        crate::antigravity::vault::ensure_readable()?;
+
+        let selected = parse_selection(accounts)?;
+        let bundle = bundle::build(&state.accounts_dir(), selected.as_deref())?;
+        if bundle.entries.is_empty() {
+            return Err("没有可导出的账户".to_string());
+        }
+
+        bundle::write(Path::new(&path), &bundle, password.as_deref())?;
+        tracing::info!(
+            target: "backup::bundle",
+            path = %path,
+            count = bundle.entries.len(),
+            encrypted = password.is_some(),
+            "导出包已写入"
+        );
+        Ok(bundle.manifest)
+    })
+}
 
-    // 遍历每个备份
-    for account_file in account_file_data {
-        let file_path = antigravity_dir.join(&account_file.filename);
+/// 从磁盘读取导出包并导入其中校验通过的账户
+///
+/// 损坏的条目记录在 `failed` 中，不影响其余条目导入；`strategy`、`dry_run` 同 `restore_backup_files`
+#[tauri::command]
+#[specta::specta]
+pub async fn import_accounts_bundle(
+    path: String,
+    password: Option<String>,
+    strategy: Option<ImportStrategy>,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<RestoreResult, String> {
+    log_async_command!("import_accounts_bundle", async {
+        // 保险库锁定时无法写入账户文件
+        crate::antigravity::vault::ensure_readable()?;
+
+        let (manifest, items) = bundle::read(Path::new(&path), password.as_deref())?;
+        tracing::info!(
+            target: "backup::bundle",
+            format_version = manifest.format_version,
+            agent_version = %manifest.agent_version,
+            count = items.len(),
+            "开始导入导出包"
+        );
+
+        let strategy = strategy.unwrap_or_default();
+        let dry_run = dry_run.unwrap_or(false);
+        let entries = import::import_backups(
+            items.into_iter().map(|item| (item.file_name, item.backup)),
+            &state.accounts_dir(),
+            strategy,
+            dry_run,
+            snapshot::configured_depth(&app),
+        )?;
+        Ok(RestoreResult::new(entries, strategy, dry_run))
+    })
+}
 
-        match fs::write(
-            &file_path,
-            serde_json::to_string_pretty(&account_file.content).unwrap_or_default(),
+/// 从指定的 state.vscdb（或 .vscdb.backup）只读导入账户，按解码出的邮箱加入账户列表
+///
+/// `strategy`、`dry_run` 同 `restore_backup_files`
+#[tauri::command]
+#[specta::specta]
+pub async fn import_account_from_database(
+    db_path: String,
+    strategy: Option<ImportStrategy>,
+    dry_run: Option<bool>,
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<RestoreResult, String> {
+    log_async_command!("import_account_from_database", async {
+        // 保险库锁定时无法写入账户文件
+        crate::antigravity::vault::ensure_readable()?;
+
+        let strategy = strategy.unwrap_or_default();
+        let dry_run = dry_run.unwrap_or(false);
+        // 会话格式变化时不把无法识别的会话写成备份；dry-run 只读，不受影响
+        if !dry_run {
+            crate::antigravity::compat::ensure_writes_allowed()?;
+        }
+
+        let db_path = Path::new(&db_path);
+        let backup = import::backup_from_database(db_path)?;
+        let file_name = db_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let entries = import::import_backups(
+            [(file_name, Ok(backup))],
+            &state.accounts_dir(),
+            strategy,
+            dry_run,
+            snapshot::configured_depth(&app),
+        )?;
+        Ok(RestoreResult::new(entries, strategy, dry_run))
+    })
+}
+
+/// 扫描旧版账户目录中迁移遗留的文件，返回它们与当前账户的差异（不写入）
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_legacy_account_files(
+    strategy: Option<ImportStrategy>,
+    state: State<'_, crate::AppState>,
+) -> Result<RestoreResult, String> {
+    log_async_command!("scan_legacy_account_files", async {
+        crate::antigravity::vault::ensure_readable()?;
+
+        let strategy = strategy.unwrap_or_default();
+        let entries = legacy::merge(&state.accounts_dir(), strategy, true, false, 0)?;
+        Ok(RestoreResult::new(entries, strategy, true))
+    })
+}
+
+/// 按冲突策略合并旧版账户目录中的遗留文件；`cleanup` 为 true 时删除已合并的遗留文件
+#[tauri::command]
+#[specta::specta]
+pub async fn merge_legacy_account_files(
+    strategy: Option<ImportStrategy>,
+    cleanup: Option<bool>,
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<RestoreResult, String> {
+    log_async_command!("merge_legacy_account_files", async {
+        // 保险库锁定时无法写入账户文件
+        crate::antigravity::vault::ensure_readable()?;
+
+        let strategy = strategy.unwrap_or_default();
+        let entries = legacy::merge(
+            &state.accounts_dir(),
+            strategy,
+            false,
+            cleanup.unwrap_or(false),
+            snapshot::configured_depth(&app),
+        )?;
+        Ok(RestoreResult::new(entries, strategy, false))
+    })
+}
+
+/// 导出脱敏的账户清单（JSON 或 CSV），不含任何令牌，无法作为凭据导入
+///
+/// 隐私模式开启时邮箱会被打码；`accounts` 为空时导出全部账户，返回导出的账户数
+#[tauri::command]
+#[specta::specta]
+pub async fn export_account_inventory(
+    path: String,
+    format: Option<InventoryFormat>,
+    accounts: Option<Vec<String>>,
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<usize, String> {
+    log_async_command!("export_account_inventory", async {
+        // 保险库锁定时无法读取账户文件
+        crate::antigravity::vault::ensure_readable()?;
+
+        let selected = parse_selection(accounts)?;
+        let backups: Vec<AccountBackup> =
+            bundle::collect_backups(&state.accounts_dir(), selected.as_deref())?
+                .into_iter()
+                .map(|(_, backup)| backup)
+                .collect();
+        if backups.is_empty() {
+            return Err("没有可导出的账户".to_string());
+        }
+
+        let private_mode = app
+            .state::<crate::app_settings::AppSettingsManager>()
+            .get_settings()
+            .private_mode;
+        inventory::export(
+            Path::new(&path),
+            &backups,
+            format.unwrap_or_default(),
+            private_mode,
         )
-        .map_err(|e| format!("写入文件失败: {}", e))
-        {
-            Ok(_) => {
-                results.restored_count += 1;
-            }
-            Err(e) => {
-                results.failed.push(FailedAccountExportedData {
-                    filename: account_file.filename,
-                    error: e,
-                });
-            }
+    })
+}
+
+/// 删除已保存账户会话中的历史记录以缩小备份，其余字段（包括 proto 未建模的字段）逐字节保留
+///
+/// 返回 agentManagerInitState 减少的字节数（没有历史记录时为 0）
+#[tauri::command]
+#[specta::specta]
+pub async fn compact_account_session(
+    account_name: String,
+    app: AppHandle,
+) -> Result<usize, String> {
+    log_async_command!("compact_account_session", async {
+        crate::antigravity::compat::ensure_writes_allowed()?;
+        let account = AccountId::parse(&account_name)?;
+        let account_file = account.backup_path()?;
+        let backup = load_backup_file(&account_file)?;
+
+        let mut session = EditableSession::from_agent_state(&backup.agent_state)?;
+        if !session.drop_history() {
+            return Ok(0);
         }
-    }
 
-    Ok(results)
+        // 重新构建信封会再次校验会话数据；保存时间和来源沿用原备份
+        let mut compacted = AccountBackup::new(session.to_agent_state(), None)?;
+        compacted.saved_at = backup.saved_at;
+        compacted.source_db = backup.source_db.clone();
+
+        snapshot::archive_before_overwrite(
+            &account,
+            &account_file,
+            &compacted,
+            snapshot::configured_depth(&app),
+        )?;
+        write_backup_file(&account_file, &compacted)?;
+        Ok(backup
+            .agent_state
+            .len()
+            .saturating_sub(compacted.agent_state.len()))
+    })
 }
 
 /// 删除指定备份
 #[tauri::command]
+#[specta::specta]
 pub async fn delete_backup(
     name: String,
     state: State<'_, crate::AppState>,
 ) -> Result<String, String> {
     // 只删除Antigravity账户JSON文件
-    let antigravity_dir = state.config_dir.join("antigravity-accounts");
-    let antigravity_file = antigravity_dir.join(format!("{}.json", name));
+    let antigravity_dir = state.accounts_dir();
+    let account = AccountId::parse(&name)?;
+    let antigravity_file = account.resolve_in(&antigravity_dir)?;
 
     if antigravity_file.exists() {
         fs::remove_file(&antigravity_file).map_err(|e| format!("删除用户文件失败: {}", e))?;
+        crate::antigravity::snapshot::remove_all_snapshots(&account)?;
+        let mut current = state.current_account_id.lock().unwrap();
+        if current.as_ref() == Some(&account) {
+            *current = None;
+        }
         Ok(format!("删除用户成功: {}", name))
     } else {
         Err("用户文件不存在".to_string())
@@ -152,8 +430,9 @@ pub async fn delete_backup(
 
 /// 清空所有备份
 #[tauri::command]
+#[specta::specta]
 pub async fn clear_all_backups(state: State<'_, crate::AppState>) -> Result<String, String> {
-    let antigravity_dir = state.config_dir.join("antigravity-accounts");
+    let antigravity_dir = state.accounts_dir();
 
     if antigravity_dir.exists() {
         // 读取目录中的所有文件
@@ -172,6 +451,15 @@ pub async fn clear_all_backups(state: State<'_, crate::AppState>) -> Result<Stri
             }
         }
 
+        // 历史快照一并清空
+        let snapshots_dir = crate::directories::get_snapshots_directory();
+        if snapshots_dir.exists() {
+            fs::remove_dir_all(&snapshots_dir).map_err(|e| format!("删除快照目录失败: {}", e))?;
+        }
+
+        state.antigravity_accounts.lock().unwrap().clear();
+        state.set_current_account(None);
+
         Ok(format!(
             "已清空所有用户备份，共删除 {} 个文件",
             deleted_count
@@ -182,66 +470,81 @@ pub async fn clear_all_backups(state: State<'_, crate::AppState>) -> Result<Stri
 }
 
 /// 加密配置数据（用于账户导出）
+///
+/// 使用 Argon2id 派生密钥 + XChaCha20-Poly1305 加密，输出带版本号的 JSON 信封
 #[tauri::command]
+#[specta::specta]
 pub async fn encrypt_config_data(json_data: String, password: String) -> Result<String, String> {
     log_async_command!("encrypt_config_data", async {
-        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
-
         if password.is_empty() {
             return Err("密码不能为空".to_string());
         }
 
-        let password_bytes = password.as_bytes();
-        let mut result = Vec::new();
-
-        // XOR 加密
-        for (i, byte) in json_data.as_bytes().iter().enumerate() {
-            let key_byte = password_bytes[i % password_bytes.len()];
-            result.push(byte ^ key_byte);
-        }
-
-        // Base64 编码
-        let encoded = BASE64.encode(&result);
-
-        Ok(encoded)
+        crypto::encrypt_with_password(json_data.as_bytes(), &password)
     })
 }
 
+/// 解密结果
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedConfig {
+    pub data: String,
+    /// 是否为旧版 XOR 加密的文件
+    pub legacy_format: bool,
+    /// 需要提示用户的警告
+    pub warning: Option<String>,
+}
+
 /// 解密配置数据（用于账户导入）
+///
+/// 旧版 XOR 加密的文件仍可导入，但会附带警告，建议用户重新导出
 #[tauri::command]
+#[specta::specta]
 pub async fn decrypt_config_data(
     encrypted_data: String,
     password: String,
-) -> Result<String, String> {
+) -> Result<DecryptedConfig, String> {
     log_async_command!("decrypt_config_data", async {
-        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
-
         if password.is_empty() {
             return Err("密码不能为空".to_string());
         }
 
-        let decoded = BASE64
-            .decode(encrypted_data)
-            .map_err(|_| "Base64 解码失败".to_string())?;
-
-        let password_bytes = password.as_bytes();
-        let mut result = Vec::new();
-
-        for (i, byte) in decoded.iter().enumerate() {
-            let key_byte = password_bytes[i % password_bytes.len()];
-            result.push(byte ^ key_byte);
+        if crypto::is_envelope(&encrypted_data) {
+            let plaintext = crypto::decrypt_envelope(&encrypted_data, &password)?;
+            let data =
+                String::from_utf8(plaintext).map_err(|_| "解密失败，数据可能已损坏".to_string())?;
+            return Ok(DecryptedConfig {
+                data,
+                legacy_format: false,
+                warning: None,
+            });
         }
 
-        let decrypted =
-            String::from_utf8(result).map_err(|_| "解密失败，数据可能已损坏".to_string())?;
-
-        Ok(decrypted)
+        // 旧版 XOR 没有完整性校验，只能通过能否解析为 JSON 判断密码是否正确
+        let plaintext = crypto::decrypt_legacy_xor(&encrypted_data, &password)?;
+        let data = String::from_utf8(plaintext)
+            .ok()
+            .filter(|data| serde_json::from_str::<Value>(data).is_ok())
+            .ok_or_else(|| "解密失败：密码错误或数据已损坏".to_string())?;
+
+        tracing::warn!(target: "account::import", "导入了旧版 XOR 加密的配置文件");
+        Ok(DecryptedConfig {
+            data,
+            legacy_format: true,
+            warning: Some(
+                "该文件使用旧版弱加密格式，建议导入后重新导出以使用新的加密格式".to_string(),
+            ),
+        })
     })
 }
 
 /// 备份并重启 Antigravity（迁移自 process_commands）
 #[tauri::command]
-pub async fn sign_in_new_antigravity_account() -> Result<String, String> {
+#[specta::specta]
+pub async fn sign_in_new_antigravity_account(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<String, String> {
     println!("🔄 开始执行 sign_in_new_antigravity_account 命令");
 
     // 1. 关闭进程 (如果存在)
@@ -271,16 +574,17 @@ pub async fn sign_in_new_antigravity_account() -> Result<String, String> {
 
     // 2. 备份当前账户信息（直接调用 save_antigravity_current_account）
     println!("💾 步骤2: 调用 save_antigravity_current_account 备份当前账户信息");
-    let backup_info = match crate::commands::save_antigravity_current_account().await {
-        Ok(msg) => {
-            println!("✅ 备份完成: {}", msg);
-            Some(msg)
-        }
-        Err(e) => {
-            println!("⚠️ 备份失败: {}", e);
-            None
-        }
-    };
+    let backup_info =
+        match crate::commands::save_antigravity_current_account(app, state.clone()).await {
+            Ok(msg) => {
+                println!("✅ 备份完成: {}", msg);
+                Some(msg)
+            }
+            Err(e) => {
+                println!("⚠️ 备份失败: {}", e);
+                None
+            }
+        };
 
     // 3. 清除 Antigravity 所有数据 (彻底注销)
     println!("🗑️ 步骤3: 清除所有 Antigravity 数据 (彻底注销)");
@@ -293,6 +597,7 @@ pub async fn sign_in_new_antigravity_account() -> Result<String, String> {
             println!("ℹ️ 清除数据时出现: {}（可能数据库本来就是空的）", e);
         }
     }
+    state.set_current_account(None);
 
     // 等待300ms确保操作完成（缩短等待时间避免前端超时）
     tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
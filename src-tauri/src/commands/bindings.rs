@@ -0,0 +1,142 @@
+//! 前端绑定
+//! 所有命令和推送事件都在这里注册：同一个 Builder 既提供 invoke_handler，也负责导出 TypeScript 绑定，
+//! 前端通过生成的 `src/commands/bindings.ts` 调用命令，参数或返回类型不一致时前端类型检查无法通过；
+//! 提交的绑定文件过期时 `cargo test` 失败
+
+use tauri_specta::{collect_commands, collect_events, Builder, ErrorHandlingMode};
+
+use super::*;
+use crate::antigravity::active::ActiveAccountChanged;
+use crate::antigravity::auto_refresh::AccountBackupRefreshed;
+use crate::antigravity::compat::SessionFormatChanged;
+use crate::antigravity::expiry::AccountExpiring;
+
+/// 注册全部命令和事件
+pub fn builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        // 与直接 invoke 的行为一致：命令返回 Err 时 Promise 被拒绝
+        .error_handling(ErrorHandlingMode::Throw)
+        .commands(collect_commands![
+            collect_account_contents,
+            restore_backup_files,
+            export_accounts_bundle,
+            import_accounts_bundle,
+            export_account_inventory,
+            import_account_from_database,
+            scan_legacy_account_files,
+            merge_legacy_account_files,
+            compact_account_session,
+            delete_backup,
+            clear_all_backups,
+            // 账户基础命令
+            get_antigravity_accounts,
+            get_account_model_catalog,
+            get_current_antigravity_account_info,
+            get_active_antigravity_account,
+            check_session_format,
+            save_antigravity_current_account,
+            restore_antigravity_account,
+            switch_to_antigravity_account,
+            clear_all_antigravity_data,
+            is_antigravity_running,
+            sign_in_new_antigravity_account,
+            // 账户快照命令
+            list_account_snapshots,
+            restore_account_snapshot,
+            prune_account_snapshots,
+            // 损坏备份隔离区命令
+            list_quarantined_backups,
+            repair_quarantined_backup,
+            delete_quarantined_backup,
+            // 账户保险库命令
+            get_vault_status,
+            enable_vault,
+            unlock_vault,
+            lock_vault,
+            disable_vault,
+            // 调试命令
+            inspect_session_protobuf,
+            // 平台支持命令
+            get_platform_info,
+            find_antigravity_installations,
+            get_current_paths,
+            // 数据库路径相关
+            detect_antigravity_installation,
+            // 可执行文件路径相关
+            validate_antigravity_executable,
+            detect_antigravity_executable,
+            save_antigravity_executable,
+            minimize_to_tray,
+            restore_from_tray,
+            update_tray_menu_command,
+            save_system_tray_state,
+            save_silent_start_state,
+            save_private_mode_state,
+            save_debug_mode_state,
+            save_snapshot_depth,
+            save_auto_refresh_backups,
+            save_expiry_warning_settings,
+            get_all_settings,
+            // 数据库监控命令
+            is_database_monitoring_running,
+            start_database_monitoring,
+            stop_database_monitoring,
+            get_backup_audit_log,
+            decrypt_config_data,
+            encrypt_config_data,
+            write_text_file,
+            write_frontend_log,
+            get_log_directory_path,
+            open_log_directory,
+        ])
+        .events(collect_events![
+            AccountExpiring,
+            AccountBackupRefreshed,
+            ActiveAccountChanged,
+            SessionFormatChanged,
+        ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+    use std::path::Path;
+
+    /// 生成的绑定文件
+    const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/commands/bindings.ts");
+
+    /// 把绑定写入指定文件
+    ///
+    /// 命令返回的 u64/usize 都是计数、字节数或秒数，不会超出 JS 的安全整数范围，按 number 导出
+    fn export(builder: &Builder<tauri::Wry>, path: &Path) -> Result<(), String> {
+        builder
+            .export(
+                Typescript::default().bigint(BigIntExportBehavior::Number),
+                path,
+            )
+            .map_err(|e| format!("导出 TypeScript 绑定失败: {}", e))
+    }
+
+    /// 生成的绑定必须与提交的文件一致；设置 UPDATE_BINDINGS=1 运行测试时改为重新生成提交的文件
+    #[test]
+    fn bindings_are_up_to_date() {
+        let committed = Path::new(BINDINGS_PATH);
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            export(&builder(), committed).unwrap();
+            return;
+        }
+
+        let generated =
+            std::env::temp_dir().join(format!("antigravity-bindings-{}.ts", std::process::id()));
+        export(&builder(), &generated).unwrap();
+        let expected = std::fs::read_to_string(&generated).unwrap();
+        let _ = std::fs::remove_file(&generated);
+
+        let actual = std::fs::read_to_string(committed).unwrap_or_default();
+        assert!(
+            actual == expected,
+            "src/commands/bindings.ts 已过期，请运行 `UPDATE_BINDINGS=1 cargo test bindings` 重新生成并提交"
+        );
+    }
+}
@@ -1,12 +1,14 @@
 //! 数据库监控相关命令
 //! 提供数据库监控状态的查询和控制功能
 
+use crate::antigravity::audit::AuditEntry;
 use crate::db_monitor::DatabaseMonitor;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
 /// 获取数据库监控运行状态
 #[tauri::command]
+#[specta::specta]
 pub async fn is_database_monitoring_running(_app: AppHandle) -> Result<bool, String> {
     crate::log_async_command!("is_database_monitoring_running", async {
         // 智能监控现在是默认功能，总是返回 true
@@ -16,6 +18,7 @@ pub async fn is_database_monitoring_running(_app: AppHandle) -> Result<bool, Str
 
 /// 手动启动数据库监控
 #[tauri::command]
+#[specta::specta]
 pub async fn start_database_monitoring(app: AppHandle) -> Result<String, String> {
     crate::log_async_command!("start_database_monitoring", async {
         let monitor = app.state::<Arc<DatabaseMonitor>>();
@@ -29,6 +32,7 @@ pub async fn start_database_monitoring(app: AppHandle) -> Result<String, String>
 
 /// 手动停止数据库监控
 #[tauri::command]
+#[specta::specta]
 pub async fn stop_database_monitoring(app: AppHandle) -> Result<String, String> {
     crate::log_async_command!("stop_database_monitoring", async {
         let monitor = app.state::<Arc<DatabaseMonitor>>();
@@ -36,3 +40,12 @@ pub async fn stop_database_monitoring(app: AppHandle) -> Result<String, String>
         Ok("数据库监控已停止".to_string())
     })
 }
+
+/// 获取最近的备份审计记录（最新的在前），`limit` 缺省为 100
+#[tauri::command]
+#[specta::specta]
+pub async fn get_backup_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    crate::log_async_command!("get_backup_audit_log", async {
+        crate::antigravity::audit::read_recent(limit.unwrap_or(100))
+    })
+}
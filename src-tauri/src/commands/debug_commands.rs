@@ -0,0 +1,31 @@
+//! 调试命令：不依赖 proto 定义查看会话数据的原始字段
+
+use crate::antigravity::account::inspect_jetski_state_wire;
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::backup::load_backup_file;
+use crate::utils::protobuf_wire::WireField;
+
+/// 逐字段解析 agentManagerInitState 的 protobuf 线格式
+///
+/// 数据来源依次为：直接传入的 `agent_state`（Base64）、`account_name` 对应的账户备份、
+/// 当前 Antigravity 数据库中的会话
+#[tauri::command]
+#[specta::specta]
+pub async fn inspect_session_protobuf(
+    account_name: Option<String>,
+    agent_state: Option<String>,
+) -> Result<Vec<WireField>, String> {
+    crate::log_async_command!("inspect_session_protobuf", async {
+        let agent_state = match (agent_state, account_name) {
+            (Some(agent_state), _) => agent_state,
+            (None, Some(account_name)) => {
+                let account = AccountId::parse(&account_name)?;
+                load_backup_file(&account.backup_path()?)?.agent_state
+            }
+            (None, None) => crate::antigravity::active::read_live_agent_state()?
+                .ok_or_else(|| "当前 Antigravity 数据库中没有会话".to_string())?,
+        };
+
+        inspect_jetski_state_wire(&agent_state)
+    })
+}
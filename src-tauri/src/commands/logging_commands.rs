@@ -6,6 +6,7 @@ use std::path::Path;
 /// 写入文本文件
 /// 将文本内容写入指定路径的文件
 #[tauri::command]
+#[specta::specta]
 pub async fn write_text_file(path: String, content: String) -> Result<String, String> {
     crate::log_async_command!("write_text_file", async {
         let file_path = Path::new(&path);
@@ -25,6 +26,7 @@ pub async fn write_text_file(path: String, content: String) -> Result<String, St
 /// 写入前端日志
 /// 将前端日志条目写入到后端日志系统
 #[tauri::command]
+#[specta::specta]
 pub async fn write_frontend_log(log_entry: serde_json::Value) -> Result<(), String> {
     use tracing::{debug, error, info, warn};
 
@@ -113,6 +115,7 @@ pub async fn write_frontend_log(log_entry: serde_json::Value) -> Result<(), Stri
 
 /// 获取日志目录路径
 #[tauri::command]
+#[specta::specta]
 pub async fn get_log_directory_path() -> Result<String, String> {
     crate::log_async_command!("get_log_directory_path", async {
         let log_dir = crate::directories::get_log_directory();
@@ -124,6 +127,7 @@ pub async fn get_log_directory_path() -> Result<String, String> {
 /// 打开日志目录
 /// 在系统文件管理器中打开日志目录（例如 Windows 资源管理器 / macOS Finder / Linux 文件管理器）
 #[tauri::command]
+#[specta::specta]
 pub async fn open_log_directory() -> Result<(), String> {
     crate::log_async_command!("open_log_directory", async {
         let log_dir = crate::directories::get_log_directory();
@@ -24,14 +24,33 @@ pub mod settings_commands;
 
 // 数据库监控命令
 pub mod db_monitor_commands;
+
+// 账户快照命令
+pub mod snapshot_commands;
+
+// 损坏备份隔离区命令
+pub mod quarantine_commands;
+
+// 账户保险库命令
+pub mod vault_commands;
+
+// 调试命令
+pub mod debug_commands;
+
+// 命令注册与 TypeScript 绑定导出
+pub mod bindings;
 // 语言服务器相关命令（在 src/language_server 下）
 
 // 重新导出所有命令，保持与 main.rs 的兼容性
 pub use account_commands::*;
 pub use account_manage_commands::*;
 pub use db_monitor_commands::*;
+pub use debug_commands::*;
 pub use logging_commands::*;
 pub use platform_commands::*;
 pub use process_commands::*;
+pub use quarantine_commands::*;
 pub use settings_commands::*;
+pub use snapshot_commands::*;
 pub use tray_commands::*;
+pub use vault_commands::*;
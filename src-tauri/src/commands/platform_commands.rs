@@ -1,11 +1,59 @@
 //! 平台支持命令
 //! 负责获取平台信息、安装位置验证等跨平台操作
 
-use serde_json::Value;
+use serde::Serialize;
+use specta::Type;
+
+/// 平台信息
+#[derive(Serialize, Debug, Type)]
+pub struct PlatformInfo {
+    /// 操作系统类型 (windows/macos/linux)
+    pub os: String,
+    /// 系统架构 (x86_64/aarch64)
+    pub arch: String,
+    /// 系统家族 (unix/windows)
+    pub family: String,
+    pub antigravity_available: bool,
+    /// Antigravity 可能的数据库路径列表
+    pub antigravity_paths: Vec<String>,
+    pub config_dir: Option<String>,
+    pub data_dir: Option<String>,
+    pub home_dir: Option<String>,
+}
+
+/// Antigravity 安装位置或可执行文件的检测结果
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectionResult {
+    pub found: bool,
+    /// 找到时的路径
+    pub path: Option<String>,
+    /// 是否为用户自定义路径
+    pub is_custom_path: bool,
+}
+
+impl DetectionResult {
+    fn not_found() -> Self {
+        Self {
+            found: false,
+            path: None,
+            is_custom_path: false,
+        }
+    }
+}
+
+/// 当前配置的路径
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PathConfig {
+    /// 用户自定义的可执行文件路径
+    pub executable_path: Option<String>,
+}
 
 /// 获取平台信息
 #[tauri::command]
-pub async fn get_platform_info() -> Result<Value, String> {
+#[specta::specta]
+pub async fn get_platform_info() -> Result<PlatformInfo, String> {
     let os_type = std::env::consts::OS;
     let arch = std::env::consts::ARCH;
     let family = std::env::consts::FAMILY;
@@ -13,20 +61,24 @@ pub async fn get_platform_info() -> Result<Value, String> {
     let antigravity_available = crate::platform::is_antigravity_available();
     let antigravity_paths = crate::platform::get_all_antigravity_db_paths();
 
-    Ok(serde_json::json!({
-        "os": os_type,
-        "arch": arch,
-        "family": family,
-        "antigravity_available": antigravity_available,
-        "antigravity_paths": antigravity_paths.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
-        "config_dir": dirs::config_dir().map(|p| p.to_string_lossy().to_string()),
-        "data_dir": dirs::data_dir().map(|p| p.to_string_lossy().to_string()),
-        "home_dir": dirs::home_dir().map(|p| p.to_string_lossy().to_string())
-    }))
+    Ok(PlatformInfo {
+        os: os_type.to_string(),
+        arch: arch.to_string(),
+        family: family.to_string(),
+        antigravity_available,
+        antigravity_paths: antigravity_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        config_dir: dirs::config_dir().map(|p| p.to_string_lossy().to_string()),
+        data_dir: dirs::data_dir().map(|p| p.to_string_lossy().to_string()),
+        home_dir: dirs::home_dir().map(|p| p.to_string_lossy().to_string()),
+    })
 }
 
 /// 查找 Antigravity 安装位置
 #[tauri::command]
+#[specta::specta]
 pub async fn find_antigravity_installations() -> Result<Vec<String>, String> {
     let paths = crate::platform::find_antigravity_installations();
     Ok(paths
@@ -37,6 +89,7 @@ pub async fn find_antigravity_installations() -> Result<Vec<String>, String> {
 
 /// 验证 Antigravity 可执行文件路径
 #[tauri::command]
+#[specta::specta]
 pub async fn validate_antigravity_executable(path: String) -> Result<bool, String> {
     Ok(crate::antigravity::path_config::validate_executable_path(
         &path,
@@ -45,7 +98,8 @@ pub async fn validate_antigravity_executable(path: String) -> Result<bool, Strin
 
 /// 检测 Antigravity 安装状态（数据库路径）
 #[tauri::command]
-pub async fn detect_antigravity_installation() -> Result<serde_json::Value, String> {
+#[specta::specta]
+pub async fn detect_antigravity_installation() -> Result<DetectionResult, String> {
     // 自动检测 Antigravity 数据库路径
     if let Some(db_path) = crate::platform::get_antigravity_db_path() {
         if db_path.exists() {
@@ -57,37 +111,34 @@ pub async fn detect_antigravity_installation() -> Result<serde_json::Value, Stri
             println!("📁 检测到 Antigravity 数据库: {}", db_path.display());
             println!("📂 Antigravity 数据目录: {}", data_dir);
 
-            return Ok(serde_json::json!({
-                "found": true,
-                "path": data_dir,
-                "isCustomPath": false
-            }));
+            return Ok(DetectionResult {
+                found: true,
+                path: Some(data_dir),
+                is_custom_path: false,
+            });
         }
     }
 
     // 未找到
     println!("⚠️ 未找到 Antigravity 数据库");
-    Ok(serde_json::json!({
-        "found": false,
-        "path": null,
-        "isCustomPath": false
-    }))
+    Ok(DetectionResult::not_found())
 }
 
 /// 检测 Antigravity 可执行文件
 #[tauri::command]
-pub async fn detect_antigravity_executable() -> Result<serde_json::Value, String> {
+#[specta::specta]
+pub async fn detect_antigravity_executable() -> Result<DetectionResult, String> {
     // 1. 尝试从配置读取自定义可执行文件路径
     let custom_exec = crate::antigravity::path_config::get_custom_executable_path().unwrap_or(None);
 
     // 2. 检查自定义可执行文件路径是否有效
     if let Some(ref path) = custom_exec {
         if crate::antigravity::path_config::validate_executable_path(path) {
-            return Ok(serde_json::json!({
-                "found": true,
-                "path": path,
-                "isCustomPath": true
-            }));
+            return Ok(DetectionResult {
+                found: true,
+                path: Some(path.clone()),
+                is_custom_path: true,
+            });
         }
     }
 
@@ -96,24 +147,21 @@ pub async fn detect_antigravity_executable() -> Result<serde_json::Value, String
     if let Some(exec_path) = detected_path {
         println!("✅ 检测到 Antigravity 可执行文件: {}", exec_path.display());
 
-        return Ok(serde_json::json!({
-            "found": true,
-            "path": exec_path.to_string_lossy().to_string(),
-            "isCustomPath": false
-        }));
+        return Ok(DetectionResult {
+            found: true,
+            path: Some(exec_path.to_string_lossy().to_string()),
+            is_custom_path: false,
+        });
     }
 
     // 4. 未找到
     println!("⚠️ 未找到 Antigravity 可执行文件，启动功能可能不可用");
-    Ok(serde_json::json!({
-        "found": false,
-        "path": null,
-        "isCustomPath": false
-    }))
+    Ok(DetectionResult::not_found())
 }
 
 /// 保存用户自定义的 Antigravity 可执行文件路径
 #[tauri::command]
+#[specta::specta]
 pub async fn save_antigravity_executable(path: String) -> Result<String, String> {
     // 1. 验证路径有效性
     if !crate::antigravity::path_config::validate_executable_path(&path) {
@@ -128,10 +176,11 @@ pub async fn save_antigravity_executable(path: String) -> Result<String, String>
 
 /// 获取当前配置的路径
 #[tauri::command]
-pub async fn get_current_paths() -> Result<serde_json::Value, String> {
+#[specta::specta]
+pub async fn get_current_paths() -> Result<PathConfig, String> {
     let exec_path = crate::antigravity::path_config::get_custom_executable_path().unwrap_or(None);
 
-    Ok(serde_json::json!({
-        "executablePath": exec_path
-    }))
+    Ok(PathConfig {
+        executable_path: exec_path,
+    })
 }
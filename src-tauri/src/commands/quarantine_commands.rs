@@ -0,0 +1,41 @@
+//! 隔离区命令：查看、重新导入、删除损坏的备份文件
+
+use crate::antigravity::quarantine::{self, QuarantinedFile};
+use crate::antigravity::snapshot;
+use tauri::{AppHandle, State};
+
+/// 列出隔离区中的备份文件（附带当前仍无法导入的原因）
+#[tauri::command]
+#[specta::specta]
+pub async fn list_quarantined_backups() -> Result<Vec<QuarantinedFile>, String> {
+    crate::log_async_command!("list_quarantined_backups", async {
+        quarantine::list_quarantined()
+    })
+}
+
+/// 重新导入隔离文件（用户修复文件内容后调用），返回恢复的账户标识
+#[tauri::command]
+#[specta::specta]
+pub async fn repair_quarantined_backup(
+    file_name: String,
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+) -> Result<String, String> {
+    crate::log_async_command!("repair_quarantined_backup", async {
+        let account = quarantine::repair_quarantined(
+            &file_name,
+            &state.accounts_dir(),
+            snapshot::configured_depth(&app),
+        )?;
+        Ok(account.to_string())
+    })
+}
+
+/// 删除隔离文件
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_quarantined_backup(file_name: String) -> Result<(), String> {
+    crate::log_async_command!("delete_quarantined_backup", async {
+        quarantine::delete_quarantined(&file_name)
+    })
+}
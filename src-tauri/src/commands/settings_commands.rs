@@ -1,10 +1,47 @@
 //! 应用设置命令
 //! 负责应用程序配置的管理和存储，使用 State 模式
 
+use serde::Serialize;
+use specta::Type;
 use tauri::{AppHandle, Manager};
 
+/// 令牌过期提醒设置
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiryWarningSettings {
+    /// 过期前多少分钟开始提醒
+    pub expiry_warning_minutes: u64,
+    /// 是否发送桌面通知
+    pub expiry_notifications_enabled: bool,
+}
+
+/// 前端读取的全部应用设置（托盘与静默启动沿用旧的下划线字段名）
+#[derive(Serialize, Debug, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AllSettings {
+    /// 系统托盘是否启用
+    #[serde(rename = "system_tray_enabled")]
+    pub system_tray_enabled: bool,
+    /// 静默启动是否启用
+    #[serde(rename = "silent_start_enabled")]
+    pub silent_start_enabled: bool,
+    /// Debug Mode：记录 debug 级别日志
+    pub debug_mode: bool,
+    /// 隐私模式：用户卡片信息打码（邮箱/用户名）
+    pub private_mode: bool,
+    /// 每个账户保留的历史快照数量
+    pub snapshot_depth: usize,
+    /// 令牌轮换后自动刷新对应账户的备份（默认关闭）
+    pub auto_refresh_backups: bool,
+    /// 令牌过期前多少分钟开始提醒
+    pub expiry_warning_minutes: u64,
+    /// 令牌即将过期时发送桌面通知
+    pub expiry_notifications_enabled: bool,
+}
+
 /// 保存系统托盘状态
 #[tauri::command]
+#[specta::specta]
 pub async fn save_system_tray_state(app: AppHandle, enabled: bool) -> Result<bool, String> {
     crate::log_async_command!("save_system_tray_state", async {
         let system_tray = app.state::<crate::system_tray::SystemTrayManager>();
@@ -23,6 +60,7 @@ pub async fn save_system_tray_state(app: AppHandle, enabled: bool) -> Result<boo
 
 /// 保存静默启动状态
 #[tauri::command]
+#[specta::specta]
 pub async fn save_silent_start_state(app: AppHandle, enabled: bool) -> Result<bool, String> {
     crate::log_async_command!("save_silent_start_state", async {
         let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
@@ -38,6 +76,7 @@ pub async fn save_silent_start_state(app: AppHandle, enabled: bool) -> Result<bo
 
 /// 保存隐私模式状态
 #[tauri::command]
+#[specta::specta]
 pub async fn save_private_mode_state(app: AppHandle, enabled: bool) -> Result<bool, String> {
     crate::log_async_command!("save_private_mode_state", async {
         let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
@@ -53,6 +92,7 @@ pub async fn save_private_mode_state(app: AppHandle, enabled: bool) -> Result<bo
 
 /// 保存 Debug Mode 状态
 #[tauri::command]
+#[specta::specta]
 pub async fn save_debug_mode_state(app: AppHandle, enabled: bool) -> Result<bool, String> {
     crate::log_async_command!("save_debug_mode_state", async {
         let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
@@ -66,18 +106,79 @@ pub async fn save_debug_mode_state(app: AppHandle, enabled: bool) -> Result<bool
     })
 }
 
+/// 保存账户快照保留数量
+#[tauri::command]
+#[specta::specta]
+pub async fn save_snapshot_depth(app: AppHandle, depth: usize) -> Result<usize, String> {
+    crate::log_async_command!("save_snapshot_depth", async {
+        let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+
+        settings_manager.update_settings(|settings| {
+            settings.snapshot_depth = depth;
+        })?;
+
+        let settings = settings_manager.get_settings();
+        Ok(settings.snapshot_depth)
+    })
+}
+
+/// 保存自动刷新备份开关
+#[tauri::command]
+#[specta::specta]
+pub async fn save_auto_refresh_backups(app: AppHandle, enabled: bool) -> Result<bool, String> {
+    crate::log_async_command!("save_auto_refresh_backups", async {
+        let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+
+        settings_manager.update_settings(|settings| {
+            settings.auto_refresh_backups = enabled;
+        })?;
+
+        let settings = settings_manager.get_settings();
+        Ok(settings.auto_refresh_backups)
+    })
+}
+
+/// 保存令牌过期提醒设置
+#[tauri::command]
+#[specta::specta]
+pub async fn save_expiry_warning_settings(
+    app: AppHandle,
+    warning_minutes: u64,
+    notifications_enabled: bool,
+) -> Result<ExpiryWarningSettings, String> {
+    crate::log_async_command!("save_expiry_warning_settings", async {
+        let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
+
+        settings_manager.update_settings(|settings| {
+            settings.expiry_warning_minutes = warning_minutes;
+            settings.expiry_notifications_enabled = notifications_enabled;
+        })?;
+
+        let settings = settings_manager.get_settings();
+        Ok(ExpiryWarningSettings {
+            expiry_warning_minutes: settings.expiry_warning_minutes,
+            expiry_notifications_enabled: settings.expiry_notifications_enabled,
+        })
+    })
+}
+
 /// 获取所有应用设置
 #[tauri::command]
-pub async fn get_all_settings(app: AppHandle) -> Result<serde_json::Value, String> {
+#[specta::specta]
+pub async fn get_all_settings(app: AppHandle) -> Result<AllSettings, String> {
     crate::log_async_command!("get_all_settings", async {
         let settings_manager = app.state::<crate::app_settings::AppSettingsManager>();
         let settings = settings_manager.get_settings();
 
-        Ok(serde_json::json!({
-            "system_tray_enabled": settings.system_tray_enabled,
-            "silent_start_enabled": settings.silent_start_enabled,
-            "debugMode": settings.debug_mode,
-            "privateMode": settings.private_mode
-        }))
+        Ok(AllSettings {
+            system_tray_enabled: settings.system_tray_enabled,
+            silent_start_enabled: settings.silent_start_enabled,
+            debug_mode: settings.debug_mode,
+            private_mode: settings.private_mode,
+            snapshot_depth: settings.snapshot_depth,
+            auto_refresh_backups: settings.auto_refresh_backups,
+            expiry_warning_minutes: settings.expiry_warning_minutes,
+            expiry_notifications_enabled: settings.expiry_notifications_enabled,
+        })
     })
 }
@@ -0,0 +1,47 @@
+//! 账户快照命令：列出、回滚、修剪历史备份
+
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::snapshot::{self, SnapshotInfo};
+use tauri::AppHandle;
+
+/// 列出账户的历史快照（最新的在前）
+#[tauri::command]
+#[specta::specta]
+pub async fn list_account_snapshots(account_name: String) -> Result<Vec<SnapshotInfo>, String> {
+    crate::log_async_command!("list_account_snapshots", async {
+        let account = AccountId::parse(&account_name)?;
+        snapshot::list_snapshots(&account)
+    })
+}
+
+/// 回滚到指定快照：先把该快照提升为当前备份，再走统一的恢复流程写入 Antigravity 数据库
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_account_snapshot(
+    account_name: String,
+    snapshot_id: String,
+    app: AppHandle,
+) -> Result<String, String> {
+    crate::log_async_command!("restore_account_snapshot", async {
+        let account = AccountId::parse(&account_name)?;
+        let account_file =
+            snapshot::promote_snapshot(&account, &snapshot_id, snapshot::configured_depth(&app))?;
+
+        crate::antigravity::restore::save_antigravity_account_to_file(account_file).await
+    })
+}
+
+/// 修剪旧快照，`keep` 缺省时使用设置中的保留数量；返回删除数量
+#[tauri::command]
+#[specta::specta]
+pub async fn prune_account_snapshots(
+    account_name: String,
+    keep: Option<usize>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    crate::log_async_command!("prune_account_snapshots", async {
+        let keep = keep.unwrap_or_else(|| snapshot::configured_depth(&app));
+        let account = AccountId::parse(&account_name)?;
+        snapshot::prune_snapshots(&account, keep)
+    })
+}
@@ -3,6 +3,7 @@ use tauri::Manager;
 
 /// 更新托盘菜单（新增命令，供前端调用）
 #[tauri::command]
+#[specta::specta]
 pub async fn update_tray_menu_command(
     app: tauri::AppHandle,
     accounts: Vec<String>,
@@ -13,6 +14,7 @@ pub async fn update_tray_menu_command(
 
 /// 最小化到托盘
 #[tauri::command]
+#[specta::specta]
 pub async fn minimize_to_tray(app: tauri::AppHandle) -> Result<String, String> {
     let system_tray = app.state::<SystemTrayManager>();
     system_tray.minimize_to_tray(&app)?;
@@ -21,6 +23,7 @@ pub async fn minimize_to_tray(app: tauri::AppHandle) -> Result<String, String> {
 
 /// 从托盘恢复
 #[tauri::command]
+#[specta::specta]
 pub async fn restore_from_tray(app: tauri::AppHandle) -> Result<String, String> {
     let system_tray = app.state::<SystemTrayManager>();
     system_tray.restore_from_tray(&app)?;
@@ -0,0 +1,55 @@
+//! 账户保险库命令：开启、解锁、锁定、关闭静态加密
+
+use crate::antigravity::vault::{self, VaultPassReport, VaultStatus};
+use std::path::PathBuf;
+use tauri::State;
+
+/// 获取保险库状态
+#[tauri::command]
+#[specta::specta]
+pub async fn get_vault_status() -> Result<VaultStatus, String> {
+    crate::log_async_command!("get_vault_status", async { vault::status() })
+}
+
+/// 开启保险库并加密现有账户文件，返回加密的文件数和加密失败的文件
+///
+/// 提供 `password` 时使用主密码，否则使用密钥文件（`key_file` 缺省为配置目录下的 vault.key）
+#[tauri::command]
+#[specta::specta]
+pub async fn enable_vault(
+    password: Option<String>,
+    key_file: Option<String>,
+) -> Result<VaultPassReport, String> {
+    crate::log_async_command!("enable_vault", async {
+        vault::enable(password.as_deref(), key_file.map(PathBuf::from))
+    })
+}
+
+/// 解锁保险库，返回顺带加密的文件数和加密失败的文件
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_vault(password: Option<String>) -> Result<VaultPassReport, String> {
+    crate::log_async_command!("unlock_vault", async { vault::unlock(password.as_deref()) })
+}
+
+/// 锁定保险库；同时清空内存中的账户索引（其中含有解密后的令牌）
+#[tauri::command]
+#[specta::specta]
+pub async fn lock_vault(state: State<'_, crate::AppState>) -> Result<(), String> {
+    crate::log_async_command!("lock_vault", async {
+        vault::lock();
+        state.antigravity_accounts.lock().unwrap().clear();
+        Ok(())
+    })
+}
+
+/// 关闭保险库并把所有账户文件解密为明文，返回解密的文件数
+#[tauri::command]
+#[specta::specta]
+pub async fn disable_vault(state: State<'_, crate::AppState>) -> Result<usize, String> {
+    crate::log_async_command!("disable_vault", async {
+        let count = vault::disable()?;
+        state.antigravity_accounts.lock().unwrap().clear();
+        Ok(count)
+    })
+}
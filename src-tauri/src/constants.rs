@@ -5,4 +5,7 @@ pub mod database {
 
     /// Agent 状态同步
     pub const AGENT_STATE: &str = "jetskiStateSync.agentManagerInitState";
+
+    /// 首次启动引导是否已完成
+    pub const ONBOARDING: &str = "antigravityOnboarding";
 }
@@ -1,5 +1,9 @@
 //! 数据库监控模块 - 简化版本：newData, oldData, diff
 
+use crate::antigravity::active::refresh_active_account;
+use crate::antigravity::auto_refresh::maybe_refresh_active_backup;
+use crate::antigravity::state_store::{SqliteStateStore, StateStore};
+use crate::constants::database;
 use serde::Serialize;
 use serde_json::Value;
 use std::sync::Arc;
@@ -84,6 +88,21 @@ impl DatabaseMonitor {
                                 } else {
                                     info!("✅ 数据库变化事件推送成功");
                                 }
+
+                                // 会话变化后重新识别活动账户（变化时推送 active-account-changed）
+                                let session_changed = diff
+                                    .changed_fields
+                                    .iter()
+                                    .any(|field| field.starts_with(database::AGENT_STATE));
+                                if session_changed {
+                                    match refresh_active_account(&app_handle) {
+                                        // 同一账户的令牌被轮换时自动刷新其备份
+                                        Ok(status) => {
+                                            maybe_refresh_active_backup(&app_handle, &status)
+                                        }
+                                        Err(e) => warn!("⚠️ 识别活动账户失败: {}", e),
+                                    }
+                                }
                             }
                         }
 
@@ -129,14 +148,8 @@ impl DatabaseMonitor {
         let mut complete_data = serde_json::Map::new();
 
         if db_path.exists() {
-            let conn = rusqlite::Connection::open(&db_path)?;
-
             // 查询所有数据（完整的ItemTable）
-            let mut stmt = conn.prepare("SELECT key, value FROM ItemTable ORDER BY key")?;
-
-            let rows: Vec<(String, String)> = stmt
-                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
-                .collect::<Result<Vec<_>, _>>()?;
+            let rows = SqliteStateStore::open_read_only(&db_path)?.entries()?;
 
             // 构建完整数据对象
             for (key, value) in rows {
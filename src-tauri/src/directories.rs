@@ -61,6 +61,31 @@ pub fn get_accounts_directory() -> PathBuf {
     accounts_dir
 }
 
+/// 获取账户快照目录（每个账户一个子目录，保存历史备份）
+pub fn get_snapshots_directory() -> PathBuf {
+    get_config_directory().join("antigravity-snapshots")
+}
+
+/// 获取损坏备份的隔离目录
+pub fn get_quarantine_directory() -> PathBuf {
+    get_config_directory().join("antigravity-quarantine")
+}
+
+/// 获取备份审计记录文件路径
+pub fn get_backup_audit_file() -> PathBuf {
+    get_config_directory().join("backup_audit.jsonl")
+}
+
+/// 获取账户保险库配置文件路径
+pub fn get_vault_config_file() -> PathBuf {
+    get_config_directory().join("vault.json")
+}
+
+/// 获取默认的保险库密钥文件路径
+pub fn get_vault_key_file() -> PathBuf {
+    get_config_directory().join("vault.key")
+}
+
 /// 获取应用设置文件路径
 pub fn get_app_settings_file() -> PathBuf {
     get_config_directory().join("app_settings.json")
@@ -76,6 +101,11 @@ pub fn get_antigravity_path_file() -> PathBuf {
     get_config_directory().join("antigravity_path.json")
 }
 
+/// 获取旧版账户目录（系统配置目录下，迁移后只剩 .bak 等遗留文件）
+pub fn get_legacy_accounts_directory() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(".antigravity-agent").join("antigravity-accounts"))
+}
+
 /// 在应用启动时检查并迁移旧账户目录到新路径。
 /// 当前为空实现，后续补充实际迁移逻辑。
 pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
@@ -88,13 +118,10 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
     );
 
     // 旧账户目录（Roaming 配置目录下）
-    let Some(config_dir) = dirs::config_dir() else {
+    let Some(legacy_accounts_dir) = get_legacy_accounts_directory() else {
         info!(target: "app::startup", "未找到系统配置目录 (dirs::config_dir)，跳过旧账户目录检测");
         return Ok(());
     };
-    let legacy_accounts_dir = config_dir
-        .join(".antigravity-agent")
-        .join("antigravity-accounts");
     info!(
         target: "app::startup",
         "检测旧账户目录: {}",
@@ -111,6 +138,7 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
     for entry in read_dir {
         let entry = entry?;
         let path = entry.path();
+        #[allow(clippy::collapsible_if)]
         if path.is_file() {
             if path
                 .extension()
@@ -133,8 +161,11 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
     let mut migrated_count = 0usize;
     let mut skipped_existing = 0usize;
     let mut renamed_count = 0usize;
+    let mut retry_count = 0usize;
     for json_path in legacy_files {
         let file_name = json_path.file_name().map(|f| f.to_owned());
+        // 写入失败的文件保留原名，下次启动再迁移
+        let mut write_failed = false;
 
         if let Some(file_name) = file_name {
             match fs::read_to_string(&json_path) {
@@ -164,13 +195,18 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
                                 } else {
                                     match serde_json::to_string_pretty(&filtered_value) {
                                         Ok(serialized) => {
-                                            if let Err(e) = fs::write(&new_path, serialized) {
+                                            // 迁移早于保险库自动解锁：已解锁时加密写入，否则先写明文，解锁时再加密
+                                            if let Err(e) = crate::antigravity::vault::write_migrated_account_file(
+                                                &new_path,
+                                                &serialized,
+                                            ) {
                                                 warn!(
                                                     target: "app::startup",
                                                     "写入新文件失败: {}，错误: {}",
                                                     new_path.display(),
                                                     e
                                                 );
+                                                write_failed = true;
                                             } else {
                                                 migrated_count += 1;
                                             }
@@ -220,7 +256,12 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
             );
         }
 
-        // 无论写入/解析是否成功，都尝试将旧文件改为 .bak
+        if write_failed {
+            retry_count += 1;
+            continue;
+        }
+
+        // 已迁移、同名跳过或内容无法使用的旧文件改为 .bak，避免每次启动重复处理
         let bak_path = json_path.with_extension("bak");
         match fs::rename(&json_path, &bak_path) {
             Ok(()) => {
@@ -239,11 +280,12 @@ pub fn migrate_legacy_accounts_if_needed() -> io::Result<()> {
     }
     info!(
         target: "app::startup",
-        "提取到旧账户状态数: {}，成功迁移: {}，同名跳过: {}，已重命名为 .bak: {}",
+        "提取到旧账户状态数: {}，成功迁移: {}，同名跳过: {}，已重命名为 .bak: {}，待下次重试: {}",
         legacy_account_states.len(),
         migrated_count,
         skipped_existing,
-        renamed_count
+        renamed_count,
+        retry_count
     );
 
     // TODO: 检测 Roaming 下的旧账户目录并迁移到新目录，处理冲突和错误。
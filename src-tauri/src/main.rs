@@ -27,9 +27,6 @@ mod state;
 // Re-export AppState for compatibility with other modules
 pub use state::{AntigravityAccount, AppState, ProfileInfo};
 
-// Use commands
-use crate::commands::*;
-
 /// 初始化双层日志系统（控制台 + 文件）
 fn init_tracing() -> WorkerGuard {
     let app_settings_path = crate::directories::get_app_settings_file();
@@ -96,6 +93,8 @@ fn main() {
         Err(e) => tracing::error!(target: "app::startup", "⚠️ 账户目录迁移检查失败: {}", e),
     }
 
+    let builder = commands::bindings::builder();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_shell::init())
@@ -104,51 +103,13 @@ fn main() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState::default())
-        .setup(|app| setup::init(app))
-        .invoke_handler(tauri::generate_handler![
-            collect_account_contents,
-            restore_backup_files,
-            delete_backup,
-            clear_all_backups,
-            // 账户基础命令
-            get_antigravity_accounts,
-            get_current_antigravity_account_info,
-            save_antigravity_current_account,
-            restore_antigravity_account,
-            switch_to_antigravity_account,
-            clear_all_antigravity_data,
-            is_antigravity_running,
-            sign_in_new_antigravity_account,
-            // 平台支持命令
-            get_platform_info,
-            find_antigravity_installations,
-            get_current_paths,
-            // 数据库路径相关
-            detect_antigravity_installation,
-            // 可执行文件路径相关
-            validate_antigravity_executable,
-            detect_antigravity_executable,
-            save_antigravity_executable,
-            minimize_to_tray,
-            restore_from_tray,
-            update_tray_menu_command,
-            save_system_tray_state,
-            save_silent_start_state,
-            save_private_mode_state,
-            save_debug_mode_state,
-            get_all_settings,
-            // 数据库监控命令
-            is_database_monitoring_running,
-            start_database_monitoring,
-            stop_database_monitoring,
-            decrypt_config_data,
-            encrypt_config_data,
-            write_text_file,
-            write_frontend_log,
-            get_log_directory_path,
-            open_log_directory,
-        ])
+        .invoke_handler(builder.invoke_handler())
+        .setup(move |app| {
+            builder.mount_events(app);
+            setup::init(app)
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
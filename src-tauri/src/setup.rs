@@ -32,6 +32,22 @@ pub fn init(app: &mut App) -> std::result::Result<(), Box<dyn std::error::Error>
 
     tracing::info!(target: "app::setup::db_monitor", "数据库监控器初始化完成");
 
+    // 账户保险库使用密钥文件时自动解锁
+    crate::antigravity::vault::try_auto_unlock();
+
+    // 后台检查会话格式兼容性，格式变化时打开写入保护
+    let app_handle_for_compat = app.handle().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let accounts_dir = app_handle_for_compat
+            .state::<crate::AppState>()
+            .accounts_dir();
+        crate::antigravity::compat::run_check(&app_handle_for_compat, &accounts_dir);
+    });
+
+    // 启动令牌过期检查
+    crate::antigravity::expiry::start_expiry_scheduler(app.handle().clone());
+    tracing::info!(target: "app::setup::expiry", "令牌过期检查已启动");
+
     // 初始化窗口事件处理器
     if let Err(e) = window::init_window_event_handler(app) {
         tracing::error!(target: "app::setup::window", error = %e, "窗口事件处理器初始化失败");
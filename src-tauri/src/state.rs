@@ -1,7 +1,15 @@
+use crate::antigravity::account_id::AccountId;
+use crate::antigravity::account_index::AccountIndex;
+use crate::antigravity::account_view::AccountProfile;
+use crate::antigravity::backup::BackupMetadata;
 use crate::directories;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProfileInfo {
@@ -12,25 +20,39 @@ pub struct ProfileInfo {
     pub last_updated: String,
 }
 
-// Antigravity 账户信息结构
-#[derive(Debug, Serialize, Deserialize)]
+// Antigravity 账户索引条目（解码结果按文件修改时间与内容哈希缓存）
+#[derive(Clone)]
 pub struct AntigravityAccount {
-    pub id: String,
-    pub name: String,
-    pub email: String,
-    pub api_key: String,
-    pub profile_url: String,   // Base64 编码的头像
-    pub user_settings: String, // 编码后的用户设置
-    pub created_at: String,
-    pub last_switched: String,
+    pub id: AccountId,
+    pub metadata: BackupMetadata,
+    pub saved_at: DateTime<Utc>,
+    pub content_hash: String,
+    /// 解码后的 SessionResponse JSON
+    pub session: Value,
+    /// 视图模型需要的字段
+    pub profile: AccountProfile,
+    pub file_modified: SystemTime,
+    pub file_len: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug)]
 pub struct AppState {
     pub profiles: HashMap<String, ProfileInfo>,
     pub config_dir: PathBuf,
-    pub antigravity_accounts: HashMap<String, AntigravityAccount>,
-    pub current_account_id: Option<String>,
+    pub antigravity_accounts: Mutex<AccountIndex>,
+    pub current_account_id: Mutex<Option<AccountId>>,
+}
+
+impl AppState {
+    /// 账户备份目录
+    pub fn accounts_dir(&self) -> PathBuf {
+        self.config_dir.join("antigravity-accounts")
+    }
+
+    /// 记录当前使用中的账户
+    pub fn set_current_account(&self, account: Option<AccountId>) {
+        *self.current_account_id.lock().unwrap() = account;
+    }
 }
 
 impl Default for AppState {
@@ -41,8 +63,8 @@ impl Default for AppState {
         Self {
             profiles: HashMap::new(),
             config_dir,
-            antigravity_accounts: HashMap::new(),
-            current_account_id: None,
+            antigravity_accounts: Mutex::new(AccountIndex::default()),
+            current_account_id: Mutex::new(None),
         }
     }
 }
@@ -2,6 +2,7 @@
 //!
 //! 使用 Tauri 2.9 内置的 tray API 实现后端控制托盘
 
+use crate::antigravity::account_id::AccountId;
 use crate::app_settings::AppSettingsManager;
 use tauri::menu::{Menu, MenuBuilder, MenuItem};
 use tauri::tray::{TrayIcon, TrayIconBuilder};
@@ -62,12 +63,27 @@ fn handle_tray_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
             app.exit(0);
         }
         // 账户切换事件
-        account_id if account_id.starts_with("account_") => {
-            let account_email = account_id.strip_prefix("account_").unwrap_or("");
-            tracing::info!("请求切换到账户: {account_email}");
+        menu_id if menu_id.starts_with("account_") => {
+            let raw_id = menu_id.strip_prefix("account_").unwrap_or("");
+            let account = match AccountId::parse(raw_id) {
+                Ok(account) => account,
+                Err(e) => {
+                    tracing::warn!("忽略非法的账户菜单项: {e}");
+                    return;
+                }
+            };
+            // 账户索引已加载时，只响应索引中仍存在的账户
+            let state = app.state::<crate::AppState>();
+            let index = state.antigravity_accounts.lock().unwrap();
+            if !index.is_empty() && index.get(&account).is_none() {
+                tracing::warn!("托盘菜单中的账户已不存在: {account}");
+                return;
+            }
+            drop(index);
+            tracing::info!("请求切换到账户: {account}");
 
             // 发射事件到前端
-            if let Err(e) = app.emit("tray-switch-account", account_email) {
+            if let Err(e) = app.emit("tray-switch-account", account.as_str()) {
                 tracing::error!("发射账户切换事件失败: {e}");
             }
         }
@@ -106,7 +122,15 @@ pub fn update_tray_menu(app: &AppHandle, accounts: Vec<String>) -> Result<(), St
         menu_builder = menu_builder.separator();
 
         for account in &accounts {
-            let masked_email = mask_email(account);
+            // 菜单 ID 只使用通过校验的账户标识
+            let account = match AccountId::parse(account) {
+                Ok(account) => account,
+                Err(e) => {
+                    tracing::warn!("跳过非法的账户: {e}");
+                    continue;
+                }
+            };
+            let masked_email = mask_email(account.as_str());
             menu_builder = menu_builder.item(
                 &MenuItem::with_id(
                     app,
@@ -139,7 +163,7 @@ pub fn update_tray_menu(app: &AppHandle, accounts: Vec<String>) -> Result<(), St
 }
 
 /// 邮箱打码函数
-fn mask_email(email: &str) -> String {
+pub(crate) fn mask_email(email: &str) -> String {
     let parts: Vec<&str> = email.split('@').collect();
     if parts.len() != 2 {
         return email.to_string();
@@ -0,0 +1,342 @@
+//! 口令加密
+//! 用 Argon2id 从口令派生密钥（每份数据独立随机盐），再用 XChaCha20-Poly1305 加密，
+//! 结果保存为带版本号的 JSON 信封；认证失败即说明口令错误或数据被篡改
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use zeroize::Zeroizing;
+
+/// 信封格式标识
+const ENVELOPE_FORMAT: &str = "antigravity-agent-encrypted";
+
+/// 当前信封版本
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// 默认 Argon2id 参数（OWASP 推荐的最低配置：19 MiB 内存，2 次迭代）
+const DEFAULT_M_COST_KIB: u32 = 19 * 1024;
+const DEFAULT_T_COST: u32 = 2;
+const DEFAULT_P_COST: u32 = 1;
+
+/// 解密时接受的参数范围：上限防止恶意文件让派生过程耗尽内存或时间，
+/// 下限防止被改成几乎不耗费算力的参数
+const M_COST_RANGE_KIB: RangeInclusive<u32> = 8 * 1024..=1024 * 1024;
+const T_COST_RANGE: RangeInclusive<u32> = 1..=16;
+const P_COST_RANGE: RangeInclusive<u32> = 1..=16;
+
+const SALT_LEN: usize = 16;
+
+/// 解密时接受的盐长度（字节）
+const SALT_LEN_RANGE: RangeInclusive<usize> = SALT_LEN..=64;
+
+/// 口令错误与数据被篡改在 AEAD 下无法区分，统一提示
+pub const WRONG_PASSWORD_OR_TAMPERED: &str = "密码错误或数据已被篡改";
+
+/// 密钥派生参数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KdfParams {
+    /// 固定为 argon2id
+    pub alg: String,
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+    /// Base64 编码的随机盐
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// 生成带新随机盐的默认参数
+    pub fn generate() -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            alg: "argon2id".to_string(),
+            m_cost: DEFAULT_M_COST_KIB,
+            t_cost: DEFAULT_T_COST,
+            p_cost: DEFAULT_P_COST,
+            salt: BASE64.encode(salt),
+        }
+    }
+
+    /// 从口令派生 32 字节密钥
+    pub fn derive_key(&self, password: &str) -> Result<Zeroizing<[u8; 32]>, String> {
+        if self.alg != "argon2id" {
+            return Err(format!("不支持的密钥派生算法: {}", self.alg));
+        }
+        if !M_COST_RANGE_KIB.contains(&self.m_cost)
+            || !T_COST_RANGE.contains(&self.t_cost)
+            || !P_COST_RANGE.contains(&self.p_cost)
+        {
+            return Err("密钥派生参数超出允许范围".to_string());
+        }
+
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|_| "加密信封中的盐无效".to_string())?;
+        if !SALT_LEN_RANGE.contains(&salt.len()) {
+            return Err(format!("加密信封中的盐长度无效（{} 字节）", salt.len()));
+        }
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| format!("密钥派生参数无效: {}", e))?;
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| format!("密钥派生失败: {}", e))?;
+        Ok(key)
+    }
+}
+
+/// 加密信封
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedEnvelope {
+    pub format: String,
+    pub version: u32,
+    pub kdf: KdfParams,
+    /// 固定为 xchacha20poly1305
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+impl EncryptedEnvelope {
+    /// 附加认证数据：格式、版本和 KDF 参数都参与认证，改动任何一项都会解密失败
+    fn associated_data(version: u32, kdf: &KdfParams) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            ENVELOPE_FORMAT, version, kdf.alg, kdf.m_cost, kdf.t_cost, kdf.p_cost, kdf.salt
+        )
+        .into_bytes()
+    }
+}
+
+/// 用已派生的密钥加密（供需要复用密钥的调用方使用）
+pub fn seal(key: &[u8; 32], plaintext: &[u8], aad: &[u8]) -> Result<(String, String), String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| "加密失败".to_string())?;
+    Ok((BASE64.encode(nonce), BASE64.encode(ciphertext)))
+}
+
+/// 用已派生的密钥解密
+pub fn open(key: &[u8; 32], nonce: &str, ciphertext: &str, aad: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = BASE64
+        .decode(nonce)
+        .map_err(|_| "加密信封中的随机数无效".to_string())?;
+    if nonce.len() != 24 {
+        return Err("加密信封中的随机数长度无效".to_string());
+    }
+    let ciphertext = BASE64
+        .decode(ciphertext)
+        .map_err(|_| "加密信封中的密文无效".to_string())?;
+
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| WRONG_PASSWORD_OR_TAMPERED.to_string())
+}
+
+/// 用口令加密，返回 JSON 信封文本
+pub fn encrypt_with_password(plaintext: &[u8], password: &str) -> Result<String, String> {
+    let kdf = KdfParams::generate();
+    let key = kdf.derive_key(password)?;
+    let aad = EncryptedEnvelope::associated_data(ENVELOPE_VERSION, &kdf);
+    let (nonce, ciphertext) = seal(&key, plaintext, &aad)?;
+
+    let envelope = EncryptedEnvelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        version: ENVELOPE_VERSION,
+        kdf,
+        cipher: "xchacha20poly1305".to_string(),
+        nonce,
+        ciphertext,
+    };
+    serde_json::to_string(&envelope).map_err(|e| format!("序列化加密信封失败: {}", e))
+}
+
+/// 判断文本是否为加密信封（而不是旧版 XOR 数据）
+pub fn is_envelope(data: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(data.trim())
+        .ok()
+        .and_then(|v| {
+            v.get("format")
+                .and_then(|f| f.as_str())
+                .map(|f| f == ENVELOPE_FORMAT)
+        })
+        .unwrap_or(false)
+}
+
+/// 解密 JSON 信封
+pub fn decrypt_envelope(data: &str, password: &str) -> Result<Vec<u8>, String> {
+    let envelope: EncryptedEnvelope =
+        serde_json::from_str(data.trim()).map_err(|e| format!("解析加密信封失败: {}", e))?;
+
+    if envelope.format != ENVELOPE_FORMAT {
+        return Err("不是 Antigravity Agent 加密文件".to_string());
+    }
+    if envelope.version > ENVELOPE_VERSION {
+        return Err(format!(
+            "加密格式版本 {} 高于当前支持的版本 {}，请升级 Antigravity Agent",
+            envelope.version, ENVELOPE_VERSION
+        ));
+    }
+    if envelope.cipher != "xchacha20poly1305" {
+        return Err(format!("不支持的加密算法: {}", envelope.cipher));
+    }
+
+    let key = envelope.kdf.derive_key(password)?;
+    let aad = EncryptedEnvelope::associated_data(envelope.version, &envelope.kdf);
+    open(&key, &envelope.nonce, &envelope.ciphertext, &aad)
+}
+
+/// 解密旧版 XOR + Base64 数据（仅用于兼容导入）
+pub fn decrypt_legacy_xor(data: &str, password: &str) -> Result<Vec<u8>, String> {
+    if password.is_empty() {
+        return Err("密码不能为空".to_string());
+    }
+    let decoded = BASE64
+        .decode(data.trim())
+        .map_err(|_| "Base64 解码失败".to_string())?;
+    let password_bytes = password.as_bytes();
+
+    Ok(decoded
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| byte ^ password_bytes[i % password_bytes.len()])
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = br#"{"version":"1.1","backups":[]}"#;
+
+    fn envelope(password: &str) -> EncryptedEnvelope {
+        serde_json::from_str(&encrypt_with_password(PLAINTEXT, password).unwrap()).unwrap()
+    }
+
+    fn decrypt(envelope: &EncryptedEnvelope, password: &str) -> Result<Vec<u8>, String> {
+        decrypt_envelope(&serde_json::to_string(envelope).unwrap(), password)
+    }
+
+    #[test]
+    fn envelope_round_trip() {
+        let data = encrypt_with_password(PLAINTEXT, "correct horse").unwrap();
+        assert!(is_envelope(&data));
+        assert_eq!(decrypt_envelope(&data, "correct horse").unwrap(), PLAINTEXT);
+
+        // 每次加密使用新的盐和随机数
+        assert_ne!(
+            encrypt_with_password(PLAINTEXT, "correct horse").unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn wrong_password_is_rejected() {
+        let envelope = envelope("correct horse");
+        assert_eq!(
+            decrypt(&envelope, "wrong horse").unwrap_err(),
+            WRONG_PASSWORD_OR_TAMPERED
+        );
+    }
+
+    #[test]
+    fn tampered_ciphertext_or_associated_data_is_rejected() {
+        let original = envelope("pw");
+
+        let mut tampered = original.clone();
+        let mut bytes = BASE64.decode(&tampered.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        tampered.ciphertext = BASE64.encode(bytes);
+        assert_eq!(
+            decrypt(&tampered, "pw").unwrap_err(),
+            WRONG_PASSWORD_OR_TAMPERED
+        );
+
+        let mut tampered = original.clone();
+        let mut nonce = BASE64.decode(&tampered.nonce).unwrap();
+        nonce[0] ^= 1;
+        tampered.nonce = BASE64.encode(nonce);
+        assert_eq!(
+            decrypt(&tampered, "pw").unwrap_err(),
+            WRONG_PASSWORD_OR_TAMPERED
+        );
+
+        // 版本号属于附加认证数据：派生出的密钥不变，改动后也无法解密
+        let mut tampered = original.clone();
+        tampered.version = 0;
+        assert_eq!(
+            decrypt(&tampered, "pw").unwrap_err(),
+            WRONG_PASSWORD_OR_TAMPERED
+        );
+
+        let mut tampered = original;
+        tampered.version = ENVELOPE_VERSION + 1;
+        assert!(decrypt(&tampered, "pw").unwrap_err().contains("版本"));
+    }
+
+    #[test]
+    fn kdf_params_outside_bounds_are_rejected() {
+        let valid = KdfParams::generate();
+        assert!(M_COST_RANGE_KIB.contains(&valid.m_cost));
+        assert!(T_COST_RANGE.contains(&valid.t_cost));
+        assert!(P_COST_RANGE.contains(&valid.p_cost));
+
+        let cases: [fn(&mut KdfParams); 8] = [
+            |k| k.m_cost = 0,
+            |k| k.m_cost = u32::MAX,
+            |k| k.t_cost = 0,
+            |k| k.t_cost = 1000,
+            |k| k.p_cost = 0,
+            |k| k.p_cost = 1000,
+            |k| k.salt = BASE64.encode([0u8; 8]),
+            |k| k.salt = BASE64.encode([0u8; 1024]),
+        ];
+        for tweak in cases {
+            let mut kdf = valid.clone();
+            tweak(&mut kdf);
+            assert!(kdf.derive_key("pw").is_err(), "应拒绝 {:?}", kdf);
+        }
+
+        let mut kdf = valid;
+        kdf.alg = "pbkdf2".to_string();
+        assert!(kdf.derive_key("pw").is_err());
+    }
+
+    #[test]
+    fn legacy_xor_data_falls_back_to_xor() {
+        let password = "legacy";
+        let legacy = BASE64.encode(
+            PLAINTEXT
+                .iter()
+                .zip(password.bytes().cycle())
+                .map(|(byte, key)| byte ^ key)
+                .collect::<Vec<u8>>(),
+        );
+
+        assert!(!is_envelope(&legacy));
+        assert!(decrypt_envelope(&legacy, password).is_err());
+        assert_eq!(decrypt_legacy_xor(&legacy, password).unwrap(), PLAINTEXT);
+        assert_ne!(decrypt_legacy_xor(&legacy, "other").unwrap(), PLAINTEXT);
+        assert!(decrypt_legacy_xor(&legacy, "").is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! 工具模块
 
+pub mod crypto;
 pub mod log_decorator;
 pub mod log_sanitizer;
+pub mod protobuf_wire;
 pub mod sanitizing_layer;
 pub mod tracing_config;
@@ -0,0 +1,264 @@
+//! 无 schema 的 protobuf 线格式解析
+//! 不依赖 .proto 定义，逐字段报告标签号、线类型和推测的含义（字符串 / 嵌套消息 / 整数 / 定长数），
+//! 用于跟踪 Antigravity 会话格式的变化而无需重新编译 proto 文件
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::Serialize;
+use specta::Type;
+
+/// 嵌套消息的最大解析深度
+const MAX_DEPTH: usize = 16;
+
+/// 推测的字段含义
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WireValue {
+    /// 变长整数，同时给出 zigzag 解码（sint）的结果
+    #[serde(rename_all = "camelCase")]
+    Varint { value: u64, zigzag: i64 },
+    /// 64 位定长数（fixed64 / double）
+    #[serde(rename_all = "camelCase")]
+    Fixed64 { value: u64, as_double: f64 },
+    /// 32 位定长数（fixed32 / float）
+    #[serde(rename_all = "camelCase")]
+    Fixed32 { value: u32, as_float: f32 },
+    /// 长度前缀字段，按 UTF-8 文本解释
+    String { value: String },
+    /// 长度前缀字段，能完整解析为嵌套消息
+    Message { fields: Vec<WireField> },
+    /// 长度前缀字段，无法识别的原始字节
+    Bytes { base64: String },
+}
+
+/// 单个字段
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WireField {
+    /// 从根消息开始的标签路径，例如 `19.33.1`
+    pub path: String,
+    pub tag: u32,
+    pub wire_type: u8,
+    pub wire_type_name: &'static str,
+    /// 字段在所属消息中的字节偏移
+    pub offset: usize,
+    /// 字段值的字节长度（不含标签）
+    pub length: usize,
+    pub value: WireValue,
+}
+
+fn wire_type_name(wire_type: u8) -> &'static str {
+    match wire_type {
+        0 => "varint",
+        1 => "fixed64",
+        2 => "length-delimited",
+        3 => "start-group",
+        4 => "end-group",
+        5 => "fixed32",
+        _ => "invalid",
+    }
+}
+
+/// 读取变长整数，返回值和占用的字节数
+fn read_varint(bytes: &[u8], offset: usize) -> Result<(u64, usize), String> {
+    let mut value = 0u64;
+    for (i, byte) in bytes[offset..].iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(format!("偏移 {} 处的变长整数无效", offset))
+}
+
+fn take(bytes: &[u8], offset: usize, len: usize) -> Result<&[u8], String> {
+    offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or_else(|| format!("偏移 {} 处的字段长度 {} 超出数据范围", offset, len))
+}
+
+/// 文本中没有控制字符时才直接视为字符串
+fn is_printable(text: &str) -> bool {
+    !text.chars().any(char::is_control)
+}
+
+/// 推测长度前缀字段的含义：可打印文本 > 嵌套消息 > 含换行的文本 > 原始字节
+fn interpret_length_delimited(data: &[u8], path: &str, depth: usize) -> WireValue {
+    let text = std::str::from_utf8(data).ok();
+    if let Some(text) = text.filter(|t| !t.is_empty() && is_printable(t)) {
+        return WireValue::String {
+            value: text.to_string(),
+        };
+    }
+    if !data.is_empty() && depth < MAX_DEPTH {
+        if let Ok(fields) = decode_at(data, path, depth + 1) {
+            return WireValue::Message { fields };
+        }
+    }
+    match text {
+        Some(text) if !text.is_empty() => WireValue::String {
+            value: text.to_string(),
+        },
+        _ => WireValue::Bytes {
+            base64: BASE64.encode(data),
+        },
+    }
+}
+
+fn decode_at(bytes: &[u8], parent: &str, depth: usize) -> Result<Vec<WireField>, String> {
+    let mut fields = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let field_offset = offset;
+        let (key, key_len) = read_varint(bytes, offset)?;
+        offset += key_len;
+
+        let tag =
+            u32::try_from(key >> 3).map_err(|_| format!("偏移 {} 处的标签号过大", field_offset))?;
+        let wire_type = (key & 0x7) as u8;
+        if tag == 0 {
+            return Err(format!("偏移 {} 处的标签号为 0", field_offset));
+        }
+        let path = if parent.is_empty() {
+            tag.to_string()
+        } else {
+            format!("{}.{}", parent, tag)
+        };
+
+        let (length, value) = match wire_type {
+            0 => {
+                let (value, len) = read_varint(bytes, offset)?;
+                let zigzag = ((value >> 1) as i64) ^ -((value & 1) as i64);
+                (len, WireValue::Varint { value, zigzag })
+            }
+            1 => {
+                let data = take(bytes, offset, 8)?;
+                let value = u64::from_le_bytes(data.try_into().unwrap_or_default());
+                let as_double = f64::from_bits(value);
+                (8, WireValue::Fixed64 { value, as_double })
+            }
+            2 => {
+                let (len, len_len) = read_varint(bytes, offset)?;
+                let len = usize::try_from(len)
+                    .map_err(|_| format!("偏移 {} 处的字段长度过大", offset))?;
+                let data = take(bytes, offset + len_len, len)?;
+                (
+                    len_len + len,
+                    interpret_length_delimited(data, &path, depth),
+                )
+            }
+            5 => {
+                let data = take(bytes, offset, 4)?;
+                let value = u32::from_le_bytes(data.try_into().unwrap_or_default());
+                let as_float = f32::from_bits(value);
+                (4, WireValue::Fixed32 { value, as_float })
+            }
+            other => {
+                return Err(format!(
+                    "偏移 {} 处不支持的线类型 {}（{}）",
+                    field_offset,
+                    other,
+                    wire_type_name(other)
+                ))
+            }
+        };
+
+        fields.push(WireField {
+            path,
+            tag,
+            wire_type,
+            wire_type_name: wire_type_name(wire_type),
+            offset: field_offset,
+            length,
+            value,
+        });
+        offset += length;
+    }
+
+    Ok(fields)
+}
+
+/// 解析一段 protobuf 消息字节
+pub fn decode_message(bytes: &[u8]) -> Result<Vec<WireField>, String> {
+    decode_at(bytes, "", 0)
+}
+
+/// 保留全部字段（包括 proto 中未建模的字段）的可编辑消息
+///
+/// 每个字段都保存原始编码，未修改的字段按原样写回，因此解码再编码得到的字节与原始数据完全一致
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RawMessage {
+    fields: Vec<RawField>,
+}
+
+/// 原始字段（标签 + 值的完整编码）
+#[derive(Debug, Clone, PartialEq)]
+struct RawField {
+    tag: u32,
+    raw: Vec<u8>,
+}
+
+impl RawMessage {
+    /// 按字段切分消息，不解释字段内容
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+
+        while offset < bytes.len() {
+            let start = offset;
+            let (key, key_len) = read_varint(bytes, offset)?;
+            offset += key_len;
+
+            let tag =
+                u32::try_from(key >> 3).map_err(|_| format!("偏移 {} 处的标签号过大", start))?;
+            if tag == 0 {
+                return Err(format!("偏移 {} 处的标签号为 0", start));
+            }
+
+            let (value_start, value_len) = match (key & 0x7) as u8 {
+                0 => (offset, read_varint(bytes, offset)?.1),
+                1 => (offset, 8),
+                2 => {
+                    let (len, len_len) = read_varint(bytes, offset)?;
+                    let len = usize::try_from(len)
+                        .map_err(|_| format!("偏移 {} 处的字段长度过大", offset))?;
+                    (offset + len_len, len)
+                }
+                5 => (offset, 4),
+                other => {
+                    return Err(format!(
+                        "偏移 {} 处不支持的线类型 {}（{}）",
+                        start,
+                        other,
+                        wire_type_name(other)
+                    ))
+                }
+            };
+
+            take(bytes, value_start, value_len)?;
+            offset = value_start + value_len;
+            fields.push(RawField {
+                tag,
+                raw: bytes[start..offset].to_vec(),
+            });
+        }
+
+        Ok(Self { fields })
+    }
+
+    /// 编码回字节
+    pub fn encode(&self) -> Vec<u8> {
+        self.fields
+            .iter()
+            .flat_map(|field| field.raw.iter().copied())
+            .collect()
+    }
+
+    /// 删除指定标签的所有字段，返回删除的数量；其余字段保持原有顺序和编码
+    pub fn remove(&mut self, tag: u32) -> usize {
+        let before = self.fields.len();
+        self.fields.retain(|field| field.tag != tag);
+        before - self.fields.len()
+    }
+}
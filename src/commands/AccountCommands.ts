@@ -1,5 +1,5 @@
-import { invoke } from '@tauri-apps/api/core';
-import {AntigravityAccount} from "@/commands/types/account.types.ts";
+import {commands} from '@/commands/bindings.ts';
+import {AccountDetail, AccountListOptions, ActiveAccountStatus, AntigravityAccountList, ModelCatalog, SessionFormatReport, WireField} from "@/commands/types/account.types.ts";
 
 /**
  * Antigravity 账户管理命令
@@ -7,18 +7,43 @@ import {AntigravityAccount} from "@/commands/types/account.types.ts";
 export class AccountCommands {
   /**
    * 获取当前登录的账户信息
-   * @returns 账户认证信息，包含邮箱、数据库路径等
+   * @returns 账户详情，包含邮箱、套餐、可用模型、凭据与过期信息
    */
-  static async getCurrentAntigravityAccount(): Promise<AntigravityAccount> {
-    return invoke('get_current_antigravity_account_info');
+  static async getCurrentAntigravityAccount(): Promise<AccountDetail> {
+    return commands.getCurrentAntigravityAccountInfo();
+  }
+
+  /**
+   * 识别 Antigravity 当前正在使用的已保存账户
+   * @returns 活动账户标识，以及保存的副本是否已过期
+   */
+  static async getActiveAntigravityAccount(): Promise<ActiveAccountStatus> {
+    return commands.getActiveAntigravityAccount();
+  }
+
+  /**
+   * 检查实时会话和全部备份的会话格式；格式变化时后端会暂停所有改写账户备份和 Antigravity 数据库的操作
+   * @returns 检查报告，包含 Antigravity 版本、格式指纹和诊断信息
+   */
+  static async checkSessionFormat(): Promise<SessionFormatReport> {
+    return commands.checkSessionFormat();
   }
 
   /**
    * 获取所有已备份的账户列表
-   * @returns 账户列表
+   * @param options 排序与过期状态筛选（缺省按保存时间排序、不筛选）
+   * @returns 账户列表，以及本次被移入隔离区的问题文件
    */
-  static async getAntigravityAccounts(): Promise<AntigravityAccount[]> {
-    return invoke('get_antigravity_accounts');
+  static async getAntigravityAccounts(options: AccountListOptions = {}): Promise<AntigravityAccountList> {
+    return commands.getAntigravityAccounts(options.sortBy ?? null, options.expiryFilter ?? null);
+  }
+
+  /**
+   * 获取所有已保存账户的模型目录
+   * @returns 每个账户的可用/推荐模型与套餐，以及按模型、按套餐的跨账户对比
+   */
+  static async getAccountModelCatalog(): Promise<ModelCatalog> {
+    return commands.getAccountModelCatalog();
   }
 
   /**
@@ -26,7 +51,7 @@ export class AccountCommands {
    * @returns 备份结果消息
    */
   static async saveAntigravityCurrentAccount(): Promise<string> {
-    return invoke('save_antigravity_current_account');
+    return commands.saveAntigravityCurrentAccount();
   }
 
   /**
@@ -35,7 +60,7 @@ export class AccountCommands {
    * @returns 切换结果消息
    */
   static async switchToAntigravityAccount(accountName: string): Promise<string> {
-    return invoke('switch_to_antigravity_account', { accountName: accountName });
+    return commands.switchToAntigravityAccount(accountName);
   }
 
   /**
@@ -43,6 +68,16 @@ export class AccountCommands {
    * @returns 清除结果消息
    */
   static async clearAllData(): Promise<string> {
-    return invoke('clear_all_antigravity_data');
+    return commands.clearAllAntigravityData();
+  }
+
+  /**
+   * 调试：不依赖 proto 定义逐字段解析会话数据
+   * @param accountName 账户名（邮箱），为空时读取当前 Antigravity 数据库中的会话
+   * @param agentState 直接传入的 agentManagerInitState（Base64），优先于 accountName
+   * @returns 字段树
+   */
+  static async inspectSessionProtobuf(accountName?: string, agentState?: string): Promise<WireField[]> {
+    return commands.inspectSessionProtobuf(accountName ?? null, agentState ?? null);
   }
 }
@@ -1,36 +1,115 @@
-import {invoke} from '@tauri-apps/api/core';
-import type {BackupData, RestoreResult} from './types/account-manage.types.ts';
+import {commands} from './bindings.ts';
+import type {BundleManifest, DecryptedConfig, QuarantinedFile, RestoreResult, SnapshotInfo, VaultPassReport, VaultStatus} from './bindings.ts';
+import type {BackupData, ImportStrategy, InventoryFormat} from './types/account-manage.types.ts';
 
 /**
  * 账户与备份综合命令
  */
 export class AccountManageCommands {
   static collectAccountContents(): Promise<BackupData[]> {
-    return invoke('collect_account_contents');
+    return commands.collectAccountContents();
   }
 
-  static restoreBackupFiles(backups: BackupData[]): Promise<RestoreResult> {
-    return invoke('restore_backup_files', { accountFileData: backups });
+  static restoreBackupFiles(backups: BackupData[], strategy?: ImportStrategy, dryRun?: boolean): Promise<RestoreResult> {
+    return commands.restoreBackupFiles(backups, strategy ?? null, dryRun ?? null);
+  }
+
+  // ==== 导出包 ====
+  static exportAccountsBundle(path: string, accounts?: string[], password?: string): Promise<BundleManifest> {
+    return commands.exportAccountsBundle(path, accounts ?? null, password ?? null);
+  }
+
+  static importAccountsBundle(path: string, password?: string, strategy?: ImportStrategy, dryRun?: boolean): Promise<RestoreResult> {
+    return commands.importAccountsBundle(path, password ?? null, strategy ?? null, dryRun ?? null);
+  }
+
+  static importAccountFromDatabase(dbPath: string, strategy?: ImportStrategy, dryRun?: boolean): Promise<RestoreResult> {
+    return commands.importAccountFromDatabase(dbPath, strategy ?? null, dryRun ?? null);
+  }
+
+  // ==== 旧版目录遗留文件 ====
+  static scanLegacyAccountFiles(strategy?: ImportStrategy): Promise<RestoreResult> {
+    return commands.scanLegacyAccountFiles(strategy ?? null);
+  }
+
+  static mergeLegacyAccountFiles(strategy?: ImportStrategy, cleanup?: boolean): Promise<RestoreResult> {
+    return commands.mergeLegacyAccountFiles(strategy ?? null, cleanup ?? null);
+  }
+
+  static compactAccountSession(accountName: string): Promise<number> {
+    return commands.compactAccountSession(accountName);
+  }
+
+  // ==== 脱敏账户清单 ====
+  static exportAccountInventory(path: string, format?: InventoryFormat, accounts?: string[]): Promise<number> {
+    return commands.exportAccountInventory(path, format ?? null, accounts ?? null);
   }
 
   static deleteBackup(name: string): Promise<string> {
-    return invoke('delete_backup', { name });
+    return commands.deleteBackup(name);
   }
 
   static clearAllBackups(): Promise<string> {
-    return invoke('clear_all_backups');
+    return commands.clearAllBackups();
   }
 
   // ==== 配置加解密 ====
   static encryptConfig(jsonData: string, password: string): Promise<string> {
-    return invoke('encrypt_config_data', { jsonData: jsonData, password });
+    return commands.encryptConfigData(jsonData, password);
+  }
+
+  static decryptConfig(encryptedData: string, password: string): Promise<DecryptedConfig> {
+    return commands.decryptConfigData(encryptedData, password);
+  }
+
+  // ==== 账户快照 ====
+  static listAccountSnapshots(accountName: string): Promise<SnapshotInfo[]> {
+    return commands.listAccountSnapshots(accountName);
+  }
+
+  static restoreAccountSnapshot(accountName: string, snapshotId: string): Promise<string> {
+    return commands.restoreAccountSnapshot(accountName, snapshotId);
+  }
+
+  static pruneAccountSnapshots(accountName: string, keep?: number): Promise<number> {
+    return commands.pruneAccountSnapshots(accountName, keep ?? null);
+  }
+
+  // ==== 损坏备份隔离区 ====
+  static listQuarantinedBackups(): Promise<QuarantinedFile[]> {
+    return commands.listQuarantinedBackups();
+  }
+
+  static repairQuarantinedBackup(fileName: string): Promise<string> {
+    return commands.repairQuarantinedBackup(fileName);
+  }
+
+  static async deleteQuarantinedBackup(fileName: string): Promise<void> {
+    await commands.deleteQuarantinedBackup(fileName);
+  }
+
+  // ==== 账户保险库 ====
+  static getVaultStatus(): Promise<VaultStatus> {
+    return commands.getVaultStatus();
+  }
+
+  static enableVault(password?: string, keyFile?: string): Promise<VaultPassReport> {
+    return commands.enableVault(password ?? null, keyFile ?? null);
+  }
+
+  static unlockVault(password?: string): Promise<VaultPassReport> {
+    return commands.unlockVault(password ?? null);
+  }
+
+  static async lockVault(): Promise<void> {
+    await commands.lockVault();
   }
 
-  static decryptConfig(encryptedData: string, password: string): Promise<string> {
-    return invoke('decrypt_config_data', { encryptedData: encryptedData, password });
+  static disableVault(): Promise<number> {
+    return commands.disableVault();
   }
 
   static signInNewAntigravityAccount(): Promise<string> {
-    return invoke('sign_in_new_antigravity_account');
+    return commands.signInNewAntigravityAccount();
   }
 }
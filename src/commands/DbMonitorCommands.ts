@@ -1,4 +1,5 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from './bindings.ts';
+import type { AuditEntry } from './bindings.ts';
 
 /**
  * 数据库监控命令
@@ -9,7 +10,7 @@ export class DbMonitorCommands {
    * @returns 是否正在运行
    */
   static async isRunning(): Promise<boolean> {
-    return invoke('is_database_monitoring_running');
+    return commands.isDatabaseMonitoringRunning();
   }
 
   /**
@@ -17,7 +18,7 @@ export class DbMonitorCommands {
    * @returns 启动结果消息
    */
   static async start(): Promise<string> {
-    return invoke('start_database_monitoring');
+    return commands.startDatabaseMonitoring();
   }
 
   /**
@@ -25,6 +26,15 @@ export class DbMonitorCommands {
    * @returns 停止结果消息
    */
   static async stop(): Promise<string> {
-    return invoke('stop_database_monitoring');
+    return commands.stopDatabaseMonitoring();
+  }
+
+  /**
+   * 获取最近的备份审计记录（自动刷新等后台操作）
+   * @param limit 最多返回条数，缺省为 100
+   * @returns 审计记录（最新的在前）
+   */
+  static async getBackupAuditLog(limit?: number): Promise<AuditEntry[]> {
+    return commands.getBackupAuditLog(limit ?? null);
   }
 }
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from './bindings.ts';
 import type { FrontendLogEntry } from './types/logging.types';
 
 /**
@@ -10,21 +10,21 @@ export class LoggingCommands {
    * @param logEntry 日志条目
    */
   static async writeFrontendLog(logEntry: FrontendLogEntry): Promise<void> {
-    return invoke('write_frontend_log', { logEntry });
+    await commands.writeFrontendLog(logEntry);
   }
 
   /**
    * 打开日志目录（在系统文件管理器中打开）
    */
   static async openLogDirectory(): Promise<void> {
-    return invoke('open_log_directory');
+    await commands.openLogDirectory();
   }
 
   /**
    * 获取日志目录路径
    */
   static async getLogDirectoryPath(): Promise<string> {
-    return invoke('get_log_directory_path');
+    return commands.getLogDirectoryPath();
   }
 
   /**
@@ -34,6 +34,6 @@ export class LoggingCommands {
    * @returns 写入结果消息
    */
   static async writeTextFile(path: string, content: string): Promise<string> {
-    return invoke('write_text_file', { path, content });
+    return commands.writeTextFile(path, content);
   }
 }
@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from './bindings.ts';
 import type { PlatformInfo, DetectionResult, PathConfig } from './types/platform.types';
 
 /**
@@ -10,7 +10,7 @@ export class PlatformCommands {
    * @returns 平台信息，包含操作系统、架构、路径等
    */
   static async getInfo(): Promise<PlatformInfo> {
-    return invoke('get_platform_info');
+    return commands.getPlatformInfo();
   }
 
   /**
@@ -18,7 +18,7 @@ export class PlatformCommands {
    * @returns 所有可能的安装路径
    */
   static async findInstallations(): Promise<string[]> {
-    return invoke('find_antigravity_installations');
+    return commands.findAntigravityInstallations();
   }
 
   /**
@@ -26,7 +26,7 @@ export class PlatformCommands {
    * @returns 检测结果
    */
   static async detectInstallation(): Promise<DetectionResult> {
-    return invoke('detect_antigravity_installation');
+    return commands.detectAntigravityInstallation();
   }
 
   /**
@@ -34,7 +34,7 @@ export class PlatformCommands {
    * @returns 检测结果
    */
   static async detectExecutable(): Promise<DetectionResult> {
-    return invoke('detect_antigravity_executable');
+    return commands.detectAntigravityExecutable();
   }
 
   /**
@@ -43,7 +43,7 @@ export class PlatformCommands {
    * @returns 是否有效
    */
   static async validateExecutable(path: string): Promise<boolean> {
-    return invoke('validate_antigravity_executable', { path });
+    return commands.validateAntigravityExecutable(path);
   }
 
   /**
@@ -52,7 +52,7 @@ export class PlatformCommands {
    * @returns 保存结果消息
    */
   static async saveAntigravityExecutable(path: string): Promise<string> {
-    return invoke('save_antigravity_executable', { path });
+    return commands.saveAntigravityExecutable(path);
   }
 
   /**
@@ -60,6 +60,6 @@ export class PlatformCommands {
    * @returns 路径配置
    */
   static async getCurrentPaths(): Promise<PathConfig> {
-    return invoke('get_current_paths');
+    return commands.getCurrentPaths();
   }
 }
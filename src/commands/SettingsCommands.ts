@@ -1,5 +1,5 @@
-import { invoke } from '@tauri-apps/api/core';
-import type { AppSettings } from './types/settings.types';
+import { commands } from './bindings.ts';
+import type { AppSettings, ExpiryWarningSettings } from './types/settings.types';
 
 /**
  * 设置管理命令
@@ -12,7 +12,7 @@ export class SettingsCommands {
    * @returns 保存后的状态
    */
   static async saveSystemTrayState(enabled: boolean): Promise<boolean> {
-    return invoke('save_system_tray_state', { enabled });
+    return commands.saveSystemTrayState(enabled);
   }
 
   /**
@@ -21,7 +21,7 @@ export class SettingsCommands {
    * @returns 保存结果消息
    */
   static async saveSilentStartState(enabled: boolean): Promise<boolean> {
-    return invoke('save_silent_start_state', { enabled });
+    return commands.saveSilentStartState(enabled);
   }
 
   /**
@@ -30,7 +30,7 @@ export class SettingsCommands {
    * @returns 保存后的状态
    */
   static async savePrivateModeState(enabled: boolean): Promise<boolean> {
-    return invoke('save_private_mode_state', { enabled });
+    return commands.savePrivateModeState(enabled);
   }
 
   /**
@@ -39,7 +39,38 @@ export class SettingsCommands {
    * @returns 保存后的状态
    */
   static async saveDebugModeState(enabled: boolean): Promise<boolean> {
-    return invoke('save_debug_mode_state', { enabled });
+    return commands.saveDebugModeState(enabled);
+  }
+
+  /**
+   * 保存账户快照保留数量
+   * @param depth 每个账户保留的快照数（0 表示不保留）
+   * @returns 保存后的数量
+   */
+  static async saveSnapshotDepth(depth: number): Promise<number> {
+    return commands.saveSnapshotDepth(depth);
+  }
+
+  /**
+   * 保存自动刷新备份开关（Antigravity 轮换令牌后自动更新对应账户的备份）
+   * @param enabled 是否启用
+   * @returns 保存后的状态
+   */
+  static async saveAutoRefreshBackups(enabled: boolean): Promise<boolean> {
+    return commands.saveAutoRefreshBackups(enabled);
+  }
+
+  /**
+   * 保存令牌过期提醒设置
+   * @param warningMinutes 过期前多少分钟开始提醒
+   * @param notificationsEnabled 是否发送桌面通知
+   * @returns 保存后的设置
+   */
+  static async saveExpiryWarningSettings(
+    warningMinutes: number,
+    notificationsEnabled: boolean
+  ): Promise<ExpiryWarningSettings> {
+    return commands.saveExpiryWarningSettings(warningMinutes, notificationsEnabled);
   }
 
   /**
@@ -47,6 +78,6 @@ export class SettingsCommands {
    * @returns 应用设置对象
    */
   static async getAll(): Promise<AppSettings> {
-    return invoke('get_all_settings');
+    return commands.getAllSettings();
   }
 }
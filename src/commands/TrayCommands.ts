@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { commands } from './bindings.ts';
 
 /**
  * 系统托盘命令
@@ -9,7 +9,7 @@ export class TrayCommands {
    * @returns 最小化结果消息
    */
   static async minimize(): Promise<string> {
-    return invoke('minimize_to_tray');
+    return commands.minimizeToTray();
   }
 
   /**
@@ -17,7 +17,7 @@ export class TrayCommands {
    * @returns 恢复结果消息
    */
   static async restore(): Promise<string> {
-    return invoke('restore_from_tray');
+    return commands.restoreFromTray();
   }
 
   
@@ -27,6 +27,6 @@ export class TrayCommands {
    * @returns 更新结果消息
    */
   static async updateMenu(accounts: string[]): Promise<string> {
-    return invoke('update_tray_menu_command', { accounts });
+    return commands.updateTrayMenuCommand(accounts);
   }
 }
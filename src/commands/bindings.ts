@@ -0,0 +1,1094 @@
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+/**
+ * 收集所有账户文件的完整内容, 用于导出
+ */
+async collectAccountContents() : Promise<AccountExportedData[]> {
+    return await TAURI_INVOKE("collect_account_contents");
+},
+/**
+ * 恢复备份文件到本地
+ * 
+ * `strategy` 缺省为覆盖；`dry_run` 为 true 时只返回每个条目的导入计划
+ */
+async restoreBackupFiles(accountFileData: AccountExportedData[], strategy: ImportStrategy | null, dryRun: boolean | null) : Promise<RestoreResult> {
+    return await TAURI_INVOKE("restore_backup_files", { accountFileData, strategy, dryRun });
+},
+/**
+ * 导出账户为可移植导出包并直接写入磁盘
+ * 
+ * `accounts` 为空时导出全部账户；提供 `password` 时整包加密
+ */
+async exportAccountsBundle(path: string, accounts: string[] | null, password: string | null) : Promise<BundleManifest> {
+    return await TAURI_INVOKE("export_accounts_bundle", { path, accounts, password });
+},
+/**
+ * 从磁盘读取导出包并导入其中校验通过的账户
+ * 
+ * 损坏的条目记录在 `failed` 中，不影响其余条目导入；`strategy`、`dry_run` 同 `restore_backup_files`
+ */
+async importAccountsBundle(path: string, password: string | null, strategy: ImportStrategy | null, dryRun: boolean | null) : Promise<RestoreResult> {
+    return await TAURI_INVOKE("import_accounts_bundle", { path, password, strategy, dryRun });
+},
+/**
+ * 导出脱敏的账户清单（JSON 或 CSV），不含任何令牌，无法作为凭据导入
+ * 
+ * 隐私模式开启时邮箱会被打码；`accounts` 为空时导出全部账户，返回导出的账户数
+ */
+async exportAccountInventory(path: string, format: InventoryFormat | null, accounts: string[] | null) : Promise<number> {
+    return await TAURI_INVOKE("export_account_inventory", { path, format, accounts });
+},
+/**
+ * 从指定的 state.vscdb（或 .vscdb.backup）只读导入账户，按解码出的邮箱加入账户列表
+ * 
+ * `strategy`、`dry_run` 同 `restore_backup_files`
+ */
+async importAccountFromDatabase(dbPath: string, strategy: ImportStrategy | null, dryRun: boolean | null) : Promise<RestoreResult> {
+    return await TAURI_INVOKE("import_account_from_database", { dbPath, strategy, dryRun });
+},
+/**
+ * 扫描旧版账户目录中迁移遗留的文件，返回它们与当前账户的差异（不写入）
+ */
+async scanLegacyAccountFiles(strategy: ImportStrategy | null) : Promise<RestoreResult> {
+    return await TAURI_INVOKE("scan_legacy_account_files", { strategy });
+},
+/**
+ * 按冲突策略合并旧版账户目录中的遗留文件；`cleanup` 为 true 时删除已合并的遗留文件
+ */
+async mergeLegacyAccountFiles(strategy: ImportStrategy | null, cleanup: boolean | null) : Promise<RestoreResult> {
+    return await TAURI_INVOKE("merge_legacy_account_files", { strategy, cleanup });
+},
+/**
+ * 删除已保存账户会话中的历史记录以缩小备份，其余字段（包括 proto 未建模的字段）逐字节保留
+ * 
+ * 返回 agentManagerInitState 减少的字节数（没有历史记录时为 0）
+ */
+async compactAccountSession(accountName: string) : Promise<number> {
+    return await TAURI_INVOKE("compact_account_session", { accountName });
+},
+/**
+ * 删除指定备份
+ */
+async deleteBackup(name: string) : Promise<string> {
+    return await TAURI_INVOKE("delete_backup", { name });
+},
+/**
+ * 清空所有备份
+ */
+async clearAllBackups() : Promise<string> {
+    return await TAURI_INVOKE("clear_all_backups");
+},
+/**
+ * 获取所有 Antigravity 账户（解码 jetskiStateSync.agentManagerInitState，返回账户视图模型）
+ * 
+ * 解码结果缓存在 AppState 的账户索引中，只有新增或变化的备份文件才会重新解码；
+ * 损坏的文件会被移到隔离区并在 issues 中列出，暂时无法读取的文件只跳过，都不影响其余账户；
+ * 可按过期时间排序，并按过期状态筛选
+ */
+async getAntigravityAccounts(sortBy: AccountSortKey | null, expiryFilter: ExpiryStatus[] | null) : Promise<AntigravityAccountList> {
+    return await TAURI_INVOKE("get_antigravity_accounts", { sortBy, expiryFilter });
+},
+/**
+ * 汇总所有已保存账户的可用模型与套餐，并按模型、按套餐做跨账户对比
+ */
+async getAccountModelCatalog() : Promise<ModelCatalog> {
+    return await TAURI_INVOKE("get_account_model_catalog");
+},
+/**
+ * 获取当前 Antigravity 账户信息
+ */
+async getCurrentAntigravityAccountInfo() : Promise<AccountDetail> {
+    return await TAURI_INVOKE("get_current_antigravity_account_info");
+},
+/**
+ * 识别 Antigravity 当前正在使用的已保存账户，并判断保存的副本是否过期
+ */
+async getActiveAntigravityAccount() : Promise<ActiveAccountStatus> {
+    return await TAURI_INVOKE("get_active_antigravity_account");
+},
+/**
+ * 检查实时会话和全部备份是否符合预期的会话格式，并据此更新写入保护
+ */
+async checkSessionFormat() : Promise<SessionFormatReport> {
+    return await TAURI_INVOKE("check_session_format");
+},
+/**
+ * 备份当前 Antigravity 账户
+ */
+async saveAntigravityCurrentAccount() : Promise<string> {
+    return await TAURI_INVOKE("save_antigravity_current_account");
+},
+/**
+ * 恢复 Antigravity 账户
+ */
+async restoreAntigravityAccount(accountName: string) : Promise<string> {
+    return await TAURI_INVOKE("restore_antigravity_account", { accountName });
+},
+/**
+ * 切换到 Antigravity 账户
+ * 
+ * 清除旧登录信息与写入新会话在同一个事务中完成，任何一步失败都不会让用户停在已登出的状态
+ */
+async switchToAntigravityAccount(accountName: string) : Promise<string> {
+    return await TAURI_INVOKE("switch_to_antigravity_account", { accountName });
+},
+/**
+ * 清除所有 Antigravity 数据
+ */
+async clearAllAntigravityData() : Promise<string> {
+    return await TAURI_INVOKE("clear_all_antigravity_data");
+},
+/**
+ * 检查 Antigravity 进程是否正在运行
+ */
+async isAntigravityRunning() : Promise<boolean> {
+    return await TAURI_INVOKE("is_antigravity_running");
+},
+/**
+ * 备份并重启 Antigravity（迁移自 process_commands）
+ */
+async signInNewAntigravityAccount() : Promise<string> {
+    return await TAURI_INVOKE("sign_in_new_antigravity_account");
+},
+/**
+ * 列出账户的历史快照（最新的在前）
+ */
+async listAccountSnapshots(accountName: string) : Promise<SnapshotInfo[]> {
+    return await TAURI_INVOKE("list_account_snapshots", { accountName });
+},
+/**
+ * 回滚到指定快照：先把该快照提升为当前备份，再走统一的恢复流程写入 Antigravity 数据库
+ */
+async restoreAccountSnapshot(accountName: string, snapshotId: string) : Promise<string> {
+    return await TAURI_INVOKE("restore_account_snapshot", { accountName, snapshotId });
+},
+/**
+ * 修剪旧快照，`keep` 缺省时使用设置中的保留数量；返回删除数量
+ */
+async pruneAccountSnapshots(accountName: string, keep: number | null) : Promise<number> {
+    return await TAURI_INVOKE("prune_account_snapshots", { accountName, keep });
+},
+/**
+ * 列出隔离区中的备份文件（附带当前仍无法导入的原因）
+ */
+async listQuarantinedBackups() : Promise<QuarantinedFile[]> {
+    return await TAURI_INVOKE("list_quarantined_backups");
+},
+/**
+ * 重新导入隔离文件（用户修复文件内容后调用），返回恢复的账户标识
+ */
+async repairQuarantinedBackup(fileName: string) : Promise<string> {
+    return await TAURI_INVOKE("repair_quarantined_backup", { fileName });
+},
+/**
+ * 删除隔离文件
+ */
+async deleteQuarantinedBackup(fileName: string) : Promise<null> {
+    return await TAURI_INVOKE("delete_quarantined_backup", { fileName });
+},
+/**
+ * 获取保险库状态
+ */
+async getVaultStatus() : Promise<VaultStatus> {
+    return await TAURI_INVOKE("get_vault_status");
+},
+/**
+ * 开启保险库并加密现有账户文件，返回加密的文件数和加密失败的文件
+ * 
+ * 提供 `password` 时使用主密码，否则使用密钥文件（`key_file` 缺省为配置目录下的 vault.key）
+ */
+async enableVault(password: string | null, keyFile: string | null) : Promise<VaultPassReport> {
+    return await TAURI_INVOKE("enable_vault", { password, keyFile });
+},
+/**
+ * 解锁保险库，返回顺带加密的文件数和加密失败的文件
+ */
+async unlockVault(password: string | null) : Promise<VaultPassReport> {
+    return await TAURI_INVOKE("unlock_vault", { password });
+},
+/**
+ * 锁定保险库；同时清空内存中的账户索引（其中含有解密后的令牌）
+ */
+async lockVault() : Promise<null> {
+    return await TAURI_INVOKE("lock_vault");
+},
+/**
+ * 关闭保险库并把所有账户文件解密为明文，返回解密的文件数
+ */
+async disableVault() : Promise<number> {
+    return await TAURI_INVOKE("disable_vault");
+},
+/**
+ * 逐字段解析 agentManagerInitState 的 protobuf 线格式
+ * 
+ * 数据来源依次为：直接传入的 `agent_state`（Base64）、`account_name` 对应的账户备份、
+ * 当前 Antigravity 数据库中的会话
+ */
+async inspectSessionProtobuf(accountName: string | null, agentState: string | null) : Promise<WireField[]> {
+    return await TAURI_INVOKE("inspect_session_protobuf", { accountName, agentState });
+},
+/**
+ * 获取平台信息
+ */
+async getPlatformInfo() : Promise<PlatformInfo> {
+    return await TAURI_INVOKE("get_platform_info");
+},
+/**
+ * 查找 Antigravity 安装位置
+ */
+async findAntigravityInstallations() : Promise<string[]> {
+    return await TAURI_INVOKE("find_antigravity_installations");
+},
+/**
+ * 获取当前配置的路径
+ */
+async getCurrentPaths() : Promise<PathConfig> {
+    return await TAURI_INVOKE("get_current_paths");
+},
+/**
+ * 检测 Antigravity 安装状态（数据库路径）
+ */
+async detectAntigravityInstallation() : Promise<DetectionResult> {
+    return await TAURI_INVOKE("detect_antigravity_installation");
+},
+/**
+ * 验证 Antigravity 可执行文件路径
+ */
+async validateAntigravityExecutable(path: string) : Promise<boolean> {
+    return await TAURI_INVOKE("validate_antigravity_executable", { path });
+},
+/**
+ * 检测 Antigravity 可执行文件
+ */
+async detectAntigravityExecutable() : Promise<DetectionResult> {
+    return await TAURI_INVOKE("detect_antigravity_executable");
+},
+/**
+ * 保存用户自定义的 Antigravity 可执行文件路径
+ */
+async saveAntigravityExecutable(path: string) : Promise<string> {
+    return await TAURI_INVOKE("save_antigravity_executable", { path });
+},
+/**
+ * 最小化到托盘
+ */
+async minimizeToTray() : Promise<string> {
+    return await TAURI_INVOKE("minimize_to_tray");
+},
+/**
+ * 从托盘恢复
+ */
+async restoreFromTray() : Promise<string> {
+    return await TAURI_INVOKE("restore_from_tray");
+},
+/**
+ * 更新托盘菜单（新增命令，供前端调用）
+ */
+async updateTrayMenuCommand(accounts: string[]) : Promise<string> {
+    return await TAURI_INVOKE("update_tray_menu_command", { accounts });
+},
+/**
+ * 保存系统托盘状态
+ */
+async saveSystemTrayState(enabled: boolean) : Promise<boolean> {
+    return await TAURI_INVOKE("save_system_tray_state", { enabled });
+},
+/**
+ * 保存静默启动状态
+ */
+async saveSilentStartState(enabled: boolean) : Promise<boolean> {
+    return await TAURI_INVOKE("save_silent_start_state", { enabled });
+},
+/**
+ * 保存隐私模式状态
+ */
+async savePrivateModeState(enabled: boolean) : Promise<boolean> {
+    return await TAURI_INVOKE("save_private_mode_state", { enabled });
+},
+/**
+ * 保存 Debug Mode 状态
+ */
+async saveDebugModeState(enabled: boolean) : Promise<boolean> {
+    return await TAURI_INVOKE("save_debug_mode_state", { enabled });
+},
+/**
+ * 保存账户快照保留数量
+ */
+async saveSnapshotDepth(depth: number) : Promise<number> {
+    return await TAURI_INVOKE("save_snapshot_depth", { depth });
+},
+/**
+ * 保存自动刷新备份开关
+ */
+async saveAutoRefreshBackups(enabled: boolean) : Promise<boolean> {
+    return await TAURI_INVOKE("save_auto_refresh_backups", { enabled });
+},
+/**
+ * 保存令牌过期提醒设置
+ */
+async saveExpiryWarningSettings(warningMinutes: number, notificationsEnabled: boolean) : Promise<ExpiryWarningSettings> {
+    return await TAURI_INVOKE("save_expiry_warning_settings", { warningMinutes, notificationsEnabled });
+},
+/**
+ * 获取所有应用设置
+ */
+async getAllSettings() : Promise<AllSettings> {
+    return await TAURI_INVOKE("get_all_settings");
+},
+/**
+ * 获取数据库监控运行状态
+ */
+async isDatabaseMonitoringRunning() : Promise<boolean> {
+    return await TAURI_INVOKE("is_database_monitoring_running");
+},
+/**
+ * 手动启动数据库监控
+ */
+async startDatabaseMonitoring() : Promise<string> {
+    return await TAURI_INVOKE("start_database_monitoring");
+},
+/**
+ * 手动停止数据库监控
+ */
+async stopDatabaseMonitoring() : Promise<string> {
+    return await TAURI_INVOKE("stop_database_monitoring");
+},
+/**
+ * 获取最近的备份审计记录（最新的在前），`limit` 缺省为 100
+ */
+async getBackupAuditLog(limit: number | null) : Promise<AuditEntry[]> {
+    return await TAURI_INVOKE("get_backup_audit_log", { limit });
+},
+/**
+ * 解密配置数据（用于账户导入）
+ * 
+ * 旧版 XOR 加密的文件仍可导入，但会附带警告，建议用户重新导出
+ */
+async decryptConfigData(encryptedData: string, password: string) : Promise<DecryptedConfig> {
+    return await TAURI_INVOKE("decrypt_config_data", { encryptedData, password });
+},
+/**
+ * 加密配置数据（用于账户导出）
+ * 
+ * 使用 Argon2id 派生密钥 + XChaCha20-Poly1305 加密，输出带版本号的 JSON 信封
+ */
+async encryptConfigData(jsonData: string, password: string) : Promise<string> {
+    return await TAURI_INVOKE("encrypt_config_data", { jsonData, password });
+},
+/**
+ * 写入文本文件
+ * 将文本内容写入指定路径的文件
+ */
+async writeTextFile(path: string, content: string) : Promise<string> {
+    return await TAURI_INVOKE("write_text_file", { path, content });
+},
+/**
+ * 写入前端日志
+ * 将前端日志条目写入到后端日志系统
+ */
+async writeFrontendLog(logEntry: JsonValue) : Promise<null> {
+    return await TAURI_INVOKE("write_frontend_log", { logEntry });
+},
+/**
+ * 获取日志目录路径
+ */
+async getLogDirectoryPath() : Promise<string> {
+    return await TAURI_INVOKE("get_log_directory_path");
+},
+/**
+ * 打开日志目录
+ * 在系统文件管理器中打开日志目录（例如 Windows 资源管理器 / macOS Finder / Linux 文件管理器）
+ */
+async openLogDirectory() : Promise<null> {
+    return await TAURI_INVOKE("open_log_directory");
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+accountBackupRefreshed: AccountBackupRefreshed,
+accountExpiring: AccountExpiring,
+activeAccountChanged: ActiveAccountChanged,
+sessionFormatChanged: SessionFormatChanged
+}>({
+accountBackupRefreshed: "account-backup-refreshed",
+accountExpiring: "account-expiring",
+activeAccountChanged: "active-account-changed",
+sessionFormatChanged: "session-format-changed"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * 调用 Cloud Code API 所需的凭据
+ */
+export type AccountAuth = { accessToken: string; idToken: string; tokenType: string; 
+/**
+ * 从 id_token 本地解码出的声明（未校验签名，无法解码时为空）
+ */
+idTokenClaims: IdTokenClaims | null }
+/**
+ * 备份被自动刷新后推送给前端的事件（`account-backup-refreshed`）
+ */
+export type AccountBackupRefreshed = { accountId: AccountId; expiryTimestamp: number | null }
+/**
+ * Antigravity 当前登录账户的详情（来自实时数据库）
+ */
+export type AccountDetail = { 
+/**
+ * 保存时使用的账户标识
+ */
+id: AccountId; email: string; planName: string; plan: AccountPlan | null; models: AccountModels; auth: AccountAuth; expiry: ExpiryInfo; 
+/**
+ * id_token 中的邮箱与会话邮箱不一致
+ */
+tokenEmailMismatch: boolean; 
+/**
+ * 已保存且备份内容与实时会话一致（未保存或备份已过时为 false）
+ */
+active: boolean; 
+/**
+ * 已保存备份的保存时间（尚未保存时为空）
+ */
+savedAt: string | null; 
+/**
+ * UserContext.status
+ */
+userStatus: number; 
+/**
+ * 会话中的历史记录条数
+ */
+historyCount: number }
+/**
+ * 账户令牌即将过期或已过期时推送给前端的事件（`account-expiring`）
+ */
+export type AccountExpiring = ({ status: ExpiryStatus; 
+/**
+ * 令牌过期时间（Unix 秒）
+ */
+expiryTimestamp: number | null; 
+/**
+ * 距离过期的秒数（已过期为负数）
+ */
+secondsRemaining: number | null }) & { accountId: AccountId }
+/**
+ * 备份数据收集结构
+ */
+export type AccountExportedData = { filename: string; content: JsonValue; timestamp: number }
+/**
+ * 读取账户目录时发现的问题文件
+ */
+export type AccountFileIssue = { 
+/**
+ * 原始文件名
+ */
+fileName: string; reason: string; 
+/**
+ * 隔离后的文件名（移动失败时为空，文件仍留在账户目录中）
+ */
+quarantinedAs: string | null }
+/**
+ * 经过校验的账户标识（由邮箱派生，见 [`AccountId::from_email`]）
+ */
+export type AccountId = string
+/**
+ * 单个账户的模型与套餐
+ */
+export type AccountModelEntry = { accountId: AccountId; email: string; 
+/**
+ * 套餐标识（小写），会话中没有套餐配置时为空
+ */
+planSlug: string | null; planName: string | null; 
+/**
+ * 可用模型（去除首尾空白和重复项，保持会话中的顺序）
+ */
+available: string[]; recommended: string[] }
+/**
+ * 可用模型
+ */
+export type AccountModels = { 
+/**
+ * 账户可用的模型名称（保持会话中的顺序）
+ */
+available: string[]; 
+/**
+ * Antigravity 推荐的模型名称
+ */
+recommended: string[] }
+/**
+ * 套餐信息
+ */
+export type AccountPlan = { slug: string; name: string; description: string; upgradeUrl: string; upgradeMsg: string }
+/**
+ * 账户列表排序方式
+ */
+export type AccountSortKey = 
+/**
+ * 按保存时间（最新的在前）
+ */
+"savedAt" | 
+/**
+ * 按过期时间（最先过期的在前，没有过期时间的排最后）
+ */
+"expirySoonest" | 
+/**
+ * 按过期时间（最晚过期的在前，没有过期时间的排最后）
+ */
+"expiryLatest"
+/**
+ * 账户列表中的一项（已保存的账户）
+ */
+export type AccountSummary = { id: AccountId; email: string; planName: string; plan: AccountPlan | null; models: AccountModels; auth: AccountAuth; expiry: ExpiryInfo; 
+/**
+ * id_token 中的邮箱与会话邮箱不一致
+ */
+tokenEmailMismatch: boolean; 
+/**
+ * 是否为 Antigravity 当前正在使用的账户
+ */
+active: boolean; savedAt: string }
+/**
+ * 活动账户变化时推送给前端的事件（`active-account-changed`）
+ */
+export type ActiveAccountChanged = ActiveAccountStatus
+/**
+ * 活动账户识别结果
+ */
+export type ActiveAccountStatus = { 
+/**
+ * 匹配到的已保存账户（未登录或未保存时为空）
+ */
+accountId: AccountId | null; 
+/**
+ * 实时会话中的邮箱（未登录时为空）
+ */
+liveEmail: string | null; matchedBy: MatchKind | null; 
+/**
+ * 保存的副本与实时会话内容不一致
+ */
+stale: boolean; 
+/**
+ * 实时会话的访问令牌与保存的副本不同（令牌已轮换）
+ */
+tokenRotated: boolean }
+/**
+ * 前端读取的全部应用设置（托盘与静默启动沿用旧的下划线字段名）
+ */
+export type AllSettings = { 
+/**
+ * 系统托盘是否启用
+ */
+system_tray_enabled: boolean; 
+/**
+ * 静默启动是否启用
+ */
+silent_start_enabled: boolean; 
+/**
+ * Debug Mode：记录 debug 级别日志
+ */
+debugMode: boolean; 
+/**
+ * 隐私模式：用户卡片信息打码（邮箱/用户名）
+ */
+privateMode: boolean; 
+/**
+ * 每个账户保留的历史快照数量
+ */
+snapshotDepth: number; 
+/**
+ * 令牌轮换后自动刷新对应账户的备份（默认关闭）
+ */
+autoRefreshBackups: boolean; 
+/**
+ * 令牌过期前多少分钟开始提醒
+ */
+expiryWarningMinutes: number; 
+/**
+ * 令牌即将过期时发送桌面通知
+ */
+expiryNotificationsEnabled: boolean }
+/**
+ * 账户列表结果
+ */
+export type AntigravityAccountList = { accounts: AccountSummary[]; 
+/**
+ * 问题文件：隔离区中的全部文件，以及本次读取时暂时无法读取而跳过的文件
+ */
+issues: AccountFileIssue[] }
+/**
+ * 单条审计记录
+ */
+export type AuditEntry = { at: string; 
+/**
+ * 操作类型，例如 `auto_refresh`
+ */
+action: string; account: string; previousHash: string | null; newHash: string; previousExpiry: number | null; newExpiry: number | null }
+/**
+ * 导出包清单
+ */
+export type BundleManifest = { format: string; formatVersion: number; 
+/**
+ * 生成该导出包的 Antigravity Agent 版本
+ */
+agentVersion: string; createdAt: string; accounts: BundleManifestEntry[] }
+/**
+ * 清单中的单个账户
+ */
+export type BundleManifestEntry = { fileName: string; email: string; planName: string | null; expiryTimestamp: number | null; 
+/**
+ * 条目内容（紧凑 JSON）的 SHA-256
+ */
+sha256: string }
+/**
+ * 解密结果
+ */
+export type DecryptedConfig = { data: string; 
+/**
+ * 是否为旧版 XOR 加密的文件
+ */
+legacyFormat: boolean; 
+/**
+ * 需要提示用户的警告
+ */
+warning: string | null }
+/**
+ * Antigravity 安装位置或可执行文件的检测结果
+ */
+export type DetectionResult = { found: boolean; 
+/**
+ * 找到时的路径
+ */
+path: string | null; 
+/**
+ * 是否为用户自定义路径
+ */
+isCustomPath: boolean }
+/**
+ * 账户的过期信息
+ */
+export type ExpiryInfo = { status: ExpiryStatus; 
+/**
+ * 令牌过期时间（Unix 秒）
+ */
+expiryTimestamp: number | null; 
+/**
+ * 距离过期的秒数（已过期为负数）
+ */
+secondsRemaining: number | null }
+/**
+ * 过期状态
+ */
+export type ExpiryStatus = "valid" | "expiringSoon" | "expired" | 
+/**
+ * 备份中没有过期时间
+ */
+"unknown"
+/**
+ * 令牌过期提醒设置
+ */
+export type ExpiryWarningSettings = { 
+/**
+ * 过期前多少分钟开始提醒
+ */
+expiryWarningMinutes: number; 
+/**
+ * 是否发送桌面通知
+ */
+expiryNotificationsEnabled: boolean }
+export type FailedAccountExportedData = { filename: string; error: string }
+/**
+ * id_token 载荷中的常用声明
+ */
+export type IdTokenClaims = { 
+/**
+ * 账户的稳定标识
+ */
+sub: string | null; email: string | null; emailVerified: boolean | null; 
+/**
+ * 签发方
+ */
+iss: string | null; 
+/**
+ * 过期时间（Unix 秒）
+ */
+exp: number | null; 
+/**
+ * 签发时间（Unix 秒）
+ */
+iat: number | null }
+/**
+ * 对条目实际执行（dry-run 时为将要执行）的操作
+ */
+export type ImportAction = "created" | "overwritten" | "savedAsSnapshot" | "skipped" | "unchanged" | "failed"
+/**
+ * 单个条目的导入计划/结果
+ */
+export type ImportEntry = { fileName: string; email: string | null; status: ImportStatus; action: ImportAction; 
+/**
+ * 导入内容的令牌过期时间（Unix 秒）
+ */
+incomingExpiry: number | null; 
+/**
+ * 现有备份的令牌过期时间（Unix 秒）
+ */
+existingExpiry: number | null; 
+/**
+ * 无效、失败或未覆盖的原因
+ */
+reason: string | null }
+/**
+ * 导入条目与现有备份的比较结果
+ */
+export type ImportStatus = "new" | "identical" | "newer" | "older" | "invalid" | 
+/**
+ * 现有备份无法读取，没有可比较或归档的内容，为避免丢失数据不覆盖
+ */
+"existingUnreadable"
+/**
+ * 与现有备份冲突时的处理策略
+ */
+export type ImportStrategy = 
+/**
+ * 保留现有备份
+ */
+"skip" | 
+/**
+ * 用导入内容覆盖（旧内容先归档为快照）
+ */
+"overwrite" | 
+/**
+ * 按令牌过期时间保留较新的一份
+ */
+"keepNewer" | 
+/**
+ * 保留现有备份，导入内容另存为该账户的快照
+ */
+"keepBoth"
+/**
+ * 清单输出格式
+ */
+export type InventoryFormat = "json" | "csv"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * 主密钥来源
+ */
+export type KeyProvider = 
+/**
+ * 由主密码经 Argon2id 派生
+ */
+"password" | 
+/**
+ * 从密钥文件读取（32 字节随机数，Base64）
+ */
+"keyFile"
+/**
+ * 匹配依据（按可信度从高到低，声明顺序即优先级）
+ */
+export type MatchKind = 
+/**
+ * id_token 的 `sub` 一致（邮箱变更后仍能匹配）
+ */
+"subject" | 
+/**
+ * 邮箱与 user_id_raw 均一致
+ */
+"userId" | 
+/**
+ * 邮箱一致且访问令牌一致
+ */
+"accessToken" | 
+/**
+ * 仅邮箱一致
+ */
+"email"
+/**
+ * 某个模型在各账户中的可用情况
+ */
+export type ModelAvailability = { model: string; 
+/**
+ * 可以使用该模型的账户
+ */
+accounts: AccountId[]; 
+/**
+ * 把该模型列为推荐模型的账户
+ */
+recommendedBy: AccountId[] }
+/**
+ * 模型目录与跨账户对比
+ */
+export type ModelCatalog = { accounts: AccountModelEntry[]; 
+/**
+ * 按可用账户数从多到少排列，数量相同时按模型名称排列
+ */
+models: ModelAvailability[]; 
+/**
+ * 按套餐标识排列，没有套餐信息的账户排在最后
+ */
+plans: PlanTier[] }
+/**
+ * 当前配置的路径
+ */
+export type PathConfig = { 
+/**
+ * 用户自定义的可执行文件路径
+ */
+executablePath: string | null }
+/**
+ * 同一套餐下的账户
+ */
+export type PlanTier = { 
+/**
+ * 为空表示会话中没有套餐信息
+ */
+planSlug: string | null; planName: string | null; accounts: AccountId[] }
+/**
+ * 平台信息
+ */
+export type PlatformInfo = { 
+/**
+ * 操作系统类型 (windows/macos/linux)
+ */
+os: string; 
+/**
+ * 系统架构 (x86_64/aarch64)
+ */
+arch: string; 
+/**
+ * 系统家族 (unix/windows)
+ */
+family: string; antigravity_available: boolean; 
+/**
+ * Antigravity 可能的数据库路径列表
+ */
+antigravity_paths: string[]; config_dir: string | null; data_dir: string | null; home_dir: string | null }
+/**
+ * 隔离区中的文件
+ */
+export type QuarantinedFile = { fileName: string; quarantinedAt: string; size: number; 
+/**
+ * 当前仍无法导入的原因；为空表示文件已修复，可以重新导入
+ */
+reason: string | null }
+/**
+ * 恢复结果
+ */
+export type RestoreResult = { restoredCount: number; failed: FailedAccountExportedData[]; 
+/**
+ * 本次使用的冲突处理策略
+ */
+strategy: ImportStrategy; 
+/**
+ * 为 true 时只生成计划，未写入任何文件
+ */
+dryRun: boolean; 
+/**
+ * 每个条目的比较结果与执行的操作
+ */
+entries: ImportEntry[] }
+/**
+ * 写入保护状态变化或仍处于打开状态时推送给前端的事件（`session-format-changed`）
+ */
+export type SessionFormatChanged = SessionFormatReport
+/**
+ * 单个会话的检查结果
+ */
+export type SessionFormatCheck = { 
+/**
+ * 实时数据库或备份文件名
+ */
+source: string; compatible: boolean; 
+/**
+ * 会话中包含 auth 或 context（未登录的实时会话不参与兼容性判断）
+ */
+signedIn: boolean; 
+/**
+ * 关键字段出现情况的指纹（8 位十六进制），无法解码时为空
+ */
+fingerprint: string | null; 
+/**
+ * 缺失或线类型不符的必需字段，例如 `context.email (19.7)`
+ */
+missing: string[]; 
+/**
+ * proto 中未建模的顶层标签（仅供参考，不影响兼容性）
+ */
+unknownTags: number[]; 
+/**
+ * 无法解码的原因
+ */
+error: string | null }
+/**
+ * 一次完整的兼容性检查
+ */
+export type SessionFormatReport = { 
+/**
+ * 实时会话兼容（未登录时视为兼容）
+ */
+compatible: boolean; 
+/**
+ * 检测到的 Antigravity 版本
+ */
+antigravityVersion: string | null; 
+/**
+ * 实时会话的检查结果（未登录或数据库不存在时为空）
+ */
+live: SessionFormatCheck | null; backups: SessionFormatCheck[]; 
+/**
+ * 保险库锁定，未检查备份
+ */
+backupsSkipped: boolean; 
+/**
+ * 写入保护已打开
+ */
+writesBlocked: boolean; 
+/**
+ * 格式变化的诊断信息
+ */
+diagnostic: string | null; checkedAt: string }
+/**
+ * 快照概要信息
+ */
+export type SnapshotInfo = { 
+/**
+ * 快照 ID（即快照文件名去掉 .json）
+ */
+id: string; savedAt: string; contentHash: string; planName: string | null; expiryTimestamp: number | null }
+/**
+ * 批量处理中失败的文件
+ */
+export type VaultFileFailure = { 
+/**
+ * 文件（或无法读取的目录）路径
+ */
+path: string; reason: string }
+/**
+ * 加密/解密一次批量处理的结果；单个文件失败不会中断其余文件
+ */
+export type VaultPassReport = { 
+/**
+ * 成功处理的文件数
+ */
+processed: number; 
+/**
+ * 处理失败的文件，文件保持原样，下次解锁时重试
+ */
+failures: VaultFileFailure[] }
+/**
+ * 保险库状态
+ */
+export type VaultStatus = { enabled: boolean; unlocked: boolean; provider: KeyProvider | null; keyFile: string | null }
+/**
+ * 单个字段
+ */
+export type WireField = { 
+/**
+ * 从根消息开始的标签路径，例如 `19.33.1`
+ */
+path: string; tag: number; wireType: number; wireTypeName: string; 
+/**
+ * 字段在所属消息中的字节偏移
+ */
+offset: number; 
+/**
+ * 字段值的字节长度（不含标签）
+ */
+length: number; value: WireValue }
+/**
+ * 推测的字段含义
+ */
+export type WireValue = 
+/**
+ * 变长整数，同时给出 zigzag 解码（sint）的结果
+ */
+{ kind: "varint"; value: number; zigzag: number } | 
+/**
+ * 64 位定长数（fixed64 / double）
+ */
+{ kind: "fixed64"; value: number; asDouble: number } | 
+/**
+ * 32 位定长数（fixed32 / float）
+ */
+{ kind: "fixed32"; value: number; asFloat: number } | 
+/**
+ * 长度前缀字段，按 UTF-8 文本解释
+ */
+{ kind: "string"; value: string } | 
+/**
+ * 长度前缀字段，能完整解析为嵌套消息
+ */
+{ kind: "message"; fields: WireField[] } | 
+/**
+ * 长度前缀字段，无法识别的原始字节
+ */
+{ kind: "bytes"; base64: string }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
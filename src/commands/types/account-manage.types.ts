@@ -1,50 +1,14 @@
-/**
- * 备份相关类型定义
- */
-
-/**
- * JSON 值类型（匹配 Rust 的 serde_json::Value）
- */
-export type JsonValue =
-  | string
-  | number
-  | boolean
-  | null
-  | JsonValue[]
-  | { [key: string]: JsonValue };
+// 备份相关类型由后端生成（src/commands/bindings.ts），这里统一导出
+export type {
+  ImportAction,
+  ImportEntry,
+  ImportStatus,
+  ImportStrategy,
+  InventoryFormat,
+  JsonValue,
+} from '@/commands/bindings.ts';
 
 /**
- * 备份数据结构
+ * 备份数据结构（备份文件名、内容与时间戳）
  */
-export interface BackupData {
-  /** 备份文件名 */
-  filename: string;
-
-  /** 备份内容（JSON 格式，可以是任何有效的 JSON 值） */
-  content: JsonValue;
-
-  /** 备份时间戳（Unix 时间戳，秒） */
-  timestamp: number;
-}
-
-/**
- * 恢复操作失败的备份信息
- */
-export interface FailedBackup {
-  /** 文件名 */
-  filename: string;
-
-  /** 错误信息 */
-  error: string;
-}
-
-/**
- * 恢复操作结果
- */
-export interface RestoreResult {
-  /** 成功恢复的数量 */
-  restoredCount: number;
-
-  /** 失败的备份列表 */
-  failed: FailedBackup[];
-}
+export type {AccountExportedData as BackupData} from '@/commands/bindings.ts';
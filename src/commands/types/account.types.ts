@@ -1,59 +1,36 @@
-
-// Antigravity 当前用户信息类型
-export interface AntigravityAccount {
-  auth: Auth
-  context: Context
-  f11_base64: string
-  f18_base64: string
-  f7_base64: any
-  f9_base64: string
-  flags_f5_base64: string
-  history: any[]
-  user_id_raw_base64: string
-}
-
-interface Auth {
-  access_token: string
-  id_token: string
-  meta: Meta
-  type: string
-}
-
-interface Meta {
-  expiry_timestamp: number
-}
-
-interface Context {
-  email: string
-  models: Models
-  plan: Plan
-  plan_name: string
-  status: number
-}
-
-interface Models {
-  items: Item[]
-  recommended: Recommended
-  unknown_f3_base64: string
-}
-
-interface Item {
-  name: string
-  unknown_f11: number
-  unknown_f15_base64: string
-  unknown_f2_base64: string
-  unknown_f5: number
-}
-
-interface Recommended {
-  names: string[]
-  unknown_f2_base64: string
-}
-
-interface Plan {
-  description: string
-  name: string
-  slug: string
-  upgrade_msg: string
-  upgrade_url: string
+// 账户相关类型由后端生成（src/commands/bindings.ts），这里统一导出
+export type {
+  AccountAuth,
+  AccountDetail,
+  AccountFileIssue,
+  AccountModelEntry,
+  AccountModels,
+  AccountPlan,
+  AccountSortKey,
+  AccountSummary,
+  ActiveAccountStatus,
+  AntigravityAccountList,
+  ExpiryInfo,
+  ExpiryStatus,
+  IdTokenClaims,
+  MatchKind,
+  ModelAvailability,
+  ModelCatalog,
+  PlanTier,
+  SessionFormatCheck,
+  SessionFormatReport,
+  WireField,
+  WireValue,
+} from '@/commands/bindings.ts';
+
+import type {AccountSortKey, ExpiryStatus} from '@/commands/bindings.ts';
+
+/**
+ * 账户列表查询选项
+ */
+export interface AccountListOptions {
+  sortBy?: AccountSortKey;
+
+  /** 只返回这些过期状态的账户 */
+  expiryFilter?: ExpiryStatus[];
 }
@@ -23,9 +23,9 @@ export interface LogInfo {
 }
 
 /**
- * 前端日志条目
+ * 前端日志条目（类型别名而非接口，才能作为 JSON 值传给后端）
  */
-export interface FrontendLogEntry {
+export type FrontendLogEntry = {
   /** 日志级别 */
   level: 'info' | 'warn' | 'error' | 'debug';
 
@@ -37,4 +37,4 @@ export interface FrontendLogEntry {
 
   /** 会话 ID */
   sessionId?: string;
-}
+};
@@ -62,7 +62,7 @@ export function AppContent() {
         } catch (e) {
           logger.error('获取用户额外数据失败', {
             module: 'AppContent',
-            email: user.context.email,
+            email: user.email,
             error: e instanceof Error ? e.message : String(e)
           })
         }
@@ -99,22 +99,22 @@ export function AppContent() {
         {`确定要删除账户 "${user.email}" 吗？此操作无法撤销。`}
       </p>,
       onOk() {
-        return confirmDeleteAccount(user.email);
+        return confirmDeleteAccount(user);
       },
       onCancel() {
       },
     });
   };
 
-  const confirmDeleteAccount = async (email: string) => {
-    await antigravityAccount.delete(email);
-    toast.success(`账户 "${email}" 删除成功`);
+  const confirmDeleteAccount = async (user: AccountSessionListAccountItem) => {
+    await antigravityAccount.delete(user.id);
+    toast.success(`账户 "${user.email}" 删除成功`);
   };
 
   const handleSwitchAccount = async (user: AccountSessionListAccountItem) => {
     try {
       appGlobalLoader.open({label: `正在切换到用户: ${maskEmail(user.email)}...`});
-      await antigravityAccount.switchToAccount(user.email);
+      await antigravityAccount.switchToAccount(user.id);
     } finally {
       appGlobalLoader.close();
     }
@@ -151,7 +151,7 @@ export function AppContent() {
   };
 
   const accounts: AccountSessionListAccountItem[] = antigravityAccount.accounts.map((account) => {
-    const accountAdditionDatum = accountAdditionData.data[account.context.email]
+    const accountAdditionDatum = accountAdditionData.data[account.email]
 
     return {
       geminiProQuote: accountAdditionDatum?.geminiProQuote ?? -1,
@@ -162,12 +162,13 @@ export function AppContent() {
       geminiImageQuoteRestIn: accountAdditionDatum?.geminiImageQuoteRestIn,
       claudeQuote: accountAdditionDatum?.claudeQuote ?? -1,
       claudeQuoteRestIn: accountAdditionDatum?.claudeQuoteRestIn,
-      email: account.context.email,
-      nickName: account.context.plan_name,
+      id: account.id,
+      email: account.email,
+      nickName: account.planName,
       userAvatar: accountAdditionDatum?.userAvatar ?? "",
-      apiKey: account.auth.access_token,
+      apiKey: account.auth.accessToken,
       // 似乎在某些情况下 plan 可能为 null，这里添加 null 检查
-      tier: (account.context.plan?.slug ?? '') as UserTier,
+      tier: (account.plan?.slug ?? '') as UserTier,
     }
   })
 
@@ -234,7 +235,7 @@ export function AppContent() {
           onSwitch={handleSwitchAccount}
           onDelete={handleDeleteBackup}
           onSelect={handleUserClick}
-          currentUserEmail={currentAntigravityAccount?.context.email}
+          currentUserEmail={currentAntigravityAccount?.email}
         />
       </section>
 
@@ -1,6 +1,5 @@
 import React, {useState} from 'react';
 import {Check, Copy, Key, User} from 'lucide-react';
-import type {AntigravityAccount} from '@/commands/types/account.types';
 import {BaseButton} from '@/components/base-ui/BaseButton';
 import {cn} from '@/lib/utils.ts';
 import {logger} from '@/lib/logger.ts';
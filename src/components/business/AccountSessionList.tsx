@@ -15,6 +15,8 @@ export interface AccountSessionListAccountItem {
   geminiImageQuoteRestIn: string
   claudeQuote: number | -1
   claudeQuoteRestIn: string
+  // 账户标识（备份文件名），删除、切换等命令都以它为参数
+  id: string;
   email: string;
   nickName: string;
   userAvatar: string;
@@ -89,7 +91,7 @@ export function AccountSessionList({
       <AnimatePresence mode="popLayout">
         {accounts.map((account) => (
           <motion.div
-            key={account.email}
+            key={account.id}
             layout // 关键：当有元素被删除时，其他元素会自动平滑移动填补空缺
             variants={itemVariants} // 继承父级的 hidden/show 状态
             className="z-10" // 确保在背景之上
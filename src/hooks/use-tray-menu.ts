@@ -29,12 +29,12 @@ export function useTrayMenu() {
   // 监听来自后端的账户切换请求
   useEffect(() => {
     const unlisten = listen("tray-switch-account", async (event) => {
-      const email = event.payload as string;
-      logger.info("收到托盘账户切换请求", { email });
+      const accountId = event.payload as string;
+      logger.info("收到托盘账户切换请求", { accountId });
 
       try {
-        await switchToAccount(email);
-        toast.success(`已切换到账户: ${email}`);
+        await switchToAccount(accountId);
+        toast.success(`已切换到账户: ${accountId}`);
       } catch (error) {
         logger.error("托盘账户切换失败", error);
         toast.error(`切换账户失败: ${error}`);
@@ -49,9 +49,9 @@ export function useTrayMenu() {
   // 当账户列表变化时更新托盘菜单
   useEffect(() => {
     if (accounts.length > 0) {
-      // 提取邮箱列表并更新托盘菜单
-      const emails = accounts.map((user) => user.context.email);
-      updateTrayMenu(emails);
+      // 提取账户标识列表并更新托盘菜单
+      const accountIds = accounts.map((user) => user.id);
+      updateTrayMenu(accountIds);
     } else {
       // 没有账户时清空托盘菜单
       updateTrayMenu([]);
@@ -1,5 +1,5 @@
 ﻿import { create } from "zustand";
-import { AntigravityAccount } from "@/commands/types/account.types.ts";
+import { AccountSummary } from "@/commands/types/account.types.ts";
 import { CloudCodeAPI } from "@/services/cloudcode-api.ts";
 import { CloudCodeAPITypes } from "@/services/cloudcode-api.types.ts";
 import { AccountCommands } from "@/commands/AccountCommands.ts";
@@ -11,7 +11,7 @@ type State = {
 }
 
 type Actions = {
-  update: (antigravityAccount: AntigravityAccount) => Promise<void>
+  update: (antigravityAccount: AccountSummary) => Promise<void>
 }
 
 // 暂时不知道 ultra 定义, 先模糊匹配,
@@ -32,11 +32,11 @@ export interface AccountAdditionData {
 
 export const useAccountAdditionData = create<State & Actions>((setState, getState) => ({
   data: {},
-  update: async (antigravityAccount: AntigravityAccount) => {
+  update: async (antigravityAccount: AccountSummary) => {
     let codeAssistResponse: CloudCodeAPITypes.LoadCodeAssistResponse | CloudCodeAPITypes.ErrorResponse = null
 
     try {
-      codeAssistResponse = await CloudCodeAPI.loadCodeAssist(antigravityAccount.auth.access_token);
+      codeAssistResponse = await CloudCodeAPI.loadCodeAssist(antigravityAccount.auth.accessToken);
     } catch (e) {
       codeAssistResponse = e
     }
@@ -49,29 +49,29 @@ export const useAccountAdditionData = create<State & Actions>((setState, getStat
       // 避免冲突 如果是当前账户, 并且 Antigravity 在运行, 则不刷新 access token
       const currentAccount = await AccountCommands.getCurrentAntigravityAccount()
       const isAntigravityRunning = await ProcessCommands.isRunning()
-      if (antigravityAccount.context.email === currentAccount?.context.email && isAntigravityRunning) {
+      if (antigravityAccount.email === currentAccount?.email && isAntigravityRunning) {
         return
       }
       // 刷新 access token
-      const refreshTokenResponse = await CloudCodeAPI.refreshAccessToken(antigravityAccount.auth.id_token);
+      const refreshTokenResponse = await CloudCodeAPI.refreshAccessToken(antigravityAccount.auth.idToken);
       // 更新一下内存里面的 access token, 这里就不写入本地了
-      antigravityAccount.auth.access_token = refreshTokenResponse.access_token;
+      antigravityAccount.auth.accessToken = refreshTokenResponse.access_token;
     }
 
-    codeAssistResponse = await CloudCodeAPI.loadCodeAssist(antigravityAccount.auth.access_token);
+    codeAssistResponse = await CloudCodeAPI.loadCodeAssist(antigravityAccount.auth.accessToken);
 
-    const modelsResponse = await CloudCodeAPI.fetchAvailableModels(antigravityAccount.auth.access_token, codeAssistResponse.cloudaicompanionProject);
-    const userInfoResponse = await CloudCodeAPI.userinfo(antigravityAccount.auth.access_token);
+    const modelsResponse = await CloudCodeAPI.fetchAvailableModels(antigravityAccount.auth.accessToken, codeAssistResponse.cloudaicompanionProject);
+    const userInfoResponse = await CloudCodeAPI.userinfo(antigravityAccount.auth.accessToken);
 
     logger.debug('获取 AccountAdditionData 成功', {
       module: 'use-account-addition-data',
-      email: antigravityAccount.context.email,
+      email: antigravityAccount.email,
     })
 
     setState({
       data: {
         ...getState().data,
-        [antigravityAccount.context.email]: {
+        [antigravityAccount.email]: {
           geminiProQuote: modelsResponse.models["gemini-3-pro-high"].quotaInfo.remainingFraction,
           geminiProQuoteRestIn: modelsResponse.models["gemini-3-pro-high"].quotaInfo.resetTime,
           geminiFlashQuote: modelsResponse.models["gemini-3-flash"].quotaInfo.remainingFraction,
@@ -1,7 +1,7 @@
 import {create} from 'zustand';
 import {logger} from '../lib/logger.ts';
 import {AccountCommands} from '@/commands/AccountCommands.ts';
-import type {AntigravityAccount} from '@/commands/types/account.types.ts';
+import type {AccountDetail, AccountFileIssue, AccountSummary} from '@/commands/types/account.types.ts';
 import {AccountManageCommands} from "@/commands/AccountManageCommands.ts";
 
 // 常量定义
@@ -9,22 +9,24 @@ const FILE_WRITE_DELAY_MS = 500; // 等待文件写入完成的延迟时间
 
 // Store 状态
 export interface AntigravityAccountState {
-  accounts: AntigravityAccount[];
-  currentAuthInfo: AntigravityAccount | null;
+  accounts: AccountSummary[];
+  currentAuthInfo: AccountDetail | null;
+  // 最近一次读取时被隔离的问题文件
+  accountIssues: AccountFileIssue[];
 }
 
 // Store Actions
 export interface AntigravityAccountActions {
   // 基础操作
-  delete: (email: string) => Promise<void>;
+  delete: (accountId: string) => Promise<void>;
   insertOrUpdateCurrentAccount: () => Promise<void>;
-  switchToAccount: (email: string) => Promise<void>;
+  switchToAccount: (accountId: string) => Promise<void>;
 
   // 批量操作
   clearAllAccounts: () => Promise<void>;
 
   // 查询
-  getAccounts: () => Promise<AntigravityAccount[]>;
+  getAccounts: () => Promise<AccountSummary[]>;
 }
 
 // 创建 Store
@@ -32,19 +34,20 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
   // 初始状态
   accounts: [],
   currentAuthInfo: null,
+  accountIssues: [],
 
   // ============ 基础操作 ============
-  delete: async (email: string): Promise<void> => {
+  delete: async (accountId: string): Promise<void> => {
     try {
-      await AccountManageCommands.deleteBackup(email);
+      await AccountManageCommands.deleteBackup(accountId);
 
       // 删除成功后重新获取数据
-      const accounts = await AccountCommands.getAntigravityAccounts();
-      set({ accounts: accounts });
+      const {accounts, issues} = await AccountCommands.getAntigravityAccounts();
+      set({ accounts: accounts, accountIssues: issues });
     } catch (error) {
       logger.error('用户删除失败', {
         module: 'UserManagement',
-        email,
+        accountId,
         error: error instanceof Error ? error.message : String(error)
       });
       throw error;
@@ -57,7 +60,7 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
       const currentInfo = await AccountCommands.getCurrentAntigravityAccount();
       console.log("insertOrUpdateCurrentAccount", currentInfo)
       // 2. 检查是否有有效的用户信息（通过API Key或用户状态判断）
-      if (currentInfo?.auth.access_token) {
+      if (currentInfo?.auth.accessToken) {
         // 3. 执行保存操作
         await AccountCommands.saveAntigravityCurrentAccount();
 
@@ -65,8 +68,8 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
         await new Promise(resolve => setTimeout(resolve, FILE_WRITE_DELAY_MS));
 
         // 5. 重新获取用户列表
-        const accounts = await AccountCommands.getAntigravityAccounts();
-        set({ accounts });
+        const {accounts, issues} = await AccountCommands.getAntigravityAccounts();
+        set({ accounts, accountIssues: issues });
 
         // 6. 更新当前认证信息
         set({currentAuthInfo: currentInfo});
@@ -82,14 +85,14 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
     }
   },
 
-  switchToAccount: async (email: string): Promise<void> => {
+  switchToAccount: async (accountId: string): Promise<void> => {
     try {
       // 调用后端切换用户命令
-      await AccountCommands.switchToAntigravityAccount(email);
+      await AccountCommands.switchToAntigravityAccount(accountId);
     } catch (error) {
       logger.error('切换用户失败', {
         module: 'UserManagement',
-        email,
+        accountId,
         error: error instanceof Error ? error.message : String(error)
       });
       throw error;
@@ -102,18 +105,24 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
     // 调用清空所有备份的命令
     await AccountManageCommands.clearAllBackups();
     // 清空成功后重新获取数据
-    const accounts = await AccountCommands.getAntigravityAccounts();
-    set({ accounts: accounts });
+    const {accounts, issues} = await AccountCommands.getAntigravityAccounts();
+    set({ accounts: accounts, accountIssues: issues });
   },
 
   // ============ 查询 ============
-  getAccounts: async (): Promise<AntigravityAccount[]> => {
+  getAccounts: async (): Promise<AccountSummary[]> => {
     try {
       // 从后端获取账户列表
-      const accounts = await AccountCommands.getAntigravityAccounts();
+      const {accounts, issues} = await AccountCommands.getAntigravityAccounts();
 
       // 同步更新 store 中的状态
-      set({ accounts });
+      set({ accounts, accountIssues: issues });
+      if (issues.length > 0) {
+        logger.warn('部分备份文件无法读取，已移入隔离区', {
+          module: 'UserManagement',
+          issues: issues.map(issue => `${issue.fileName}: ${issue.reason}`)
+        });
+      }
       return accounts;
     } catch (error) {
       logger.error('获取用户列表失败', {
@@ -126,4 +135,4 @@ export const useAntigravityAccount = create<AntigravityAccountState & Antigravit
   },
 }));
 
-export const useCurrentAntigravityAccount: () => AntigravityAccount | undefined = () => useAntigravityAccount(state => state.accounts.find(user => user.context.email === state.currentAuthInfo?.context.email));
+export const useCurrentAntigravityAccount: () => AccountSummary | undefined = () => useAntigravityAccount(state => state.accounts.find(user => user.email === state.currentAuthInfo?.email));
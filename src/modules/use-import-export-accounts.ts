@@ -5,18 +5,10 @@
 
 import {create} from 'zustand';
 import {open, save} from '@tauri-apps/plugin-dialog';
-import {readTextFile} from '@tauri-apps/plugin-fs';
 import {logger} from '@/lib/logger.ts';
 import toast from 'react-hot-toast';
 import {AccountManageCommands} from "@/commands/AccountManageCommands.ts";
 import {BackupData} from "@/commands/types/account-manage.types.ts";
-import {LoggingCommands} from "@/commands/LoggingCommands.ts";
-
-interface EncryptedConfigData {
-  version: string;
-  backupCount: number;
-  backups: BackupData[];
-}
 
 // Store 状态
 interface ConfigState {
@@ -105,25 +97,10 @@ export const useImportExportAccount = create<ConfigState & ConfigActions>()(
         try {
           get().closeImportDialog();
           set({ isImporting: true });
-          toast.loading('正在使用 Rust 解密文件...', {duration: 1});
-
-          // 读取文件并解密
-          const encryptedFile = await readTextFile(pendingImportPath);
-          const decryptedJson: string = await AccountManageCommands.decryptConfig(encryptedFile, password);
-          const configData: EncryptedConfigData = JSON.parse(decryptedJson);
-
-          // 验证配置数据格式
-          if (!configData.version || !configData.backups || !Array.isArray(configData.backups)) {
-            throw new Error('配置文件格式无效');
-          }
-
-          logger.info('开始恢复备份数据', {
-            module: 'useImportExportAccount',
-            backupCount: configData.backups.length
-          });
-          toast.loading('正在恢复账户数据...', {duration: 1});
+          toast.loading('正在导入账户数据...', {duration: 1});
 
-          const result = await AccountManageCommands.restoreBackupFiles(configData.backups);
+          // 后端直接读取导出包，逐条校验后导入
+          const result = await AccountManageCommands.importAccountsBundle(pendingImportPath, password);
 
           if (result.failed.length > 0) {
             logger.warn('部分文件恢复失败', {
@@ -163,27 +140,6 @@ export const useImportExportAccount = create<ConfigState & ConfigActions>()(
         try {
           get().closeExportDialog();
           set({ isExporting: true });
-          toast.loading('正在生成加密配置文件...', {duration: 1});
-
-          // 构建配置数据
-          const configData: EncryptedConfigData = {
-            version: '1.1.0',
-            backupCount: pendingExportData.length,
-            backups: pendingExportData
-          };
-
-          // 调用后端加密
-          const configJson = JSON.stringify(configData, null, 2);
-          const configSize = new Blob([configJson]).size;
-
-          logger.info('配置数据已生成', {
-            module: 'useImportExportAccount',
-            backupCount: pendingExportData.length,
-            configSize
-          });
-
-          const encryptedData = await AccountManageCommands.encryptConfig(configJson, password);
-
           // 选择保存位置
           const timestamp = new Date().toISOString().slice(0, 19).replace(/:/g, '-');
           const defaultFileName = `antigravity_encrypted_config_${timestamp}.enc`;
@@ -207,15 +163,17 @@ export const useImportExportAccount = create<ConfigState & ConfigActions>()(
             return;
           }
 
-          // 保存加密文件
-          await LoggingCommands.writeTextFile(savePath, encryptedData);
+          toast.loading('正在生成加密导出包...', {duration: 1});
+
+          // 后端按所选账户生成带清单的导出包并加密写入
+          const accounts = pendingExportData.map(backup => backup.filename.replace(/\.json$/, ''));
+          const manifest = await AccountManageCommands.exportAccountsBundle(savePath, accounts, password);
 
           toast.success(`配置文件已保存: ${savePath}`);
           logger.info('导出配置成功', {
             module: 'useImportExportAccount',
             savePath,
-            backupCount: pendingExportData.length,
-            configSize
+            backupCount: manifest.accounts.length
           });
 
         } catch (error) {
@@ -12,7 +12,7 @@ import { useImportExportAccount } from '@/modules/use-import-export-accounts.ts'
 import { useSignInNewAntigravityAccount } from '@/hooks/use-sign-in-new-antigravity-account.ts';
 import { PlatformCommands } from '@/commands/PlatformCommands.ts';
 import { TrayCommands } from '@/commands/TrayCommands.ts';
-import type { AntigravityAccount } from '@/commands/types/account.types.ts';
+import type { AccountSummary } from '@/commands/types/account.types.ts';
 import {
   mockAccounts,
   mockAdditionDataMap,
@@ -33,12 +33,12 @@ PlatformCommands.detectInstallation = async () => null as any;
 TrayCommands.updateMenu = async () => '';
 
 const seedMocks = (
-  accounts: AntigravityAccount[],
+  accounts: AccountSummary[],
   additionData: Record<string, AccountAdditionData>
 ) => {
   useAntigravityAccount.setState({
     accounts,
-    currentAuthInfo: accounts[0] ?? null,
+    currentAuthInfo: accounts[0] ? { ...accounts[0], active: true, userStatus: 1, historyCount: 0 } : null,
     getAccounts: async () => accounts,
     delete: async () => {},
     insertOrUpdateCurrentAccount: async () => {},
@@ -1,4 +1,4 @@
-import type { AntigravityAccount } from '@/commands/types/account.types.ts';
+import type { AccountSummary } from '@/commands/types/account.types.ts';
 import type { AccountSessionListAccountItem } from '@/components/business/AccountSessionList.tsx';
 import type {
   AccountAdditionData,
@@ -164,39 +164,31 @@ const baseMockAccounts: BaseMockAccount[] = [
   },
 ];
 
-function makeAccount(base: BaseMockAccount): AntigravityAccount {
+function makeAccount(base: BaseMockAccount): AccountSummary {
   const [local] = base.email.split('@');
+  const expiryTimestamp = Math.floor(Date.now() / 1000) + 60 * 60;
   return {
-    auth: {
-      access_token: base.accessToken ?? `sk_mock_${local}`,
-      id_token: base.idToken ?? `id_mock_${local}`,
-      meta: { expiry_timestamp: Date.now() + 60 * 60 * 1000 },
-      type: 'oauth',
+    id: base.email,
+    email: base.email,
+    planName: base.planName,
+    plan: {
+      slug: base.tier,
+      name: base.tier,
+      description: '',
+      upgradeUrl: '',
+      upgradeMsg: '',
     },
-    context: {
-      email: base.email,
-      models: {
-        items: [],
-        recommended: { names: [], unknown_f2_base64: '' },
-        unknown_f3_base64: '',
-      } as any,
-      plan: {
-        description: '',
-        name: base.tier,
-        slug: base.tier,
-        upgrade_msg: '',
-        upgrade_url: '',
-      } as any,
-      plan_name: base.planName,
-      status: 1,
+    models: { available: [], recommended: [] },
+    auth: {
+      accessToken: base.accessToken ?? `sk_mock_${local}`,
+      idToken: base.idToken ?? `id_mock_${local}`,
+      tokenType: 'oauth',
+      idTokenClaims: null,
     },
-    f11_base64: '',
-    f18_base64: '',
-    f7_base64: '',
-    f9_base64: '',
-    flags_f5_base64: '',
-    history: [],
-    user_id_raw_base64: '',
+    expiry: { status: 'valid', expiryTimestamp, secondsRemaining: 60 * 60 },
+    tokenEmailMismatch: false,
+    active: false,
+    savedAt: new Date().toISOString(),
   };
 }
 
@@ -214,6 +206,7 @@ function makeSessionItem(
   const [local] = base.email.split('@');
   return {
     nickName: base.nickName ?? local,
+    id: base.email,
     email: base.email,
     userAvatar: addition.userAvatar ?? defaultQuotas.userAvatar,
     geminiProQuote: addition.geminiProQuote,
@@ -238,6 +231,7 @@ function buildGridItems(
     const [name, domain] = base.email.split('@');
     return {
       ...base,
+      id: `${name}+${i}@${domain}`,
       email: `${name}+${i}@${domain}`,
       nickName: `${base.nickName} #${i + 1}`,
       apiKey: `${base.apiKey}_${i}`,
@@ -248,6 +242,8 @@ function buildGridItems(
 const longEmailItem: AccountSessionListAccountItem = {
   nickName:
     'ThisIsAnExcessivelyLongNickName_ToTest_TextOverflow_AndLayoutStability_InUserCardHeader',
+  id:
+    'this.is.a.super.long.email.address.with.many.sections.and.tags+storybook-overflow-test@subdomain1.subdomain2.subdomain3.subdomain4.some-very-long-company-domain.example.corp.company.com',
   email:
     'this.is.a.super.long.email.address.with.many.sections.and.tags+storybook-overflow-test@subdomain1.subdomain2.subdomain3.subdomain4.some-very-long-company-domain.example.corp.company.com',
   userAvatar: 'https://api.dicebear.com/9.x/avataaars/svg?seed=LongEmail',